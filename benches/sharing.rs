@@ -0,0 +1,110 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand_chacha::rand_core::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use secret_sharing::ntt::NttScalar;
+use secret_sharing::{
+    gf256, random_element, reconstruct_with, FieldElement, LagrangeCoefficients, Polynomial, Secret,
+};
+
+/// Dealing n shares of a degree t-1 polynomial, by forward differences and by Horner per x
+fn share(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::from_seed([0; 32]);
+    let mut group = c.benchmark_group("share");
+    group.sample_size(10);
+    for (t, n) in [(3, 5), (256, 1_000), (256, 10_000)] {
+        let polynomial = Polynomial::new(t, FieldElement::random(&mut rng), &mut rng).unwrap();
+        let xs: Vec<FieldElement> = (1..=n).map(FieldElement::new).collect();
+        let id = format!("t={} n={}", t, n);
+        group.bench_with_input(BenchmarkId::new("share", &id), &n, |b, n| {
+            b.iter(|| polynomial.share(black_box(*n)))
+        });
+        group.bench_with_input(BenchmarkId::new("evaluate_batch", &id), &xs, |b, xs| {
+            b.iter(|| polynomial.evaluate_batch(black_box(xs)))
+        });
+        group.bench_with_input(BenchmarkId::new("evaluate", &id), &xs, |b, xs| {
+            b.iter(|| {
+                xs.iter()
+                    .map(|x| polynomial.evaluate(black_box(x)))
+                    .collect::<Vec<_>>()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Reconstructing the secret from t shares, from scratch and with precomputed coefficients
+fn reconstruct(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::from_seed([0; 32]);
+    let mut group = c.benchmark_group("reconstruct");
+    for t in [3, 32, 256] {
+        let polynomial = Polynomial::new(t, FieldElement::random(&mut rng), &mut rng).unwrap();
+        let shares = polynomial.share(t);
+        group.bench_with_input(BenchmarkId::new("reconstruct", t), &shares, |b, shares| {
+            b.iter(|| Polynomial::reconstruct(black_box(shares)).unwrap())
+        });
+        let xs: Vec<FieldElement> = shares.iter().map(|s| s.x).collect();
+        let ys: Vec<FieldElement> = shares.iter().map(|s| s.y).collect();
+        let coefficients = LagrangeCoefficients::precompute(&xs).unwrap();
+        group.bench_with_input(BenchmarkId::new("reconstruct_with", t), &ys, |b, ys| {
+            b.iter(|| reconstruct_with(&coefficients, black_box(ys)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Dealing and reconstructing multi-megabyte secrets, compare with `--features parallel`
+fn large_secrets(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::from_seed([0; 32]);
+    let mut group = c.benchmark_group("large_secrets");
+    group.sample_size(10);
+    let mut bytes = vec![0u8; 4 << 20];
+    rng.fill_bytes(&mut bytes);
+    group.bench_function("gf256 split 4MiB", |b| {
+        b.iter(|| gf256::split(black_box(&bytes), 3, 5, &mut rng).unwrap())
+    });
+    let shares = gf256::split(&bytes, 3, 5, &mut rng).unwrap();
+    group.bench_function("gf256 reconstruct 4MiB", |b| {
+        b.iter(|| gf256::reconstruct(black_box(&shares[..3])).unwrap())
+    });
+    let secret = Secret::from_bytes(&bytes[..1 << 20]);
+    group.bench_function("split 1MiB", |b| {
+        b.iter(|| black_box(&secret).split(3, 5, &mut rng).unwrap())
+    });
+    let shares = secret.split(3, 5, &mut rng).unwrap();
+    group.bench_function("reconstruct 1MiB", |b| {
+        b.iter(|| Secret::reconstruct(black_box(&shares)).unwrap())
+    });
+    group.finish();
+}
+
+/// Dealing and interpolating high-degree polynomials of the NTT-friendly field, on the roots of
+/// unity with the NTT and at 1, .., n without
+fn ntt(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::from_seed([0; 32]);
+    let mut group = c.benchmark_group("ntt");
+    group.sample_size(10);
+    for n in [256, 1_024] {
+        let polynomial: Polynomial<NttScalar> =
+            Polynomial::new(n as u64, random_element(&mut rng), &mut rng).unwrap();
+        group.bench_with_input(BenchmarkId::new("share_domain", n), &n, |b, n| {
+            b.iter(|| polynomial.share_domain(black_box(*n)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("share", n), &n, |b, n| {
+            b.iter(|| polynomial.share(black_box(*n as u64)))
+        });
+        let shares = polynomial.share_domain(n).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("from_points domain", n),
+            &shares,
+            |b, s| b.iter(|| Polynomial::from_points(black_box(s)).unwrap()),
+        );
+        let shares = polynomial.share(n as u64);
+        group.bench_with_input(BenchmarkId::new("from_points range", n), &shares, |b, s| {
+            b.iter(|| Polynomial::from_points(black_box(s)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, share, reconstruct, large_secrets, ntt);
+criterion_main!(benches);
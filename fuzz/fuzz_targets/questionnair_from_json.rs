@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use secret_sharing::Questionnair;
+
+fuzz_target!(|json: &str| {
+    if let Ok(questionnair) = Questionnair::from_json(json) {
+        // grading without answers touches every question but runs no KDF
+        let _ = questionnair.grade(&[]);
+        let _ = questionnair.localized("en");
+    }
+});
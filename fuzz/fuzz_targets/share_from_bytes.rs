@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use secret_sharing::Share;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(share) = Share::from_slice(data) {
+        assert_eq!(&share.to_bytes()[..], data);
+    }
+});
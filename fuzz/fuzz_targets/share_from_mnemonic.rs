@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use secret_sharing::Share;
+
+fuzz_target!(|phrase: &str| {
+    if let Ok(share) = Share::from_mnemonic(phrase) {
+        let again = Share::from_mnemonic(&share.to_mnemonic()).expect("written phrases are read");
+        assert_eq!(share.to_bytes(), again.to_bytes());
+    }
+});
@@ -0,0 +1,31 @@
+use crate::error::Error;
+use crate::field::FieldElement;
+use alloc::vec::Vec;
+use ff::Field;
+use rand_core::{CryptoRng, RngCore};
+
+/// n-of-n additive sharing: the shares are random elements summing to the secret, so all n
+/// are needed and any fewer reveal nothing
+pub struct AdditiveSharing;
+
+impl AdditiveSharing {
+    /// Split s into n random elements summing to s
+    pub fn share<R: RngCore + CryptoRng>(
+        s: FieldElement,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<Vec<FieldElement>, Error> {
+        if n == 0 {
+            return Err(Error::InvalidThreshold);
+        }
+        let mut shares: Vec<FieldElement> = (1..n).map(|_| FieldElement::random(rng)).collect();
+        let sum = AdditiveSharing::combine(&shares);
+        shares.push(s - sum);
+        Ok(shares)
+    }
+
+    /// The sum of the shares, which is the secret if all n are given
+    pub fn combine(shares: &[FieldElement]) -> FieldElement {
+        shares.iter().fold(FieldElement::zero(), |acc, s| acc + s)
+    }
+}
@@ -0,0 +1,287 @@
+use crate::error::Error;
+use crate::questionnair::{AnswerReport, GradeReport, Questionnair};
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// When a question may be answered again after failed answers
+/// Times are in seconds of whatever clock the host application passes in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BackoffPolicy {
+    /// Failures allowed without a delay
+    pub free_attempts: u32,
+    /// Delay after the first failure beyond the free ones, doubled with every further failure
+    pub base_delay: u64,
+    /// Upper bound of the delay
+    pub max_delay: u64,
+    /// Failures after which the question is locked until `reset`, if any
+    pub max_failures: Option<u32>,
+}
+
+/// Three free attempts, then 1 minute doubling up to a day, locked after 20 failures
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        BackoffPolicy {
+            free_attempts: 3,
+            base_delay: 60,
+            max_delay: 24 * 60 * 60,
+            max_failures: Some(20),
+        }
+    }
+}
+
+impl BackoffPolicy {
+    /// The delay after the given number of failures
+    pub fn delay(&self, failures: u32) -> u64 {
+        match failures.checked_sub(self.free_attempts.saturating_add(1)) {
+            None => 0,
+            Some(doublings) => self
+                .base_delay
+                .checked_shl(doublings)
+                .filter(|delay| delay >> doublings == self.base_delay)
+                .unwrap_or(u64::MAX)
+                .min(self.max_delay),
+        }
+    }
+}
+
+/// Limits guessing the answers of a questionnair: failed answers to a question delay its next
+/// attempt by the backoff policy, and can lock it
+/// The state has to be persisted by the host application between sessions, e.g. with serde,
+/// and guessing is only limited as far as the host enforces it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AttemptGuard {
+    policy: BackoffPolicy,
+    /// Consecutive failures of each question
+    failures: Vec<u32>,
+    /// Time from which each question may be answered again
+    retry_at: Vec<u64>,
+}
+
+impl AttemptGuard {
+    /// A guard without failures for the questions of the questionnair
+    pub fn new(questionnair: &Questionnair, policy: BackoffPolicy) -> Self {
+        let n = questionnair.questions().len();
+        AttemptGuard {
+            policy,
+            failures: vec![0; n],
+            retry_at: vec![0; n],
+        }
+    }
+
+    /// Fails if question `index` may not be answered at time `now`
+    pub fn check(&self, index: usize, now: u64) -> Result<(), Error> {
+        let (failures, retry_at) = self
+            .failures
+            .get(index)
+            .zip(self.retry_at.get(index))
+            .ok_or(Error::UnknownQuestionIndex { index })?;
+        if self.policy.max_failures.is_some_and(|max| *failures >= max) {
+            return Err(Error::LockedOut { index });
+        }
+        if now < *retry_at {
+            return Err(Error::RateLimited {
+                index,
+                retry_at: *retry_at,
+            });
+        }
+        Ok(())
+    }
+
+    /// Answer the questionnair as `Questionnair::answer_report` at time `now`, if none of the
+    /// answered questions is delayed or locked, and record the outcome
+    pub fn answer_report(
+        &mut self,
+        questionnair: &Questionnair,
+        answers: &[(usize, &str)],
+        now: u64,
+    ) -> Result<AnswerReport, Error> {
+        self.check_answers(questionnair, answers, now)?;
+        let report = questionnair.answer_report(answers)?;
+        self.record(&report, now);
+        Ok(report)
+    }
+
+    /// Grade the answers as `Questionnair::grade` at time `now`, with the limits and recording
+    /// of `answer_report`
+    pub fn grade(
+        &mut self,
+        questionnair: &Questionnair,
+        answers: &[(usize, &str)],
+        now: u64,
+    ) -> Result<GradeReport, Error> {
+        self.check_answers(questionnair, answers, now)?;
+        let grade = questionnair.grade(answers)?;
+        self.record(
+            &AnswerReport {
+                passed: grade.passed.clone(),
+                failed: grade.failed.clone(),
+                unanswered: grade.unanswered.clone(),
+                secret: None,
+            },
+            now,
+        );
+        Ok(grade)
+    }
+
+    /// Fails if the guard is not for the questionnair or an answered question may not be
+    /// answered at time `now`
+    fn check_answers(
+        &self,
+        questionnair: &Questionnair,
+        answers: &[(usize, &str)],
+        now: u64,
+    ) -> Result<(), Error> {
+        let n = questionnair.questions().len();
+        if self.failures.len() != n || self.retry_at.len() != n {
+            return Err(Error::LengthMismatch);
+        }
+        answers
+            .iter()
+            .try_for_each(|(index, _)| self.check(*index, now))
+    }
+
+    /// Record the outcome of answering at time `now`: failed questions are delayed further and
+    /// passed ones start over
+    pub fn record(&mut self, report: &AnswerReport, now: u64) {
+        for index in &report.failed {
+            if let (Some(failures), Some(retry_at)) =
+                (self.failures.get_mut(*index), self.retry_at.get_mut(*index))
+            {
+                *failures = failures.saturating_add(1);
+                *retry_at = now.saturating_add(self.policy.delay(*failures));
+            }
+        }
+        for index in &report.passed {
+            // indices outside the guard were never limited
+            let _ = self.reset(*index);
+        }
+    }
+
+    /// Clear the failures of question `index`, e.g. after the user proved their identity
+    /// another way
+    pub fn reset(&mut self, index: usize) -> Result<(), Error> {
+        match (self.failures.get_mut(index), self.retry_at.get_mut(index)) {
+            (Some(failures), Some(retry_at)) => {
+                *failures = 0;
+                *retry_at = 0;
+                Ok(())
+            }
+            _ => Err(Error::UnknownQuestionIndex { index }),
+        }
+    }
+
+    /// Consecutive failures of question `index`, if the guard has it
+    pub fn failures(&self, index: usize) -> Option<u32> {
+        self.failures.get(index).copied()
+    }
+
+    /// Time from which question `index` may be answered again, if the guard has it
+    pub fn retry_at(&self, index: usize) -> Option<u64> {
+        self.retry_at.get(index).copied()
+    }
+
+    /// The backoff policy
+    pub fn policy(&self) -> &BackoffPolicy {
+        &self.policy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(passed: Vec<usize>, failed: Vec<usize>) -> AnswerReport {
+        AnswerReport {
+            passed,
+            failed,
+            unanswered: vec![],
+            secret: None,
+        }
+    }
+
+    #[test]
+    fn mismatched_lengths_do_not_panic() {
+        let mut guard = AttemptGuard {
+            policy: BackoffPolicy::default(),
+            failures: vec![0; 3],
+            retry_at: vec![0; 1],
+        };
+        assert_eq!(
+            guard.check(2, 0),
+            Err(Error::UnknownQuestionIndex { index: 2 })
+        );
+        guard.record(&report(vec![2], vec![1, 2, 5]), 0);
+        assert_eq!(guard.failures(1), Some(0));
+        assert_eq!(guard.retry_at(1), None);
+        assert_eq!(
+            guard.reset(1),
+            Err(Error::UnknownQuestionIndex { index: 1 })
+        );
+        assert_eq!(guard.reset(0), Ok(()));
+    }
+
+    #[test]
+    fn backoff_saturates() {
+        let policy = BackoffPolicy {
+            free_attempts: u32::MAX,
+            ..BackoffPolicy::default()
+        };
+        assert_eq!(policy.delay(u32::MAX - 1), 0);
+        let policy = BackoffPolicy {
+            free_attempts: 0,
+            base_delay: 60,
+            max_delay: u64::MAX,
+            max_failures: None,
+        };
+        assert_eq!(policy.delay(1), 60);
+        assert_eq!(policy.delay(3), 240);
+        assert_eq!(policy.delay(200), u64::MAX);
+        let mut guard = AttemptGuard {
+            policy,
+            failures: vec![0],
+            retry_at: vec![0],
+        };
+        guard.record(&report(vec![], vec![0]), 100);
+        assert_eq!(
+            guard.check(0, 159),
+            Err(Error::RateLimited {
+                index: 0,
+                retry_at: 160
+            })
+        );
+        assert_eq!(guard.check(0, 160), Ok(()));
+    }
+
+    #[test]
+    fn grade_rejects_guards_of_other_questionnairs() {
+        use crate::field::FieldElement;
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        let questionnair = Questionnair::with_threshold(
+            FieldElement::new(42),
+            2,
+            vec!["city", "river", "pet"],
+            vec!["paris", "seine", "rex"],
+            &mut ChaCha20Rng::from_seed([1; 32]),
+        )
+        .unwrap();
+        let mut stale = AttemptGuard {
+            policy: BackoffPolicy::default(),
+            failures: vec![0; 3],
+            retry_at: vec![0; 2],
+        };
+        let answers = [(2, "tom")];
+        assert_eq!(
+            stale.grade(&questionnair, &answers, 0).unwrap_err(),
+            Error::LengthMismatch
+        );
+        let mut guard = AttemptGuard::new(&questionnair, BackoffPolicy::default());
+        let grade = guard.grade(&questionnair, &answers, 0).unwrap();
+        assert_eq!(grade.failed, vec![2]);
+        assert_eq!(guard.failures(2), Some(1));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::error::Error;
+use crate::field::FieldElement;
+use crate::polynomial::Polynomial;
+use crate::share::{Share, SHARE_BYTES};
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// Length of the public group identifier shared by all shares of a secret
+pub const GROUP_ID_BYTES: usize = 16;
+/// Length of the truncated checksum
+pub const CHECKSUM_BYTES: usize = 8;
+/// Length of the encoding group id || threshold || x || y || checksum
+pub const AUTHENTICATED_SHARE_BYTES: usize = GROUP_ID_BYTES + 8 + SHARE_BYTES + CHECKSUM_BYTES;
+
+/// A share with its metadata and a truncated SHA-256 checksum over both, so corrupted or
+/// truncated shares are rejected instead of reconstructing garbage
+/// The checksum is not keyed: it detects accidents, not forgeries
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AuthenticatedShare {
+    /// Public identifier of the sharing, e.g. random bytes picked by the dealer
+    pub group_id: [u8; GROUP_ID_BYTES],
+    pub threshold: u64,
+    pub share: Share,
+    pub checksum: [u8; CHECKSUM_BYTES],
+}
+
+impl AuthenticatedShare {
+    /// Attach the metadata and its checksum to the share
+    pub fn new(share: Share, group_id: [u8; GROUP_ID_BYTES], threshold: u64) -> Self {
+        let checksum = checksum(&group_id, threshold, &share);
+        AuthenticatedShare {
+            group_id,
+            threshold,
+            share,
+            checksum,
+        }
+    }
+
+    /// Whether the checksum matches the share and metadata
+    pub fn verify(&self) -> bool {
+        let expected = checksum(&self.group_id, self.threshold, &self.share);
+        expected.ct_eq(&self.checksum).into()
+    }
+
+    /// The encoding group id || threshold || x || y || checksum
+    pub fn to_bytes(&self) -> [u8; AUTHENTICATED_SHARE_BYTES] {
+        let mut bytes = [0u8; AUTHENTICATED_SHARE_BYTES];
+        let (group_id, rest) = bytes.split_at_mut(GROUP_ID_BYTES);
+        let (threshold, rest) = rest.split_at_mut(8);
+        let (share, checksum) = rest.split_at_mut(SHARE_BYTES);
+        group_id.copy_from_slice(&self.group_id);
+        threshold.copy_from_slice(&self.threshold.to_le_bytes());
+        share.copy_from_slice(&self.share.to_bytes());
+        checksum.copy_from_slice(&self.checksum);
+        bytes
+    }
+
+    /// Read the encoding and verify the checksum, rejecting truncated input
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != AUTHENTICATED_SHARE_BYTES {
+            return Err(Error::InvalidEncoding);
+        }
+        let (group_id, rest) = bytes.split_at(GROUP_ID_BYTES);
+        let (threshold, rest) = rest.split_at(8);
+        let (share, checksum) = rest.split_at(SHARE_BYTES);
+        let mut share_bytes = [0u8; SHARE_BYTES];
+        share_bytes.copy_from_slice(share);
+        let mut threshold_bytes = [0u8; 8];
+        threshold_bytes.copy_from_slice(threshold);
+        let mut authenticated = AuthenticatedShare {
+            group_id: [0u8; GROUP_ID_BYTES],
+            threshold: u64::from_le_bytes(threshold_bytes),
+            share: Share::from_bytes(share_bytes).map_err(|_| Error::CorruptShare { index: 0 })?,
+            checksum: [0u8; CHECKSUM_BYTES],
+        };
+        authenticated.group_id.copy_from_slice(group_id);
+        authenticated.checksum.copy_from_slice(checksum);
+        if !authenticated.verify() {
+            return Err(Error::CorruptShare { index: 0 });
+        }
+        Ok(authenticated)
+    }
+}
+
+/// Reconstruct the secret after checking every share's checksum and that all belong to the same
+/// sharing, with at least its threshold of shares
+pub fn reconstruct_authenticated(shares: &[AuthenticatedShare]) -> Result<FieldElement, Error> {
+    let first = shares
+        .first()
+        .ok_or(Error::InsufficientShares { needed: 1, got: 0 })?;
+    for (index, share) in shares.iter().enumerate() {
+        if !share.verify() {
+            return Err(Error::CorruptShare { index });
+        }
+        if share.group_id != first.group_id || share.threshold != first.threshold {
+            return Err(Error::GroupMismatch { index });
+        }
+    }
+    if (shares.len() as u64) < first.threshold {
+        return Err(Error::InsufficientShares {
+            needed: first.threshold as usize,
+            got: shares.len(),
+        });
+    }
+    let shares: Vec<Share> = shares.iter().map(|s| s.share.clone()).collect();
+    Polynomial::reconstruct(&shares)
+}
+
+/// Truncated H(domain || group id || threshold || x || y)
+fn checksum(group_id: &[u8], threshold: u64, share: &Share) -> [u8; CHECKSUM_BYTES] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"polynomials share checksum");
+    hasher.update(group_id);
+    hasher.update(threshold.to_le_bytes());
+    hasher.update(share.to_bytes());
+    let hash = hasher.finalize();
+    let mut checksum = [0u8; CHECKSUM_BYTES];
+    checksum.copy_from_slice(&hash[..CHECKSUM_BYTES]);
+    checksum
+}
@@ -0,0 +1,69 @@
+//! Beaver triples from a trusted dealer, to multiply shared values. Holders of [x], [y] and a
+//! triple ([a], [b], [ab]) open d = x - a and e = y - b, which reveal nothing as a and b are
+//! uniform, and then [xy] = [ab] + d [b] + e [a] + d e without further interaction.
+
+use crate::error::Error;
+use crate::field::{random_element, FieldElement};
+use crate::polynomial::Polynomial;
+use crate::share::Share;
+use alloc::vec::Vec;
+use ff::PrimeField;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// One holder's shares of a triple a, b, c = ab, all at the same x
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BeaverTriple<F: PrimeField + Zeroize = FieldElement> {
+    pub a: Share<F>,
+    pub b: Share<F>,
+    pub c: Share<F>,
+}
+
+/// Trusted dealer of Beaver triples
+pub struct TripleDealer;
+
+impl TripleDealer {
+    /// Deal a random triple to n holders at x = 1..=n, t of which reconstruct each of a, b, c
+    pub fn deal<F: PrimeField + Zeroize, R: RngCore + CryptoRng>(
+        t: u64,
+        n: u64,
+        rng: &mut R,
+    ) -> Result<Vec<BeaverTriple<F>>, Error> {
+        if t > n {
+            return Err(Error::InvalidThreshold);
+        }
+        let mut a: F = random_element(rng);
+        let mut b: F = random_element(rng);
+        let mut c = a * b;
+        let shares_a = Polynomial::new(t, a, rng)?.share(n);
+        let shares_b = Polynomial::new(t, b, rng)?.share(n);
+        let shares_c = Polynomial::new(t, c, rng)?.share(n);
+        a.zeroize();
+        b.zeroize();
+        c.zeroize();
+        Ok(shares_a
+            .into_iter()
+            .zip(shares_b)
+            .zip(shares_c)
+            .map(|((a, b), c)| BeaverTriple { a, b, c })
+            .collect())
+    }
+}
+
+impl<F: PrimeField + Zeroize> BeaverTriple<F> {
+    /// The shares of d = x - a and e = y - b this holder publishes, to be reconstructed by all
+    pub fn mask(&self, x: &Share<F>, y: &Share<F>) -> Result<(Share<F>, Share<F>), Error> {
+        Ok(((x - &self.a)?, (y - &self.b)?))
+    }
+}
+
+/// This holder's share of xy from its triple and the opened d = x - a and e = y - b, the triple
+/// must not be used again
+pub fn mul_open<F: PrimeField + Zeroize>(triple: BeaverTriple<F>, d: F, e: F) -> Share<F> {
+    let mut product = triple.c.clone();
+    product.y += d * triple.b.y + e * triple.a.y + d * e;
+    product
+}
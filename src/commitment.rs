@@ -0,0 +1,95 @@
+#[cfg(feature = "vss")]
+use crate::curves::Ed25519Scalar;
+use crate::field::FieldElement;
+use crate::polynomial::Dealer;
+#[cfg(feature = "vss")]
+use crate::vss::GroupElement;
+use ff::Field;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+/// A commitment to the secret, published when dealing so whoever reconstructs can tell the
+/// secret from the garbage that wrong or too few shares interpolate
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SecretCommitment {
+    /// SHA-256(domain || salt || s)
+    Hash { salt: [u8; 32], digest: [u8; 32] },
+    /// g^s h^r in ristretto255 with its opening r, s as a scalar below p
+    /// Hides s only computationally once r is published along, as the hash does
+    #[cfg(feature = "vss")]
+    Pedersen {
+        commitment: GroupElement,
+        blinding: Ed25519Scalar,
+    },
+}
+
+impl SecretCommitment {
+    /// Commit to s with a salted hash
+    pub fn hash<R: RngCore + CryptoRng>(s: &FieldElement, rng: &mut R) -> Self {
+        let mut salt = [0u8; 32];
+        rng.fill_bytes(&mut salt);
+        SecretCommitment::Hash {
+            salt,
+            digest: digest(&salt, s),
+        }
+    }
+
+    /// Commit to s as g^s h^r for a random r
+    #[cfg(feature = "vss")]
+    pub fn pedersen<R: RngCore + CryptoRng>(s: &FieldElement, rng: &mut R) -> Self {
+        let blinding = Ed25519Scalar::random(rng);
+        SecretCommitment::Pedersen {
+            commitment: pedersen(s, &blinding),
+            blinding,
+        }
+    }
+
+    /// Checks a reconstructed secret against the commitment
+    pub fn verify(&self, s: &FieldElement) -> bool {
+        match self {
+            SecretCommitment::Hash { salt, digest: d } => digest(salt, s).ct_eq(d).into(),
+            #[cfg(feature = "vss")]
+            SecretCommitment::Pedersen {
+                commitment,
+                blinding,
+            } => pedersen(s, blinding) == *commitment,
+        }
+    }
+}
+
+impl Dealer {
+    /// A salted hash commitment to the dealt secret f(0)
+    pub fn commit<R: RngCore + CryptoRng>(&self, rng: &mut R) -> SecretCommitment {
+        SecretCommitment::hash(&self.secret(), rng)
+    }
+
+    /// A Pedersen commitment to the dealt secret f(0)
+    #[cfg(feature = "vss")]
+    pub fn commit_pedersen<R: RngCore + CryptoRng>(&self, rng: &mut R) -> SecretCommitment {
+        SecretCommitment::pedersen(&self.secret(), rng)
+    }
+
+    fn secret(&self) -> Zeroizing<FieldElement> {
+        Zeroizing::new(self.polynomial.evaluate(&FieldElement::zero()))
+    }
+}
+
+/// g^s h^r with s embedded in the scalars of ristretto255
+#[cfg(feature = "vss")]
+fn pedersen(s: &FieldElement, blinding: &Ed25519Scalar) -> GroupElement {
+    let s = Zeroizing::new(Ed25519Scalar::from(*s));
+    GroupElement::generator().pow(&s) * GroupElement::blinding_generator().pow(blinding)
+}
+
+fn digest(salt: &[u8; 32], s: &FieldElement) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"polynomials secret commitment");
+    hasher.update(salt);
+    hasher.update(Zeroizing::new(s.to_bytes()));
+    hasher.finalize().into()
+}
@@ -0,0 +1,360 @@
+//! Scalar fields of elliptic curves, so shares of a curve secret key are directly key shares.
+//!
+//! `Polynomial`, `Share` and the reconstruction functions work over any `ff::PrimeField`, and
+//! these are the scalar fields of ristretto255 / Ed25519 and of secp256k1. The curve crates
+//! implement a newer, incompatible `ff`, so shares are exchanged with them as the canonical
+//! 32-byte scalar encodings.
+
+/// Length of the canonical encoding of a curve scalar
+pub const SCALAR_BYTES: usize = 32;
+
+#[cfg(feature = "ed25519")]
+pub use ed25519::{Ed25519Scalar, Ed25519ScalarRepr};
+#[cfg(feature = "secp256k1")]
+pub use secp256k1::{Secp256k1Scalar, Secp256k1ScalarRepr};
+
+#[cfg(feature = "ed25519")]
+mod ed25519 {
+    use super::SCALAR_BYTES;
+    use crate::error::Error;
+    use crate::field::FieldElement;
+    use bitvec::{array::BitArray, order::Lsb0};
+    use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+    use curve25519_dalek::scalar::Scalar;
+    use ff::{Field, PrimeField};
+    use rand_core::RngCore;
+    use sha2::{Digest, Sha512};
+    use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+    use zeroize::Zeroize;
+
+    /// l little-endian
+    const MODULUS: [u8; SCALAR_BYTES] = [
+        237, 211, 245, 92, 26, 99, 18, 88, 214, 156, 247, 162, 222, 249, 222, 20, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 16,
+    ];
+    /// (l - 5) / 8, for square roots as l = 5 (mod 8)
+    const SQRT_EXPONENT: [u64; 4] = [
+        0xcb02_4c63_4b9e_ba7d,
+        0x029b_df3b_d45e_f39a,
+        0,
+        0x0200_0000_0000_0000,
+    ];
+    /// (l - 1) / 4, so 2^((l - 1) / 4) is a primitive 4th root of unity
+    const ROOT_EXPONENT: [u64; 4] = [
+        0x9604_98c6_973d_74fb,
+        0x0537_be77_a8bd_e735,
+        0,
+        0x0400_0000_0000_0000,
+    ];
+
+    /// The scalar field of ristretto255 and Ed25519, of prime order
+    /// l = 2^252 + 27742317777372353535851937790883648493
+    ///
+    /// `ff_derive` has no square root for l = 5 (mod 8), so this wraps curve25519-dalek's
+    /// `Scalar`, which it converts to and from, and the encoding is the same 32-byte
+    /// little-endian one
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Ed25519Scalar(Scalar);
+
+    /// The `ff` repr of an `Ed25519Scalar`, its canonical encoding
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Ed25519ScalarRepr(pub [u8; SCALAR_BYTES]);
+
+    impl Ed25519Scalar {
+        /// The canonical 32-byte little-endian encoding
+        pub fn to_bytes(self) -> [u8; SCALAR_BYTES] {
+            self.0.to_bytes()
+        }
+
+        /// Read the canonical 32-byte little-endian encoding, rejecting values not below l
+        pub fn from_bytes(bytes: [u8; SCALAR_BYTES]) -> Result<Self, Error> {
+            Option::from(Scalar::from_canonical_bytes(bytes))
+                .map(Ed25519Scalar)
+                .ok_or(Error::InvalidEncoding)
+        }
+
+        /// Hash bytes to a scalar by reducing SHA-512, which is indistinguishable from uniform
+        pub fn hash_bytes(x: &[u8]) -> Self {
+            let wide: [u8; 2 * SCALAR_BYTES] = Sha512::digest(x).into();
+            Ed25519Scalar(Scalar::from_bytes_mod_order_wide(&wide))
+        }
+    }
+
+    impl From<Scalar> for Ed25519Scalar {
+        fn from(scalar: Scalar) -> Self {
+            Ed25519Scalar(scalar)
+        }
+    }
+
+    impl From<Ed25519Scalar> for Scalar {
+        fn from(scalar: Ed25519Scalar) -> Self {
+            scalar.0
+        }
+    }
+
+    /// The same integer, as p < l
+    impl From<FieldElement> for Ed25519Scalar {
+        fn from(e: FieldElement) -> Self {
+            let mut bytes = [0u8; SCALAR_BYTES];
+            bytes[..e.to_bytes().len()].copy_from_slice(&e.to_bytes());
+            let scalar = Ed25519Scalar(Scalar::from_bytes_mod_order(bytes));
+            bytes.zeroize();
+            scalar
+        }
+    }
+
+    impl From<u64> for Ed25519Scalar {
+        fn from(v: u64) -> Self {
+            Ed25519Scalar(Scalar::from(v))
+        }
+    }
+
+    /// Ed25519Scalar is Copy and so cannot zeroize on drop, containers of secret scalars do
+    impl Zeroize for Ed25519Scalar {
+        fn zeroize(&mut self) {
+            self.0.zeroize();
+        }
+    }
+
+    /// Serialized as the canonical 32-byte encoding, as a hex string for human-readable formats
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for Ed25519Scalar {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let bytes = self.to_bytes();
+            if serializer.is_human_readable() {
+                let hex: alloc::string::String =
+                    bytes.iter().map(|b| alloc::format!("{:02x}", b)).collect();
+                serializer.serialize_str(&hex)
+            } else {
+                serde::Serialize::serialize(&bytes, serializer)
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for Ed25519Scalar {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            use serde::de::Error as _;
+            let mut bytes = [0u8; SCALAR_BYTES];
+            if deserializer.is_human_readable() {
+                let hex: alloc::string::String = serde::Deserialize::deserialize(deserializer)?;
+                if hex.len() != 2 * SCALAR_BYTES || !hex.is_ascii() {
+                    return Err(D::Error::invalid_length(hex.len(), &"64 hex digits"));
+                }
+                for (i, b) in bytes.iter_mut().enumerate() {
+                    *b =
+                        u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(D::Error::custom)?;
+                }
+            } else {
+                bytes = serde::Deserialize::deserialize(deserializer)?;
+            }
+            Ed25519Scalar::from_bytes(bytes).map_err(D::Error::custom)
+        }
+    }
+
+    impl ConditionallySelectable for Ed25519Scalar {
+        fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+            Ed25519Scalar(Scalar::conditional_select(&a.0, &b.0, choice))
+        }
+    }
+
+    impl ConstantTimeEq for Ed25519Scalar {
+        fn ct_eq(&self, other: &Self) -> Choice {
+            self.0.ct_eq(&other.0)
+        }
+    }
+
+    impl Neg for Ed25519Scalar {
+        type Output = Self;
+
+        fn neg(self) -> Self {
+            Ed25519Scalar(-self.0)
+        }
+    }
+
+    macro_rules! impl_op {
+        ($op:ident, $fn:ident, $assign:ident, $assign_fn:ident) => {
+            impl $op for Ed25519Scalar {
+                type Output = Self;
+
+                fn $fn(self, other: Self) -> Self {
+                    Ed25519Scalar($op::$fn(self.0, other.0))
+                }
+            }
+
+            impl<'a> $op<&'a Ed25519Scalar> for Ed25519Scalar {
+                type Output = Self;
+
+                fn $fn(self, other: &'a Self) -> Self {
+                    Ed25519Scalar($op::$fn(self.0, other.0))
+                }
+            }
+
+            impl $assign for Ed25519Scalar {
+                fn $assign_fn(&mut self, other: Self) {
+                    self.0 = $op::$fn(self.0, other.0);
+                }
+            }
+
+            impl<'a> $assign<&'a Ed25519Scalar> for Ed25519Scalar {
+                fn $assign_fn(&mut self, other: &'a Self) {
+                    self.0 = $op::$fn(self.0, other.0);
+                }
+            }
+        };
+    }
+
+    impl_op!(Add, add, AddAssign, add_assign);
+    impl_op!(Sub, sub, SubAssign, sub_assign);
+    impl_op!(Mul, mul, MulAssign, mul_assign);
+
+    impl Field for Ed25519Scalar {
+        fn random(mut rng: impl RngCore) -> Self {
+            // reducing 512 bits is indistinguishable from uniform
+            let mut wide = [0u8; 2 * SCALAR_BYTES];
+            rng.fill_bytes(&mut wide);
+            let scalar = Ed25519Scalar(Scalar::from_bytes_mod_order_wide(&wide));
+            wide.zeroize();
+            scalar
+        }
+
+        fn zero() -> Self {
+            Ed25519Scalar(Scalar::ZERO)
+        }
+
+        fn one() -> Self {
+            Ed25519Scalar(Scalar::ONE)
+        }
+
+        fn is_zero(&self) -> bool {
+            self.0 == Scalar::ZERO
+        }
+
+        fn square(&self) -> Self {
+            Ed25519Scalar(self.0 * self.0)
+        }
+
+        fn double(&self) -> Self {
+            Ed25519Scalar(self.0 + self.0)
+        }
+
+        fn invert(&self) -> CtOption<Self> {
+            CtOption::new(Ed25519Scalar(self.0.invert()), !self.0.ct_eq(&Scalar::ZERO))
+        }
+
+        /// Atkin's square root for l = 5 (mod 8)
+        fn sqrt(&self) -> CtOption<Self> {
+            let v = self.double().pow_vartime(SQRT_EXPONENT);
+            let i = self.double() * v.square();
+            let root = *self * v * (i - Self::one());
+            CtOption::new(root, root.square().ct_eq(self))
+        }
+    }
+
+    impl From<Ed25519Scalar> for Ed25519ScalarRepr {
+        fn from(scalar: Ed25519Scalar) -> Self {
+            Ed25519ScalarRepr(scalar.to_bytes())
+        }
+    }
+
+    impl<'a> From<&'a Ed25519Scalar> for Ed25519ScalarRepr {
+        fn from(scalar: &'a Ed25519Scalar) -> Self {
+            Ed25519ScalarRepr(scalar.to_bytes())
+        }
+    }
+
+    impl AsRef<[u8]> for Ed25519ScalarRepr {
+        fn as_ref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    impl AsMut<[u8]> for Ed25519ScalarRepr {
+        fn as_mut(&mut self) -> &mut [u8] {
+            &mut self.0
+        }
+    }
+
+    impl PrimeField for Ed25519Scalar {
+        type Repr = Ed25519ScalarRepr;
+        type ReprBits = [u8; SCALAR_BYTES];
+
+        const NUM_BITS: u32 = 253;
+        const CAPACITY: u32 = 252;
+        const S: u32 = 2;
+
+        fn from_repr(repr: Self::Repr) -> Option<Self> {
+            Ed25519Scalar::from_bytes(repr.0).ok()
+        }
+
+        fn to_repr(&self) -> Self::Repr {
+            self.into()
+        }
+
+        fn to_le_bits(&self) -> BitArray<Lsb0, Self::ReprBits> {
+            BitArray::new(self.to_bytes())
+        }
+
+        fn is_odd(&self) -> bool {
+            self.to_bytes()[0] & 1 == 1
+        }
+
+        fn char_le_bits() -> BitArray<Lsb0, Self::ReprBits> {
+            BitArray::new(MODULUS)
+        }
+
+        fn multiplicative_generator() -> Self {
+            Ed25519Scalar::from(2)
+        }
+
+        fn root_of_unity() -> Self {
+            Ed25519Scalar::multiplicative_generator().pow_vartime(ROOT_EXPONENT)
+        }
+    }
+}
+
+// the Montgomery reduction ff_derive generates takes 2 * 5 limbs
+#[cfg(feature = "secp256k1")]
+#[allow(clippy::too_many_arguments)]
+mod secp256k1 {
+    use super::SCALAR_BYTES;
+    use crate::error::Error;
+    use ff::PrimeField;
+    use zeroize::Zeroize;
+
+    /// The scalar field of secp256k1, of prime order
+    /// n = 2^256 - 432420386565659656852420866394968145599
+    ///
+    /// The encoding is the 32-byte big-endian one of SEC1 secret keys. As with `FieldElement`, the
+    /// Montgomery arithmetic of `ff_derive` needs an extra limb, so sample with `random_element`
+    #[derive(PrimeField)]
+    #[PrimeFieldModulus = "115792089237316195423570985008687907852837564279074904382605163141518161494337"]
+    #[PrimeFieldGenerator = "7"]
+    #[PrimeFieldReprEndianness = "big"]
+    pub struct Secp256k1Scalar([u64; 5]);
+
+    impl Secp256k1Scalar {
+        /// The canonical 32-byte big-endian encoding
+        pub fn to_bytes(self) -> [u8; SCALAR_BYTES] {
+            let repr = self.to_repr().0;
+            let mut bytes = [0u8; SCALAR_BYTES];
+            bytes.copy_from_slice(&repr[repr.len() - SCALAR_BYTES..]);
+            bytes
+        }
+
+        /// Read the canonical 32-byte big-endian encoding, rejecting values not below n
+        pub fn from_bytes(bytes: [u8; SCALAR_BYTES]) -> Result<Self, Error> {
+            let mut repr = Secp256k1ScalarRepr::default();
+            let len = repr.0.len();
+            repr.0[len - SCALAR_BYTES..].copy_from_slice(&bytes);
+            Secp256k1Scalar::from_repr(repr).ok_or(Error::InvalidEncoding)
+        }
+    }
+
+    /// Secp256k1Scalar is Copy and so cannot zeroize on drop, containers of secret scalars do
+    impl Zeroize for Secp256k1Scalar {
+        fn zeroize(&mut self) {
+            self.0.zeroize();
+        }
+    }
+}
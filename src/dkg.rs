@@ -0,0 +1,395 @@
+//! Joint-Feldman distributed key generation: every party deals a random secret with Feldman
+//! commitments, and each party's share of the joint secret is the sum of the shares it was dealt
+//! by the qualified dealers. No party learns the joint secret, only g^s is public.
+//!
+//! All messages are broadcast. Shares are masked with a pad derived from the Diffie-Hellman key
+//! of dealer and recipient in ristretto255, so they can travel over public channels.
+//! A recipient complains about a share failing its commitments, and the dealer has to reveal
+//! that share in the clear, which all parties check. Dealers that fail are disqualified.
+//!
+//! As with every Joint-Feldman DKG a rushing adversary can bias the distribution of g^s, which
+//! is fine for threshold Schnorr or ElGamal keys but not for uniformly random secrets.
+
+use crate::curves::Ed25519Scalar;
+use crate::error::Error;
+use crate::polynomial::Polynomial;
+use crate::share::Share;
+use crate::vss::{Commitments, GroupElement};
+use ff::Field;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Messages between the parties, identified by their index 1..=n which is their x-coordinate
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DkgMessage {
+    /// The dealer's commitments and masked shares, the share of party j at position j - 1
+    Deal {
+        from: usize,
+        commitments: Commitments,
+        shares: Vec<Share<Ed25519Scalar>>,
+    },
+    /// The share `from` was dealt by `against` fails the commitments
+    Complaint { from: usize, against: usize },
+    /// The dealer's answer to a complaint, the unmasked share of `to`
+    Reveal {
+        from: usize,
+        to: usize,
+        share: Share<Ed25519Scalar>,
+    },
+}
+
+/// The result for a party of a finished DKG
+#[derive(Debug, Clone)]
+pub struct DkgOutput {
+    /// The party's share of the joint secret
+    pub share: Share<Ed25519Scalar>,
+    /// g^s for the joint secret s
+    pub public_key: GroupElement,
+    /// Commitments to the joint polynomial, against which every party's share verifies
+    pub commitments: Commitments,
+    /// The dealers whose secrets make up the joint secret
+    pub qualified: Vec<usize>,
+}
+
+/// One party of the DKG, driven by broadcasting the messages it returns and feeding it every
+/// message of the other parties
+/// Messages can arrive in any order: a reveal arriving before the dealer's deal is kept until
+/// the deal arrives
+pub struct DkgParty {
+    index: usize,
+    t: usize,
+    secret_key: Ed25519Scalar,
+    public_keys: Vec<GroupElement>,
+    polynomial: Polynomial<Ed25519Scalar>,
+    deals: BTreeMap<usize, (Commitments, Share<Ed25519Scalar>)>,
+    complaints: BTreeSet<(usize, usize)>,
+    answered: BTreeSet<(usize, usize)>,
+    disqualified: BTreeSet<usize>,
+    /// Reveals by (dealer, recipient) whose deal has not arrived yet
+    pending: BTreeMap<(usize, usize), Share<Ed25519Scalar>>,
+}
+
+impl DkgParty {
+    /// Create party `index` of n = public_keys.len() with threshold t, returning the deal to
+    /// broadcast. Party j's key pair is (sk_j, g^sk_j) and the public keys are in party order
+    pub fn new<R: RngCore + CryptoRng>(
+        index: usize,
+        t: u64,
+        secret_key: Ed25519Scalar,
+        public_keys: Vec<GroupElement>,
+        rng: &mut R,
+    ) -> Result<(Self, DkgMessage), Error> {
+        let n = public_keys.len();
+        if index == 0 || index > n {
+            return Err(Error::UnknownParty { index });
+        }
+        if t as usize > n {
+            return Err(Error::InvalidThreshold);
+        }
+        let polynomial = Polynomial::new(t, Ed25519Scalar::random(&mut *rng), rng)?;
+        let party = DkgParty {
+            index,
+            t: t as usize,
+            secret_key,
+            public_keys,
+            polynomial,
+            deals: BTreeMap::new(),
+            complaints: BTreeSet::new(),
+            answered: BTreeSet::new(),
+            disqualified: BTreeSet::new(),
+            pending: BTreeMap::new(),
+        };
+        let shares = (1..=n)
+            .map(|j| party.dealt_share(j).mask(party.pad(index, j)))
+            .collect();
+        let deal = DkgMessage::Deal {
+            from: index,
+            commitments: Commitments::new(&party.polynomial),
+            shares,
+        };
+        Ok((party, deal))
+    }
+
+    /// Process a message of any party, including this one, returning the message to broadcast
+    /// in response, if any
+    pub fn handle_message(&mut self, message: DkgMessage) -> Result<Option<DkgMessage>, Error> {
+        match message {
+            DkgMessage::Deal {
+                from,
+                commitments,
+                shares,
+            } => {
+                self.check_party(from)?;
+                if self.deals.contains_key(&from) || shares.len() != self.public_keys.len() {
+                    return Err(Error::UnexpectedMessage);
+                }
+                let share = shares[self.index - 1].unmask(self.pad(from, self.index));
+                let wrong_threshold = commitments.threshold() != self.t;
+                self.deals.insert(from, (commitments, share));
+                if wrong_threshold {
+                    // everyone sees the commitments, so there is nothing to complain about
+                    self.disqualified.insert(from);
+                }
+                let early: Vec<(usize, Share<Ed25519Scalar>)> = self
+                    .pending
+                    .iter()
+                    .filter(|((dealer, _), _)| *dealer == from)
+                    .map(|((_, to), share)| (*to, share.clone()))
+                    .collect();
+                for (to, share) in early {
+                    self.pending.remove(&(from, to));
+                    self.reveal(from, to, share);
+                }
+                let (commitments, share) = &self.deals[&from];
+                if wrong_threshold || commitments.verify(share) {
+                    Ok(None)
+                } else {
+                    Ok(Some(DkgMessage::Complaint {
+                        from: self.index,
+                        against: from,
+                    }))
+                }
+            }
+            DkgMessage::Complaint { from, against } => {
+                self.check_party(from)?;
+                self.check_party(against)?;
+                self.complaints.insert((from, against));
+                if against == self.index {
+                    Ok(Some(DkgMessage::Reveal {
+                        from: self.index,
+                        to: from,
+                        share: self.dealt_share(from),
+                    }))
+                } else {
+                    Ok(None)
+                }
+            }
+            DkgMessage::Reveal { from, to, share } => {
+                self.check_party(from)?;
+                self.check_party(to)?;
+                if self.deals.contains_key(&from) {
+                    self.reveal(from, to, share);
+                } else {
+                    self.pending.insert((from, to), share);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Check the share dealer `from` revealed for `to` against its commitments
+    fn reveal(&mut self, from: usize, to: usize, share: Share<Ed25519Scalar>) {
+        let (commitments, dealt) = self.deals.get_mut(&from).expect("deal received");
+        if share.x != Ed25519Scalar::from(to as u64) || !commitments.verify(&share) {
+            self.disqualified.insert(from);
+        } else {
+            if to == self.index {
+                *dealt = share;
+            }
+            self.answered.insert((to, from));
+        }
+    }
+
+    /// Combine the shares of the qualified dealers, once all deals, complaints and reveals are
+    /// handled. Dealers without a deal or with an unanswered complaint are disqualified, and
+    /// reveals whose deal never arrived are ignored
+    pub fn finish(&self) -> Result<DkgOutput, Error> {
+        let qualified: Vec<usize> = (1..=self.public_keys.len())
+            .filter(|j| self.deals.contains_key(j) && !self.disqualified.contains(j))
+            .filter(|j| {
+                self.complaints
+                    .iter()
+                    .all(|(from, against)| against != j || self.answered.contains(&(*from, *j)))
+            })
+            .collect();
+        if qualified.len() < self.t {
+            return Err(Error::InsufficientShares {
+                needed: self.t,
+                got: qualified.len(),
+            });
+        }
+        let mut y = Ed25519Scalar::zero();
+        let mut commitments: Option<Commitments> = None;
+        for j in &qualified {
+            let (c, share) = &self.deals[j];
+            y += share.y;
+            commitments = Some(match commitments {
+                Some(acc) => acc.combine(c)?,
+                None => c.clone(),
+            });
+        }
+        let commitments = commitments.expect("at least t qualified dealers");
+        Ok(DkgOutput {
+            share: Share {
+                x: Ed25519Scalar::from(self.index as u64),
+                y,
+            },
+            public_key: commitments.secret().clone(),
+            commitments,
+            qualified,
+        })
+    }
+
+    fn dealt_share(&self, j: usize) -> Share<Ed25519Scalar> {
+        let x = Ed25519Scalar::from(j as u64);
+        Share {
+            x,
+            y: self.polynomial.evaluate(&x),
+        }
+    }
+
+    /// H(domain || g^{sk_dealer sk_recipient} || dealer || recipient), known to both
+    fn pad(&self, dealer: usize, recipient: usize) -> Ed25519Scalar {
+        let other = if dealer == self.index {
+            recipient
+        } else {
+            dealer
+        };
+        let shared = self.public_keys[other - 1].pow(&self.secret_key);
+        let mut input = Zeroizing::new(b"polynomials dkg pad".to_vec());
+        input.extend_from_slice(&shared.to_bytes());
+        input.extend_from_slice(&(dealer as u64).to_le_bytes());
+        input.extend_from_slice(&(recipient as u64).to_le_bytes());
+        Ed25519Scalar::hash_bytes(&input)
+    }
+
+    fn check_party(&self, index: usize) -> Result<(), Error> {
+        if index == 0 || index > self.public_keys.len() {
+            return Err(Error::UnknownParty { index });
+        }
+        Ok(())
+    }
+}
+
+impl Drop for DkgParty {
+    fn drop(&mut self) {
+        self.secret_key.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    /// n parties of threshold 2 and their deals
+    fn parties(n: usize) -> (Vec<DkgParty>, Vec<DkgMessage>) {
+        let mut rng = ChaCha20Rng::from_seed([10; 32]);
+        let secret_keys: Vec<Ed25519Scalar> =
+            (0..n).map(|_| Ed25519Scalar::random(&mut rng)).collect();
+        let public_keys: Vec<GroupElement> = secret_keys
+            .iter()
+            .map(|sk| GroupElement::generator().pow(sk))
+            .collect();
+        secret_keys
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| DkgParty::new(i + 1, 2, *sk, public_keys.clone(), &mut rng).unwrap())
+            .unzip()
+    }
+
+    /// Party 1's deal with the share of party 2 off the commitments, and the complaint of
+    /// party 2 about it
+    fn cheating_deal(parties: &mut [DkgParty], deals: &mut [DkgMessage]) -> DkgMessage {
+        if let DkgMessage::Deal { shares, .. } = &mut deals[0] {
+            shares[1].y += Ed25519Scalar::one();
+        }
+        parties[1]
+            .handle_message(deals[0].clone())
+            .unwrap()
+            .expect("party 2 complains")
+    }
+
+    /// Deliver the message to every party, collecting their responses
+    fn broadcast(parties: &mut [DkgParty], message: &DkgMessage) -> Vec<DkgMessage> {
+        parties
+            .iter_mut()
+            .filter_map(|p| p.handle_message(message.clone()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn complaint_answered_by_a_correct_reveal_keeps_the_dealer() {
+        let (mut parties, mut deals) = parties(4);
+        let complaint = cheating_deal(&mut parties, &mut deals);
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, deal) in deals.iter().enumerate() {
+                if (i, j) != (1, 0) {
+                    assert!(party.handle_message(deal.clone()).unwrap().is_none());
+                }
+            }
+        }
+        let reveals = broadcast(&mut parties, &complaint);
+        assert_eq!(reveals.len(), 1);
+        assert!(broadcast(&mut parties, &reveals[0]).is_empty());
+        let outputs: Vec<DkgOutput> = parties.iter().map(|p| p.finish().unwrap()).collect();
+        for output in &outputs {
+            assert_eq!(output.qualified, vec![1, 2, 3, 4]);
+            assert_eq!(output.public_key, outputs[0].public_key);
+            assert!(output.commitments.verify(&output.share));
+        }
+    }
+
+    #[test]
+    fn dealer_revealing_a_wrong_share_is_disqualified() {
+        let (mut parties, mut deals) = parties(4);
+        let complaint = cheating_deal(&mut parties, &mut deals);
+        let wrong = match broadcast(&mut parties[..1], &complaint).pop() {
+            Some(DkgMessage::Reveal { from, to, share }) => DkgMessage::Reveal {
+                from,
+                to,
+                share: Share {
+                    x: share.x,
+                    y: share.y + Ed25519Scalar::one(),
+                },
+            },
+            other => panic!("expected a reveal, got {:?}", other),
+        };
+        // the reveal reaches party 3 before the deal
+        assert!(parties[2].handle_message(wrong.clone()).unwrap().is_none());
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, deal) in deals.iter().enumerate() {
+                if (i, j) != (1, 0) {
+                    party.handle_message(deal.clone()).unwrap();
+                }
+            }
+            if i > 0 {
+                party.handle_message(complaint.clone()).unwrap();
+            }
+            if i != 2 {
+                party.handle_message(wrong.clone()).unwrap();
+            }
+        }
+        let outputs: Vec<DkgOutput> = parties.iter().map(|p| p.finish().unwrap()).collect();
+        for output in &outputs {
+            assert_eq!(output.qualified, vec![2, 3, 4]);
+            assert_eq!(output.public_key, outputs[0].public_key);
+            assert!(output.commitments.verify(&output.share));
+        }
+    }
+
+    #[test]
+    fn honest_parties_agree_on_the_joint_key() {
+        let (mut parties, deals) = parties(3);
+        for party in &mut parties {
+            for deal in &deals {
+                assert!(party.handle_message(deal.clone()).unwrap().is_none());
+            }
+        }
+        let outputs: Vec<DkgOutput> = parties.iter().map(|p| p.finish().unwrap()).collect();
+        for output in &outputs {
+            assert_eq!(output.public_key, outputs[0].public_key);
+            assert_eq!(output.qualified, vec![1, 2, 3]);
+            assert!(output.commitments.verify(&output.share));
+        }
+        let shares: Vec<Share<Ed25519Scalar>> =
+            outputs[1..].iter().map(|o| o.share.clone()).collect();
+        let s = Polynomial::reconstruct(&shares).unwrap();
+        assert_eq!(GroupElement::generator().pow(&s), outputs[0].public_key);
+    }
+}
@@ -0,0 +1,329 @@
+use crate::error::Error;
+use crate::field::{check_randomness, FieldElement, FIELD_ELEMENT_BYTES, REPR_BYTES};
+use crate::polynomial::Polynomial;
+use crate::share::Share;
+use alloc::{string::String, vec, vec::Vec};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Number of secret bytes packed into one field element, 15 bytes are always below the modulus
+pub const CHUNK_BYTES: usize = 15;
+
+/// Converts a secret to and from its field element representation
+pub trait SecretEncoding: Sized {
+    /// The field elements representing the secret
+    fn encode(&self) -> Vec<FieldElement>;
+    /// Recover the secret from the elements produced by `encode`
+    fn decode(elements: &[FieldElement]) -> Result<Self, Error>;
+}
+
+/// Encoded as the byte length followed by the bytes in chunks of `CHUNK_BYTES`
+impl SecretEncoding for Vec<u8> {
+    fn encode(&self) -> Vec<FieldElement> {
+        let mut elements = vec![FieldElement::new(self.len() as u64)];
+        for chunk in self.chunks(CHUNK_BYTES) {
+            let mut bytes = Zeroizing::new([0u8; REPR_BYTES]);
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            elements.push(FieldElement::from_repr_bytes(*bytes).expect("chunk is below modulus"));
+        }
+        elements
+    }
+
+    fn decode(elements: &[FieldElement]) -> Result<Self, Error> {
+        let (len, chunks) = match elements.split_first() {
+            Some((len, chunks)) => (len.to_repr_bytes(), chunks),
+            None => return Err(Error::InvalidEncoding),
+        };
+        if len[8..].iter().any(|b| *b != 0) {
+            return Err(Error::InvalidEncoding);
+        }
+        let mut len_bytes = [0u8; 8];
+        len_bytes.copy_from_slice(&len[..8]);
+        let len = u64::from_le_bytes(len_bytes) as usize;
+        if chunks.len() != len.div_ceil(CHUNK_BYTES) {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let mut bytes = Vec::with_capacity(chunks.len() * CHUNK_BYTES);
+        for chunk in chunks {
+            let chunk = chunk.to_repr_bytes();
+            if chunk[CHUNK_BYTES..].iter().any(|b| *b != 0) {
+                return Err(Error::InvalidEncoding);
+            }
+            bytes.extend_from_slice(&chunk[..CHUNK_BYTES]);
+        }
+        if bytes[len..].iter().any(|b| *b != 0) {
+            return Err(Error::InvalidEncoding);
+        }
+        bytes.truncate(len);
+        Ok(bytes)
+    }
+}
+
+impl SecretEncoding for String {
+    fn encode(&self) -> Vec<FieldElement> {
+        self.as_bytes().to_vec().encode()
+    }
+
+    fn decode(elements: &[FieldElement]) -> Result<Self, Error> {
+        String::from_utf8(Vec::decode(elements)?).map_err(|_| Error::InvalidEncoding)
+    }
+}
+
+impl SecretEncoding for u128 {
+    fn encode(&self) -> Vec<FieldElement> {
+        self.to_le_bytes().to_vec().encode()
+    }
+
+    fn decode(elements: &[FieldElement]) -> Result<Self, Error> {
+        let bytes = Vec::decode(elements)?;
+        if bytes.len() != 16 {
+            return Err(Error::InvalidEncoding);
+        }
+        let mut le_bytes = [0u8; 16];
+        le_bytes.copy_from_slice(&bytes);
+        Ok(u128::from_le_bytes(le_bytes))
+    }
+}
+
+/// An arbitrary byte string secret such as a password, a key or a seed phrase, shared in
+/// chunks of `CHUNK_BYTES` per field element
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(Vec<u8>);
+
+impl Secret {
+    /// Wrap the secret bytes
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Secret(bytes.to_vec())
+    }
+
+    /// The secret bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    /// Split into n shares per encoded element, t of which reconstruct it
+    pub fn split<R: RngCore + CryptoRng>(
+        &self,
+        t: u64,
+        n: u64,
+        rng: &mut R,
+    ) -> Result<Vec<Vec<Share>>, Error> {
+        split(self, t, n, rng)
+    }
+
+    /// Reconstruct from the shares of each encoded element
+    pub fn reconstruct(shares: &[Vec<Share>]) -> Result<Self, Error> {
+        reconstruct(shares)
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Secret {}
+
+/// Does not print the secret bytes
+impl core::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Secret({} bytes)", self.0.len())
+    }
+}
+
+impl SecretEncoding for Secret {
+    fn encode(&self) -> Vec<FieldElement> {
+        self.0.encode()
+    }
+
+    fn decode(elements: &[FieldElement]) -> Result<Self, Error> {
+        Ok(Secret(Vec::decode(elements)?))
+    }
+}
+
+/// Split a 16-byte secret into n shares, t of which reconstruct it
+/// Fails if the secret is not below the modulus or t > n
+pub fn split_bytes<R: RngCore + CryptoRng>(
+    secret: [u8; FIELD_ELEMENT_BYTES],
+    t: u64,
+    n: u64,
+    rng: &mut R,
+) -> Result<Vec<Share>, Error> {
+    if t > n {
+        return Err(Error::InvalidThreshold);
+    }
+    let s = FieldElement::from_bytes(secret)?;
+    Ok(Polynomial::new(t, s, rng)?.share(n))
+}
+
+/// Split each field element of the encoded secret into n shares, t of which reconstruct it
+/// Fails if t > n. Returns the shares per encoded element, dealt concurrently with the
+/// `parallel` feature
+pub fn split<S: SecretEncoding, R: RngCore + CryptoRng>(
+    secret: &S,
+    t: u64,
+    n: u64,
+    rng: &mut R,
+) -> Result<Vec<Vec<Share>>, Error> {
+    if t > n {
+        return Err(Error::InvalidThreshold);
+    }
+    // the randomness is drawn in order, only the evaluation runs in parallel
+    let polynomials = secret
+        .encode()
+        .into_iter()
+        .map(|s| Polynomial::new(t, s, rng))
+        .collect::<Result<Vec<_>, Error>>()?;
+    #[cfg(feature = "parallel")]
+    let polynomials = polynomials.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let polynomials = polynomials.iter();
+    Ok(polynomials.map(|p| p.share(n)).collect())
+}
+
+/// Same as `split` after a self-test of the RNG, see `check_randomness`
+pub fn split_checked<S: SecretEncoding, R: RngCore + CryptoRng>(
+    secret: &S,
+    t: u64,
+    n: u64,
+    rng: &mut R,
+) -> Result<Vec<Vec<Share>>, Error> {
+    check_randomness(rng)?;
+    split(secret, t, n, rng)
+}
+
+/// Reconstruct a secret from the shares of each of its encoded elements, concurrently with the
+/// `parallel` feature
+pub fn reconstruct<S: SecretEncoding>(shares: &[Vec<Share>]) -> Result<S, Error> {
+    #[cfg(feature = "parallel")]
+    let shares = shares.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let shares = shares.iter();
+    let elements = shares
+        .map(|s| Polynomial::reconstruct(s))
+        .collect::<Result<Vec<FieldElement>, Error>>()?;
+    S::decode(&elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::from_seed([2; 32])
+    }
+
+    /// 2^128 - 159, little-endian
+    fn modulus() -> [u8; FIELD_ELEMENT_BYTES] {
+        let mut bytes = [0xff; FIELD_ELEMENT_BYTES];
+        bytes[0] = 0x61;
+        bytes
+    }
+
+    #[test]
+    fn split_bytes_rejects_secrets_not_below_modulus() {
+        assert_eq!(
+            split_bytes(modulus(), 2, 3, &mut rng()).unwrap_err(),
+            Error::InvalidEncoding
+        );
+        assert_eq!(
+            split_bytes([0xff; FIELD_ELEMENT_BYTES], 2, 3, &mut rng()).unwrap_err(),
+            Error::InvalidEncoding
+        );
+        let mut below = modulus();
+        below[0] -= 1;
+        let shares = split_bytes(below, 2, 3, &mut rng()).unwrap();
+        assert_eq!(
+            Polynomial::reconstruct(&shares[1..]).unwrap().to_bytes(),
+            below
+        );
+    }
+
+    /// An RNG returning the same word forever
+    struct ConstantRng(u64);
+
+    impl RngCore for ConstantRng {
+        fn next_u32(&mut self) -> u32 {
+            self.0 as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for (d, b) in dest.iter_mut().zip(self.0.to_le_bytes().iter().cycle()) {
+                *d = *b;
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for ConstantRng {}
+
+    #[test]
+    fn split_checked_rejects_constant_rng() {
+        let secret = Secret::from_bytes(b"secret");
+        for word in [0, 0xdead_beef, u64::MAX] {
+            assert_eq!(
+                split_checked(&secret, 2, 3, &mut ConstantRng(word)).unwrap_err(),
+                Error::WeakRandomness
+            );
+        }
+        let shares = split_checked(&secret, 2, 3, &mut rng()).unwrap();
+        assert_eq!(Secret::reconstruct(&shares).unwrap(), secret);
+    }
+
+    #[test]
+    fn split_rejects_threshold_above_share_count() {
+        assert_eq!(
+            split_bytes([1; FIELD_ELEMENT_BYTES], 4, 3, &mut rng()).unwrap_err(),
+            Error::InvalidThreshold
+        );
+        let secret = Secret::from_bytes(b"secret");
+        assert_eq!(
+            split(&secret, 4, 3, &mut rng()).unwrap_err(),
+            Error::InvalidThreshold
+        );
+        let shares = split(&secret, 3, 3, &mut rng()).unwrap();
+        assert_eq!(Secret::reconstruct(&shares).unwrap(), secret);
+    }
+
+    /// The shares of holders 2, 4 and 5 of each element
+    fn subset(shares: &[Vec<Share>]) -> Vec<Vec<Share>> {
+        shares
+            .iter()
+            .map(|element| [1, 3, 4].iter().map(|&i| element[i].clone()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn string_round_trips_through_the_trait() {
+        let secret = String::from("correct horse battery staple, ünïcode");
+        let shares = split::<String, _>(&secret, 3, 5, &mut rng()).unwrap();
+        assert_eq!(shares.len(), 1 + secret.len().div_ceil(CHUNK_BYTES));
+        let subset = subset(&shares);
+        assert_eq!(reconstruct::<String>(&subset).unwrap(), secret);
+    }
+
+    #[test]
+    fn u128_round_trips_through_the_trait() {
+        let secret = u128::MAX - 0x1234_5678;
+        let shares = split::<u128, _>(&secret, 3, 5, &mut rng()).unwrap();
+        let subset = subset(&shares);
+        assert_eq!(reconstruct::<u128>(&subset).unwrap(), secret);
+        // fewer than t shares give another value
+        let short: Vec<Vec<Share>> = subset.iter().map(|s| s[..2].to_vec()).collect();
+        assert_ne!(reconstruct::<u128>(&short).ok(), Some(secret));
+    }
+}
@@ -0,0 +1,260 @@
+//! Shares encrypted to their recipient's X25519 public key and signed by the dealer, so they
+//! can be sent over untrusted channels.
+//!
+//! The dealer derives an XChaCha20-Poly1305 key with HKDF-SHA256 from an ephemeral X25519
+//! exchange with the recipient and encrypts the share, authenticating the metadata: the
+//! dealer's Ed25519 identity, the share index and the threshold. An Ed25519 signature over the
+//! whole envelope binds it to the dealer, so a recipient knowing the dealer's key rejects
+//! envelopes from anyone else.
+
+use crate::error::Error;
+use crate::field::FieldElement;
+use crate::share::{Share, SHARE_BYTES};
+use alloc::vec::Vec;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use core::convert::TryInto;
+use ed25519_dalek::{Signature, Signer};
+use hkdf::Hkdf;
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+pub use x25519_dalek::{PublicKey, StaticSecret};
+
+/// Length of the random XChaCha20 nonce
+pub const NONCE_BYTES: usize = 24;
+/// Length of the encrypted share, the share followed by the 16-byte Poly1305 tag
+pub const CIPHERTEXT_BYTES: usize = SHARE_BYTES + 16;
+/// Length of the encoding of an envelope
+pub const ENVELOPE_BYTES: usize = 32 + 8 + 8 + 32 + NONCE_BYTES + CIPHERTEXT_BYTES + 64;
+
+/// A share sealed to one recipient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope {
+    /// The dealer's Ed25519 public key
+    pub dealer: [u8; 32],
+    pub index: u64,
+    pub threshold: u64,
+    /// The ephemeral X25519 public key of the exchange
+    pub ephemeral: [u8; 32],
+    pub nonce: [u8; NONCE_BYTES],
+    pub ciphertext: [u8; CIPHERTEXT_BYTES],
+    /// Ed25519 signature over everything above
+    pub signature: [u8; 64],
+}
+
+impl Share {
+    /// Encrypt the share to the recipient, as share `index` of a sharing with the threshold,
+    /// and sign the envelope with the dealer's key
+    pub fn seal_to<R: RngCore + CryptoRng>(
+        &self,
+        recipient: &PublicKey,
+        dealer: &SigningKey,
+        index: u64,
+        threshold: u64,
+        rng: &mut R,
+    ) -> Result<Envelope, Error> {
+        let mut ephemeral = Zeroizing::new([0u8; 32]);
+        rng.fill_bytes(&mut ephemeral[..]);
+        let ephemeral = StaticSecret::from(*ephemeral);
+        let ephemeral_public = PublicKey::from(&ephemeral);
+        let shared = ephemeral.diffie_hellman(recipient);
+        if !shared.was_contributory() {
+            return Err(Error::InvalidEncoding);
+        }
+        let mut envelope = Envelope {
+            dealer: dealer.verifying_key().to_bytes(),
+            index,
+            threshold,
+            ephemeral: ephemeral_public.to_bytes(),
+            nonce: [0u8; NONCE_BYTES],
+            ciphertext: [0u8; CIPHERTEXT_BYTES],
+            signature: [0u8; 64],
+        };
+        rng.fill_bytes(&mut envelope.nonce);
+        let key = envelope_key(shared.as_bytes(), &envelope.ephemeral, recipient.as_bytes());
+        let share = Zeroizing::new(self.to_bytes());
+        let ciphertext = XChaCha20Poly1305::new(key[..].into())
+            .encrypt(
+                XNonce::from_slice(&envelope.nonce),
+                Payload {
+                    msg: &share[..],
+                    aad: &envelope.metadata(),
+                },
+            )
+            .map_err(|_| Error::InvalidEncoding)?;
+        envelope.ciphertext.copy_from_slice(&ciphertext);
+        envelope.signature = dealer.sign(&envelope.signed_bytes()).to_bytes();
+        Ok(envelope)
+    }
+
+    /// Verify that the dealer signed the envelope and decrypt it with the recipient's key
+    /// The share must be at x = `envelope.index`, so a dealer cannot label a share with another
+    /// holder's index
+    pub fn open_with(
+        envelope: &Envelope,
+        secret_key: &StaticSecret,
+        dealer: &VerifyingKey,
+    ) -> Result<Self, Error> {
+        if envelope.dealer != dealer.to_bytes() {
+            return Err(Error::InvalidSignature);
+        }
+        dealer
+            .verify_strict(
+                &envelope.signed_bytes(),
+                &Signature::from_bytes(&envelope.signature),
+            )
+            .map_err(|_| Error::InvalidSignature)?;
+        let shared = secret_key.diffie_hellman(&PublicKey::from(envelope.ephemeral));
+        if !shared.was_contributory() {
+            return Err(Error::DecryptionFailed);
+        }
+        let recipient = PublicKey::from(secret_key);
+        let key = envelope_key(shared.as_bytes(), &envelope.ephemeral, recipient.as_bytes());
+        let share = Zeroizing::new(
+            XChaCha20Poly1305::new(key[..].into())
+                .decrypt(
+                    XNonce::from_slice(&envelope.nonce),
+                    Payload {
+                        msg: &envelope.ciphertext[..],
+                        aad: &envelope.metadata(),
+                    },
+                )
+                .map_err(|_| Error::DecryptionFailed)?,
+        );
+        let share = Share::from_slice(&share)?;
+        if share.x != FieldElement::new(envelope.index) {
+            return Err(Error::XMismatch);
+        }
+        Ok(share)
+    }
+}
+
+impl Envelope {
+    /// The encoding dealer || index || threshold || ephemeral || nonce || ciphertext ||
+    /// signature, integers little-endian
+    pub fn to_bytes(&self) -> [u8; ENVELOPE_BYTES] {
+        let mut bytes = [0u8; ENVELOPE_BYTES];
+        let signed = self.signed_bytes();
+        bytes[..signed.len()].copy_from_slice(&signed);
+        bytes[signed.len()..].copy_from_slice(&self.signature);
+        bytes
+    }
+
+    /// Read the encoding, rejecting input of another length
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != ENVELOPE_BYTES {
+            return Err(Error::InvalidEncoding);
+        }
+        let mut envelope = Envelope {
+            dealer: [0u8; 32],
+            index: 0,
+            threshold: 0,
+            ephemeral: [0u8; 32],
+            nonce: [0u8; NONCE_BYTES],
+            ciphertext: [0u8; CIPHERTEXT_BYTES],
+            signature: [0u8; 64],
+        };
+        let (dealer, rest) = bytes.split_at(32);
+        let (index, rest) = rest.split_at(8);
+        let (threshold, rest) = rest.split_at(8);
+        let (ephemeral, rest) = rest.split_at(32);
+        let (nonce, rest) = rest.split_at(NONCE_BYTES);
+        let (ciphertext, signature) = rest.split_at(CIPHERTEXT_BYTES);
+        envelope.dealer.copy_from_slice(dealer);
+        envelope.index = u64::from_le_bytes(index.try_into().expect("8 bytes"));
+        envelope.threshold = u64::from_le_bytes(threshold.try_into().expect("8 bytes"));
+        envelope.ephemeral.copy_from_slice(ephemeral);
+        envelope.nonce.copy_from_slice(nonce);
+        envelope.ciphertext.copy_from_slice(ciphertext);
+        envelope.signature.copy_from_slice(signature);
+        Ok(envelope)
+    }
+
+    /// dealer || index || threshold, authenticated by the encryption
+    fn metadata(&self) -> Vec<u8> {
+        let mut metadata = b"polynomials envelope".to_vec();
+        metadata.extend_from_slice(&self.dealer);
+        metadata.extend_from_slice(&self.index.to_le_bytes());
+        metadata.extend_from_slice(&self.threshold.to_le_bytes());
+        metadata
+    }
+
+    /// Everything but the signature
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(ENVELOPE_BYTES - 64);
+        bytes.extend_from_slice(&self.dealer);
+        bytes.extend_from_slice(&self.index.to_le_bytes());
+        bytes.extend_from_slice(&self.threshold.to_le_bytes());
+        bytes.extend_from_slice(&self.ephemeral);
+        bytes.extend_from_slice(&self.nonce);
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+}
+
+/// HKDF-SHA256(X25519 secret, domain || ephemeral || recipient)
+fn envelope_key(
+    shared: &[u8; 32],
+    ephemeral: &[u8; 32],
+    recipient: &[u8; 32],
+) -> Zeroizing<[u8; 32]> {
+    let mut info = b"polynomials envelope key".to_vec();
+    info.extend_from_slice(ephemeral);
+    info.extend_from_slice(recipient);
+    let mut key = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(None, shared)
+        .expand(&info, &mut key[..])
+        .expect("32 bytes is a valid length for HKDF-SHA256");
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn share(x: u64) -> Share {
+        Share {
+            x: FieldElement::new(x),
+            y: FieldElement::new(1234),
+        }
+    }
+
+    #[test]
+    fn open_checks_signature_and_index() {
+        let mut rng = ChaCha20Rng::from_seed([1; 32]);
+        let dealer = SigningKey::from_bytes(&[2; 32]);
+        let secret = StaticSecret::from([3; 32]);
+        let recipient = PublicKey::from(&secret);
+        let envelope = share(3)
+            .seal_to(&recipient, &dealer, 3, 2, &mut rng)
+            .unwrap();
+        let envelope = Envelope::from_bytes(&envelope.to_bytes()).unwrap();
+        let opened = Share::open_with(&envelope, &secret, &dealer.verifying_key()).unwrap();
+        assert_eq!(opened.to_bytes(), share(3).to_bytes());
+
+        let other = SigningKey::from_bytes(&[4; 32]);
+        assert_eq!(
+            Share::open_with(&envelope, &secret, &other.verifying_key()).unwrap_err(),
+            Error::InvalidSignature
+        );
+        let mut tampered = envelope.clone();
+        tampered.threshold = 3;
+        assert_eq!(
+            Share::open_with(&tampered, &secret, &dealer.verifying_key()).unwrap_err(),
+            Error::InvalidSignature
+        );
+
+        let mislabeled = share(3)
+            .seal_to(&recipient, &dealer, 5, 2, &mut rng)
+            .unwrap();
+        assert_eq!(
+            Share::open_with(&mislabeled, &secret, &dealer.verifying_key()).unwrap_err(),
+            Error::XMismatch
+        );
+    }
+}
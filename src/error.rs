@@ -0,0 +1,160 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+/// Errors returned by the crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The answer to question `index` is wrong
+    WrongAnswer { index: usize },
+    /// Fewer shares or correct answers than the threshold
+    InsufficientShares { needed: usize, got: usize },
+    /// Two shares have the same x-coordinate
+    DuplicateX,
+    /// A share has x-coordinate 0, where the polynomial holds the secret
+    ZeroX,
+    /// Bytes or field elements that do not encode a value of the expected type
+    InvalidEncoding,
+    /// A threshold too low for the polynomial to hide the secret
+    DegreeTooLow,
+    /// A threshold above the number of shares, or hierarchical thresholds that do not increase
+    InvalidThreshold,
+    /// Inputs that have to have the same length do not
+    LengthMismatch,
+    /// A share at this x was already issued
+    AlreadyIssued { x: u64 },
+    /// The question index is out of range
+    UnknownQuestionIndex { index: usize },
+    /// There is no question with this text
+    UnknownQuestion(String),
+    /// The questionnair has this question twice
+    DuplicateQuestion(String),
+    /// Question `index` is answered twice
+    RepeatedAnswer { index: usize },
+    /// Required question `index` is not answered
+    RequiredUnanswered { index: usize },
+    /// The nonce does not satisfy the proof of work
+    InvalidProofOfWork,
+    /// Zero has no inverse
+    NotInvertible,
+    /// Key derivation parameters the KDF rejects
+    InvalidKdfParams,
+    /// A hierarchical share at a level without a threshold
+    UnknownLevel { level: usize },
+    /// The shares do not form an authorized set of the access structure
+    Unauthorized,
+    /// A file format version this crate does not read
+    UnsupportedVersion { version: u16 },
+    /// A file of shares over another field
+    UnsupportedField(String),
+    /// Share `index` does not match its checksum
+    CorruptShare { index: usize },
+    /// Share `index` belongs to another sharing than the first
+    GroupMismatch { index: usize },
+    /// Too many wrong shares to tell them from the correct ones
+    TooManyErrors,
+    /// The ciphertext does not decrypt under the reconstructed key
+    DecryptionFailed,
+    /// A protocol message names a party outside 1..=n
+    UnknownParty { index: usize },
+    /// A protocol message that is repeated or does not fit the state
+    UnexpectedMessage,
+    /// Question `index` may not be answered again before the time `retry_at`
+    RateLimited { index: usize, retry_at: u64 },
+    /// Question `index` failed too often to be answered again
+    LockedOut { index: usize },
+    /// Shares at different x-coordinates are combined into one
+    XMismatch,
+    /// The field has no subgroup of this size for a number theoretic transform
+    UnsupportedDomain { size: usize },
+    /// A signature that does not verify under the expected key
+    InvalidSignature,
+    /// Shares over different fields are combined
+    FieldMismatch,
+    /// The shares are past their expiry
+    Expired,
+    /// The secret is not valid in its domain, e.g. not a valid private key of a curve
+    InvalidSecretForDomain,
+    /// The RNG failed its self-test
+    WeakRandomness,
+    /// The shares at these positions fail verification against the dealer's commitments
+    InvalidShares { indices: Vec<usize> },
+    /// Questions cannot be added to a questionnair with required questions, whose answers mask
+    /// the points of the others
+    HasRequiredQuestions,
+    /// No share has the x-coordinate
+    UnknownX,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::WrongAnswer { index } => write!(f, "Wrong answer to question {}", index),
+            Error::InsufficientShares { needed, got } => {
+                write!(f, "Need {} shares, got {}", needed, got)
+            }
+            Error::DuplicateX => write!(f, "Duplicate x-coordinate"),
+            Error::ZeroX => write!(f, "x must not be 0 as f(0) is the secret"),
+            Error::InvalidEncoding => write!(f, "Invalid encoding"),
+            Error::DegreeTooLow => write!(f, "Threshold is too low"),
+            Error::InvalidThreshold => write!(f, "Threshold is above the number of shares"),
+            Error::LengthMismatch => write!(f, "Inputs have different lengths"),
+            Error::AlreadyIssued { x } => write!(f, "Share at x = {} already issued", x),
+            Error::UnknownQuestionIndex { index } => {
+                write!(f, "Unknown question index: {}", index)
+            }
+            Error::UnknownQuestion(q) => write!(f, "Unknown question: {}", q),
+            Error::DuplicateQuestion(q) => write!(f, "Duplicate question: {}", q),
+            Error::RepeatedAnswer { index } => write!(f, "Question {} answered twice", index),
+            Error::RequiredUnanswered { index } => {
+                write!(f, "Required question {} is not answered", index)
+            }
+            Error::InvalidProofOfWork => write!(f, "Invalid proof of work"),
+            Error::NotInvertible => write!(f, "Zero is not invertible"),
+            Error::InvalidKdfParams => write!(f, "Invalid key derivation parameters"),
+            Error::UnknownLevel { level } => write!(f, "Unknown level: {}", level),
+            Error::Unauthorized => write!(f, "The shares are not an authorized set"),
+            Error::UnsupportedVersion { version } => {
+                write!(f, "Unsupported format version: {}", version)
+            }
+            Error::UnsupportedField(field) => write!(f, "Unsupported field: {}", field),
+            Error::CorruptShare { index } => write!(f, "Share {} is corrupted", index),
+            Error::GroupMismatch { index } => {
+                write!(f, "Share {} belongs to another sharing", index)
+            }
+            Error::TooManyErrors => write!(f, "Too many wrong shares to correct"),
+            Error::DecryptionFailed => write!(f, "Decryption failed"),
+            Error::UnknownParty { index } => write!(f, "Unknown party: {}", index),
+            Error::UnexpectedMessage => write!(f, "Unexpected message"),
+            Error::RateLimited { index, retry_at } => {
+                write!(
+                    f,
+                    "Question {} may be answered again at {}",
+                    index, retry_at
+                )
+            }
+            Error::LockedOut { index } => write!(f, "Question {} is locked", index),
+            Error::XMismatch => write!(f, "Shares have different x-coordinates"),
+            Error::UnsupportedDomain { size } => {
+                write!(f, "The field has no evaluation domain of size {}", size)
+            }
+            Error::InvalidSignature => write!(f, "Invalid signature"),
+            Error::FieldMismatch => write!(f, "Shares are over different fields"),
+            Error::Expired => write!(f, "Shares have expired"),
+            Error::InvalidSecretForDomain => write!(f, "Secret is not valid for its domain"),
+            Error::WeakRandomness => write!(f, "The RNG failed its self-test"),
+            Error::InvalidShares { indices } => {
+                write!(f, "Shares {:?} fail verification", indices)
+            }
+            Error::HasRequiredQuestions => {
+                write!(
+                    f,
+                    "Cannot add questions to a questionnair with required questions"
+                )
+            }
+            Error::UnknownX => write!(f, "No share has the x-coordinate"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,332 @@
+use crate::error::Error;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use alloc::{format, string::String};
+use ff::PrimeField;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Length of the canonical encoding of a field element
+pub const FIELD_ELEMENT_BYTES: usize = 16;
+/// Length of the `ff` repr of a field element, see `FieldElement`
+pub const REPR_BYTES: usize = 3 * 8;
+
+const _: () = assert!(core::mem::size_of::<FieldElementRepr>() == REPR_BYTES);
+const _: () = assert!(FIELD_ELEMENT_BYTES + 8 == REPR_BYTES);
+
+/// This prime field has the greatest 128-bit prime as modulus. Because of the ff crate, each field
+/// element is 192bit (3*8 bytes) instead of 128 (2*8) bytes: take care when sampling random bytes.
+///
+/// A `[u64; 2]` backing is not possible with `ff_derive`: its Montgomery arithmetic needs 2*modulus
+/// to fit in the limbs, which a 128-bit modulus does not. Since every element is smaller than
+/// 2^128, the top limb of the canonical repr is always zero.
+#[derive(PrimeField)]
+#[PrimeFieldModulus = "340282366920938463463374607431768211297"]
+#[PrimeFieldGenerator = "7"]
+#[PrimeFieldReprEndianness = "little"]
+pub struct FieldElement([u64; 3]);
+impl FieldElement {
+    /// Create a field element from a u64
+    pub fn new(v: u64) -> Self {
+        let mut bytes = [0u8; REPR_BYTES];
+        bytes[0..8].copy_from_slice(&v.to_le_bytes());
+        let repr = FieldElementRepr(bytes);
+        let elm: FieldElement = PrimeField::from_repr(repr).expect("can create field elm from u64");
+        elm
+    }
+
+    /// Hash a string to a field element by rejection sampling SHA-256 outputs
+    pub fn hash(x: &str) -> Self {
+        FieldElement::hash_bytes(x.as_bytes())
+    }
+
+    /// Hash bytes to a field element by rejection sampling SHA-256 outputs
+    pub fn hash_bytes(x: &[u8]) -> Self {
+        let mut bytes = [0u8; REPR_BYTES];
+
+        let mut to_hash = Zeroizing::new(x.to_vec());
+        let max_fill = FIELD_ELEMENT_BYTES;
+        loop {
+            let mut hasher = Sha256::new();
+            hasher.update(&to_hash[..]);
+            let hash: [u8; 32] = hasher.finalize().into();
+            bytes[..max_fill].clone_from_slice(&hash[..max_fill]);
+
+            // Rejection Sampling
+            let repr = FieldElementRepr(bytes);
+            if let Some(e) = PrimeField::from_repr(repr) {
+                return e;
+            }
+            to_hash = Zeroizing::new(hash.to_vec());
+        }
+    }
+
+    /// Sample a uniformly random field element
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; REPR_BYTES];
+        let max_fill = FIELD_ELEMENT_BYTES;
+        loop {
+            rng.fill_bytes(&mut bytes[0..max_fill]);
+            let repr = FieldElementRepr(bytes);
+            if let Some(e) = PrimeField::from_repr(repr) {
+                return e;
+            }
+        }
+    }
+
+    /// Invert all elements with a single inversion (Montgomery's trick)
+    /// Fails without changing the elements if one of them is zero
+    pub fn batch_invert(elements: &mut [FieldElement]) -> Result<(), Error> {
+        batch_invert(elements)
+    }
+
+    /// The canonical 16-byte little-endian encoding
+    pub fn to_bytes(self) -> [u8; FIELD_ELEMENT_BYTES] {
+        let mut bytes = [0u8; FIELD_ELEMENT_BYTES];
+        bytes.copy_from_slice(&self.to_repr_bytes()[..FIELD_ELEMENT_BYTES]);
+        bytes
+    }
+
+    /// Read the canonical 16-byte little-endian encoding
+    /// The few values not below the modulus are rejected instead of wrapped, use 15-byte chunks
+    /// (see `SecretEncoding`) to share arbitrary bytes
+    pub fn from_bytes(bytes: [u8; FIELD_ELEMENT_BYTES]) -> Result<Self, Error> {
+        let mut repr = [0u8; REPR_BYTES];
+        repr[..FIELD_ELEMENT_BYTES].copy_from_slice(&bytes);
+        FieldElement::from_repr_bytes(repr).ok_or(Error::InvalidEncoding)
+    }
+
+    /// The raw 24-byte little-endian `ff` repr, for interop with other `ff`-based code
+    pub fn to_repr_bytes(self) -> [u8; REPR_BYTES] {
+        self.to_repr().0
+    }
+
+    /// Read the raw 24-byte little-endian `ff` repr, the top 8 bytes must be zero
+    pub fn from_repr_bytes(bytes: [u8; REPR_BYTES]) -> Option<Self> {
+        if bytes[FIELD_ELEMENT_BYTES..].iter().any(|b| *b != 0) {
+            return None;
+        }
+        PrimeField::from_repr(FieldElementRepr(bytes))
+    }
+}
+
+/// Invert all elements of any prime field with a single inversion, see `F::batch_invert`
+pub(crate) fn batch_invert<F: PrimeField>(elements: &mut [F]) -> Result<(), Error> {
+    // prefix[i] = elements[0] * .. * elements[i-1]
+    let mut prefix = Vec::with_capacity(elements.len());
+    let mut acc = F::one();
+    for e in elements.iter() {
+        prefix.push(acc);
+        acc *= e;
+    }
+    // acc = (elements[0] * .. * elements[i])^-1 going backwards
+    let mut acc = Option::<F>::from(acc.invert()).ok_or(Error::NotInvertible)?;
+    for (e, p) in elements.iter_mut().zip(prefix).rev() {
+        let inv = acc * p;
+        acc *= *e;
+        #[cfg(feature = "debug-audit")]
+        {
+            if inv * *e != F::one() {
+                tracing::warn!("batch inversion produced a wrong inverse");
+            }
+        }
+        *e = inv;
+    }
+    Ok(())
+}
+
+/// Sample an element of any prime field, uniform up to a bias of 2^-NUM_BITS by reducing twice
+/// as many random bits
+/// `Field::random` of `ff_derive` fields with a whole spare limb, like `FieldElement`, never
+/// finishes, so generic code samples with this
+pub fn random_element<F: PrimeField, R: RngCore + CryptoRng>(rng: &mut R) -> F {
+    let base = F::from(1 << 32).square();
+    (0..2 * (F::NUM_BITS as usize).div_ceil(64))
+        .fold(F::zero(), |acc, _| acc * base + F::from(rng.next_u64()))
+}
+
+/// Number of elements `check_randomness` draws
+const RANDOMNESS_SAMPLES: usize = 8;
+
+/// A self-test of the RNG before it is used to split, failing with `Error::WeakRandomness` if
+/// several drawn elements are all equal, e.g. all zero
+/// This only catches an RNG that is completely broken, it is not a statistical test
+pub fn check_randomness<R: RngCore + CryptoRng>(rng: &mut R) -> Result<(), Error> {
+    let first: FieldElement = random_element(rng);
+    let mut equal = true;
+    for _ in 1..RANDOMNESS_SAMPLES {
+        equal &= random_element::<FieldElement, R>(rng) == first;
+    }
+    if equal {
+        return Err(Error::WeakRandomness);
+    }
+    Ok(())
+}
+
+/// FieldElement is Copy and so cannot zeroize on drop, containers of secret elements do
+impl Zeroize for FieldElement {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// Compares canonically, so `assert_eq!(secret, 42u64)` works
+impl PartialEq<u64> for FieldElement {
+    fn eq(&self, other: &u64) -> bool {
+        *self == FieldElement::new(*other)
+    }
+}
+
+/// Serialized as the canonical 16-byte encoding, as a hex string for human-readable formats
+#[cfg(feature = "serde")]
+impl serde::Serialize for FieldElement {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = self.to_bytes();
+        if serializer.is_human_readable() {
+            let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            serializer.serialize_str(&hex)
+        } else {
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FieldElement {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = FieldElement;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(f, "{} bytes or a hex string of them", FIELD_ELEMENT_BYTES)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<FieldElement, E> {
+                if v.len() != 2 * FIELD_ELEMENT_BYTES || !v.is_ascii() {
+                    return Err(E::invalid_length(v.len(), &self));
+                }
+                let mut bytes = [0u8; FIELD_ELEMENT_BYTES];
+                for (i, b) in bytes.iter_mut().enumerate() {
+                    *b = u8::from_str_radix(&v[2 * i..2 * i + 2], 16)
+                        .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(v), &self))?;
+                }
+                FieldElement::from_bytes(bytes).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<FieldElement, E> {
+                if v.len() != FIELD_ELEMENT_BYTES {
+                    return Err(E::invalid_length(v.len(), &self));
+                }
+                let mut bytes = [0u8; FIELD_ELEMENT_BYTES];
+                bytes.copy_from_slice(v);
+                FieldElement::from_bytes(bytes).map_err(E::custom)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<FieldElement, A::Error> {
+                let mut bytes = [0u8; FIELD_ELEMENT_BYTES];
+                for (i, b) in bytes.iter_mut().enumerate() {
+                    *b = seq
+                        .next_element()?
+                        .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+                }
+                if seq.next_element::<u8>()?.is_some() {
+                    return Err(serde::de::Error::invalid_length(
+                        FIELD_ELEMENT_BYTES + 1,
+                        &self,
+                    ));
+                }
+                FieldElement::from_bytes(bytes).map_err(serde::de::Error::custom)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(Visitor)
+        } else {
+            deserializer.deserialize_bytes(Visitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::Field;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::from_seed([1; 32])
+    }
+
+    #[test]
+    fn top_limb_is_always_zero() {
+        assert_eq!(core::mem::size_of::<FieldElement>(), REPR_BYTES);
+        let mut rng = rng();
+        let mut elements = vec![
+            FieldElement::zero(),
+            FieldElement::one(),
+            -FieldElement::one(),
+        ];
+        for _ in 0..100 {
+            let a = FieldElement::random(&mut rng);
+            let b = FieldElement::random(&mut rng);
+            elements.extend_from_slice(&[a, a * b, a + b, a - b, -a]);
+        }
+        for e in elements {
+            assert_eq!(e.to_repr_bytes()[FIELD_ELEMENT_BYTES..], [0u8; 8]);
+        }
+    }
+
+    #[test]
+    fn repr_bytes_round_trip() {
+        let e = FieldElement::random(&mut rng());
+        assert_eq!(FieldElement::from_repr_bytes(e.to_repr_bytes()), Some(e));
+        let mut bytes = e.to_repr_bytes();
+        bytes[REPR_BYTES - 1] = 1;
+        assert_eq!(FieldElement::from_repr_bytes(bytes), None);
+    }
+
+    #[test]
+    fn bytes_are_little_endian_on_every_host() {
+        let e = FieldElement::new(0x0807_0605_0403_0201);
+        let bytes = [1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(e.to_bytes(), bytes);
+        assert_eq!(FieldElement::from_bytes(bytes).unwrap(), e);
+        let mut high = [0u8; FIELD_ELEMENT_BYTES];
+        high[15] = 1;
+        // 2^120 = (2^60)^2
+        let two_60 = FieldElement::new(1 << 60);
+        assert_eq!(FieldElement::from_bytes(high).unwrap(), two_60 * two_60);
+    }
+
+    #[test]
+    fn batch_invert_matches_individual_inversions() {
+        let mut rng = rng();
+        let elements: Vec<FieldElement> = (0..33).map(|_| FieldElement::random(&mut rng)).collect();
+        let mut inverted = elements.clone();
+        FieldElement::batch_invert(&mut inverted).unwrap();
+        for (e, inv) in elements.iter().zip(&inverted) {
+            assert_eq!(*inv, e.invert().unwrap());
+        }
+        let mut with_zero = elements.clone();
+        with_zero[5] = FieldElement::zero();
+        assert_eq!(
+            FieldElement::batch_invert(&mut with_zero),
+            Err(Error::NotInvertible)
+        );
+        assert_eq!(with_zero[..5], elements[..5]);
+    }
+
+    #[test]
+    fn compares_with_integers() {
+        assert_eq!(FieldElement::new(42), 42u64);
+        assert_ne!(FieldElement::new(42), 43u64);
+        assert_eq!(FieldElement::new(40) + FieldElement::new(2), 42u64);
+    }
+}
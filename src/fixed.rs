@@ -0,0 +1,81 @@
+//! Sharing into fixed-size arrays, without heap allocation, for embedded and MPC callers.
+
+use crate::error::Error;
+use crate::field::random_element;
+use crate::share::Share;
+use ff::PrimeField;
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroize;
+
+/// Deal N shares at x = 1..=N of a random degree t-1 polynomial with f(0)=s
+/// The coefficients are added to the shares as they are sampled, so the polynomial is never
+/// stored
+pub fn deal<F: PrimeField + Zeroize, const N: usize, R: RngCore + CryptoRng>(
+    t: usize,
+    s: F,
+    rng: &mut R,
+) -> Result<[Share<F>; N], Error> {
+    if t < 2 {
+        return Err(Error::DegreeTooLow);
+    }
+    if t > N {
+        return Err(Error::InvalidThreshold);
+    }
+    let mut shares: [Share<F>; N] = core::array::from_fn(|i| Share {
+        x: F::from(i as u64 + 1),
+        y: s,
+    });
+    // x_i^j for the coefficient of x^j being sampled
+    let mut powers: [F; N] = core::array::from_fn(|i| F::from(i as u64 + 1));
+    for _ in 1..t {
+        let mut coefficient: F = random_element(rng);
+        for (share, power) in shares.iter_mut().zip(powers.iter_mut()) {
+            share.y += coefficient * *power;
+            *power *= share.x;
+        }
+        coefficient.zeroize();
+    }
+    Ok(shares)
+}
+
+/// Compute f(0) by Lagrange interpolation over all given shares, e.g. t of those from `deal`
+/// Branches only on the x-coordinates, which are public, and never on the y-values
+pub fn combine<F: PrimeField + Zeroize>(shares: &[Share<F>]) -> Result<F, Error> {
+    if shares.is_empty() {
+        return Err(Error::InsufficientShares { needed: 1, got: 0 });
+    }
+    let mut secret = F::zero();
+    for (i, share) in shares.iter().enumerate() {
+        // l_i(0) = prod x_j / (x_j - x_i) over j != i
+        let mut n = F::one();
+        let mut d = F::one();
+        for (j, other) in shares.iter().enumerate() {
+            if i != j {
+                n *= other.x;
+                d *= other.x - share.x;
+            }
+        }
+        let d = Option::<F>::from(d.invert()).ok_or(Error::DuplicateX)?;
+        secret += n * d * share.y;
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::FieldElement;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn combine_reconstructs_and_rejects_no_shares() {
+        let secret = FieldElement::new(9);
+        let shares: [Share; 4] = deal(3, secret, &mut ChaCha20Rng::from_seed([3; 32])).unwrap();
+        assert_eq!(combine(&shares[1..]).unwrap(), secret);
+        assert_eq!(
+            combine::<FieldElement>(&[]).unwrap_err(),
+            Error::InsufficientShares { needed: 1, got: 0 }
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Versioned files for shares and questionnairs, as JSON or compact CBOR.
+//!
+//! Every file carries a magic string, the format version, the field identifier and the threshold,
+//! and shares also their index. Loading rejects other magics and fields, and versions newer
+//! than this crate knows, while unknown entries added by later versions are ignored.
+
+use crate::error::Error;
+use crate::questionnair::Questionnair;
+use crate::share::Share;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Identifies files in this format
+pub const MAGIC: &str = "polynomials";
+/// The newest format version, written by this crate
+pub const FORMAT_VERSION: u16 = 1;
+/// Identifier of the field the shares are over
+pub const FIELD_ID: &str = "gf(2^128-159)";
+
+/// A share or questionnair with its format header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Versioned<T> {
+    pub magic: String,
+    pub version: u16,
+    pub field: String,
+    /// Number of shares or correct answers needed to recover the secret
+    pub threshold: u64,
+    /// The index of the share, none for questionnairs
+    pub index: Option<u64>,
+    pub payload: T,
+}
+
+impl Versioned<Share> {
+    /// Share `index` of a sharing with threshold t
+    pub fn share(share: Share, threshold: u64, index: u64) -> Self {
+        Versioned::new(share, threshold, Some(index))
+    }
+}
+
+impl Versioned<Questionnair> {
+    /// The questionnair with its threshold
+    pub fn questionnair(questionnair: Questionnair) -> Self {
+        let threshold = questionnair.threshold() as u64;
+        Versioned::new(questionnair, threshold, None)
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> Versioned<T> {
+    fn new(payload: T, threshold: u64, index: Option<u64>) -> Self {
+        Versioned {
+            magic: MAGIC.to_string(),
+            version: FORMAT_VERSION,
+            field: FIELD_ID.to_string(),
+            threshold,
+            index,
+            payload,
+        }
+    }
+
+    /// Encode as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self).map_err(|_| Error::InvalidEncoding)
+    }
+
+    /// Decode from JSON and check the header
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str::<Self>(json)
+            .map_err(|_| Error::InvalidEncoding)?
+            .checked()
+    }
+
+    /// Encode as CBOR
+    pub fn to_cbor(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(self, &mut bytes).map_err(|_| Error::InvalidEncoding)?;
+        Ok(bytes)
+    }
+
+    /// Decode from CBOR and check the header
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, Error> {
+        ciborium::de::from_reader::<Self, _>(bytes)
+            .map_err(|_| Error::InvalidEncoding)?
+            .checked()
+    }
+
+    fn checked(self) -> Result<Self, Error> {
+        if self.magic != MAGIC {
+            return Err(Error::InvalidEncoding);
+        }
+        if self.version == 0 || self.version > FORMAT_VERSION {
+            return Err(Error::UnsupportedVersion {
+                version: self.version,
+            });
+        }
+        if self.field != FIELD_ID {
+            return Err(Error::UnsupportedField(self.field));
+        }
+        Ok(self)
+    }
+}
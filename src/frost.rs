@@ -0,0 +1,455 @@
+//! FROST(ristretto255, SHA-512) two-round threshold Schnorr signing of RFC 9591, with shares of
+//! a signing key, e.g. from `dkg`.
+//!
+//! In round one every signer publishes commitments to two fresh nonces, in round two each of
+//! t signers computes a signature share z_i = d_i + e_i rho_i + lambda_i s_i c, and the
+//! signature shares add up to a Schnorr signature (R, z) with g^z = R Y^c. The identifier of
+//! signer i is the scalar i, the x-coordinate of its share, and the signatures verify with any
+//! implementation of the RFC 9591 ciphersuite.
+
+use crate::curves::{Ed25519Scalar, SCALAR_BYTES};
+use crate::error::Error;
+use crate::polynomial::lagrange_coefficients;
+use crate::share::Share;
+use crate::vss::{GroupElement, GROUP_ELEMENT_BYTES};
+use curve25519_dalek::scalar::Scalar;
+use ff::Field;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use zeroize::{Zeroize, Zeroizing};
+
+/// The contextString of the ciphersuite, prefixed to every hash
+const CONTEXT: &[u8] = b"FROST-RISTRETTO255-SHA512-v1";
+
+/// Length of the encoding R || z of a signature
+pub const SIGNATURE_BYTES: usize = GROUP_ELEMENT_BYTES + SCALAR_BYTES;
+
+/// The secret nonces of one signing session, consumed by `Signer::sign` so they are never reused
+#[derive(Debug)]
+pub struct SigningNonces {
+    hiding: Ed25519Scalar,
+    binding: Ed25519Scalar,
+}
+
+impl Drop for SigningNonces {
+    fn drop(&mut self) {
+        self.hiding.zeroize();
+        self.binding.zeroize();
+    }
+}
+
+/// The public commitments g^d and g^e to a signer's nonces
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SigningCommitment {
+    pub index: usize,
+    pub hiding: GroupElement,
+    pub binding: GroupElement,
+}
+
+/// A Schnorr signature (R, z) with g^z = R Y^c for c = H2(R || Y || m)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Signature {
+    pub r: GroupElement,
+    pub z: Ed25519Scalar,
+}
+
+impl Signature {
+    /// Checks the signature of the message under the group public key
+    pub fn verify(&self, public_key: &GroupElement, message: &[u8]) -> bool {
+        let c = challenge(&self.r, public_key, message);
+        GroupElement::generator().pow(&self.z) == self.r.clone() * public_key.pow(&c)
+    }
+
+    /// The encoding R || z of the RFC
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_BYTES] {
+        let mut bytes = [0u8; SIGNATURE_BYTES];
+        bytes[..GROUP_ELEMENT_BYTES].copy_from_slice(&self.r.to_bytes());
+        bytes[GROUP_ELEMENT_BYTES..].copy_from_slice(&self.z.to_bytes());
+        bytes
+    }
+
+    /// Read the encoding R || z, rejecting non-canonical values
+    pub fn from_bytes(bytes: &[u8; SIGNATURE_BYTES]) -> Result<Self, Error> {
+        let mut z = [0u8; SCALAR_BYTES];
+        z.copy_from_slice(&bytes[GROUP_ELEMENT_BYTES..]);
+        Ok(Signature {
+            r: GroupElement::from_bytes(&bytes[..GROUP_ELEMENT_BYTES])?,
+            z: Ed25519Scalar::from_bytes(z)?,
+        })
+    }
+}
+
+/// A holder of a share of the signing key, whose public key is g^s
+pub struct Signer {
+    index: usize,
+    share: Share<Ed25519Scalar>,
+    public_key: GroupElement,
+}
+
+impl Signer {
+    /// Signer `index` holding the share at x = index of the key with the public key
+    pub fn new(
+        index: usize,
+        share: Share<Ed25519Scalar>,
+        public_key: GroupElement,
+    ) -> Result<Self, Error> {
+        if index == 0 || share.x != Ed25519Scalar::from(index as u64) {
+            return Err(Error::UnknownParty { index });
+        }
+        Ok(Signer {
+            index,
+            share,
+            public_key,
+        })
+    }
+
+    /// Round one: fresh nonces and the commitment to publish
+    pub fn commit<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> (SigningNonces, SigningCommitment) {
+        let mut hiding = Zeroizing::new([0u8; 32]);
+        let mut binding = Zeroizing::new([0u8; 32]);
+        rng.fill_bytes(&mut hiding[..]);
+        rng.fill_bytes(&mut binding[..]);
+        self.commit_with(&hiding, &binding)
+    }
+
+    /// The nonces H3(randomness || s_i) and their commitments
+    fn commit_with(
+        &self,
+        hiding: &[u8; 32],
+        binding: &[u8; 32],
+    ) -> (SigningNonces, SigningCommitment) {
+        let nonces = SigningNonces {
+            hiding: nonce(hiding, &self.share.y),
+            binding: nonce(binding, &self.share.y),
+        };
+        let g = GroupElement::generator();
+        let commitment = SigningCommitment {
+            index: self.index,
+            hiding: g.pow(&nonces.hiding),
+            binding: g.pow(&nonces.binding),
+        };
+        (nonces, commitment)
+    }
+
+    /// Round two: the signature share of the message, given the commitments of all signers in
+    /// this session including this one, in increasing order of index
+    /// Fails if this signer's commitment among them is not the one to the nonces, so a
+    /// coordinator cannot substitute it
+    pub fn sign(
+        &self,
+        nonces: SigningNonces,
+        message: &[u8],
+        commitments: &[SigningCommitment],
+    ) -> Result<Ed25519Scalar, Error> {
+        let index = commitments
+            .iter()
+            .position(|c| c.index == self.index)
+            .ok_or(Error::UnexpectedMessage)?;
+        let g = GroupElement::generator();
+        let own = &commitments[index];
+        if own.hiding != g.pow(&nonces.hiding) || own.binding != g.pow(&nonces.binding) {
+            return Err(Error::UnexpectedMessage);
+        }
+        let session = Session::new(message, commitments, &self.public_key)?;
+        Ok(nonces.hiding
+            + nonces.binding * session.binding_factors[index]
+            + session.lagrange[index] * self.share.y * session.challenge)
+    }
+}
+
+/// Checks signer i's signature share against its verification share Y_i = g^{s_i}, so a
+/// misbehaving signer is identified before aggregating
+pub fn verify_partial(
+    partial: &Ed25519Scalar,
+    index: usize,
+    verification_share: &GroupElement,
+    message: &[u8],
+    commitments: &[SigningCommitment],
+    public_key: &GroupElement,
+) -> Result<bool, Error> {
+    let session = Session::new(message, commitments, public_key)?;
+    let i = commitments
+        .iter()
+        .position(|c| c.index == index)
+        .ok_or(Error::UnknownParty { index })?;
+    let c = &commitments[i];
+    let expected = c.hiding.clone()
+        * c.binding.pow(&session.binding_factors[i])
+        * verification_share.pow(&(session.lagrange[i] * session.challenge));
+    Ok(GroupElement::generator().pow(partial) == expected)
+}
+
+/// Add up the signature shares, given in the order of the commitments
+pub fn aggregate(
+    message: &[u8],
+    commitments: &[SigningCommitment],
+    partials: &[Ed25519Scalar],
+    public_key: &GroupElement,
+) -> Result<Signature, Error> {
+    if partials.len() != commitments.len() {
+        return Err(Error::LengthMismatch);
+    }
+    let session = Session::new(message, commitments, public_key)?;
+    Ok(Signature {
+        r: session.r,
+        z: partials
+            .iter()
+            .fold(Ed25519Scalar::zero(), |acc, z| acc + z),
+    })
+}
+
+/// What every participant of a signing session derives from the public values
+struct Session {
+    binding_factors: Vec<Ed25519Scalar>,
+    lagrange: Vec<Ed25519Scalar>,
+    r: GroupElement,
+    challenge: Ed25519Scalar,
+}
+
+impl Session {
+    /// Fails unless the commitments are in strictly increasing order of nonzero index, and
+    /// for identity commitments, which the RFC does not encode
+    fn new(
+        message: &[u8],
+        commitments: &[SigningCommitment],
+        public_key: &GroupElement,
+    ) -> Result<Self, Error> {
+        if commitments.is_empty() {
+            return Err(Error::InsufficientShares { needed: 1, got: 0 });
+        }
+        if commitments[0].index == 0 {
+            return Err(Error::ZeroX);
+        }
+        if commitments.windows(2).any(|w| w[0].index >= w[1].index) {
+            return Err(Error::UnexpectedMessage);
+        }
+        let identity = GroupElement::identity();
+        if commitments
+            .iter()
+            .any(|c| c.hiding == identity || c.binding == identity)
+        {
+            return Err(Error::InvalidEncoding);
+        }
+        let xs: Vec<Ed25519Scalar> = commitments
+            .iter()
+            .map(|c| Ed25519Scalar::from(c.index as u64))
+            .collect();
+        let lagrange = lagrange_coefficients(&xs, &Ed25519Scalar::zero())?;
+        // rho_i = H1(Y || H4(m) || H5(encoded commitments) || i)
+        let mut encoded = Vec::with_capacity(commitments.len() * 96);
+        for (c, x) in commitments.iter().zip(&xs) {
+            encoded.extend_from_slice(&x.to_bytes());
+            encoded.extend_from_slice(&c.hiding.to_bytes());
+            encoded.extend_from_slice(&c.binding.to_bytes());
+        }
+        let mut prefix = public_key.to_bytes().to_vec();
+        prefix.extend_from_slice(&hash(b"msg", &[message]));
+        prefix.extend_from_slice(&hash(b"com", &[&encoded]));
+        let binding_factors: Vec<Ed25519Scalar> = xs
+            .iter()
+            .map(|x| hash_to_scalar(b"rho", &[&prefix, &x.to_bytes()]))
+            .collect();
+        let r = commitments
+            .iter()
+            .zip(&binding_factors)
+            .map(|(c, rho)| c.hiding.clone() * c.binding.pow(rho))
+            .fold(identity, |acc, e| acc * e);
+        let challenge = challenge(&r, public_key, message);
+        Ok(Session {
+            binding_factors,
+            lagrange,
+            r,
+            challenge,
+        })
+    }
+}
+
+/// H(contextString || tag || parts)
+fn hash(tag: &[u8], parts: &[&[u8]]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    hasher.update(CONTEXT);
+    hasher.update(tag);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// H1, H2 and H3 of the RFC, the hash reduced modulo l
+fn hash_to_scalar(tag: &[u8], parts: &[&[u8]]) -> Ed25519Scalar {
+    let mut wide = hash(tag, parts);
+    let scalar = Ed25519Scalar::from(Scalar::from_bytes_mod_order_wide(&wide));
+    wide.zeroize();
+    scalar
+}
+
+/// H3(randomness || s_i), so a weak RNG alone does not leak the share
+fn nonce(randomness: &[u8; 32], secret: &Ed25519Scalar) -> Ed25519Scalar {
+    let secret = Zeroizing::new(secret.to_bytes());
+    hash_to_scalar(b"nonce", &[randomness, &secret[..]])
+}
+
+/// c = H2(R || Y || m)
+fn challenge(r: &GroupElement, public_key: &GroupElement, message: &[u8]) -> Ed25519Scalar {
+    hash_to_scalar(b"chal", &[&r.to_bytes(), &public_key.to_bytes(), message])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vss::deal;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn bytes(hex: &str) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        bytes
+    }
+
+    fn scalar(hex: &str) -> Ed25519Scalar {
+        Ed25519Scalar::from_bytes(bytes(hex)).unwrap()
+    }
+
+    /// The FROST(ristretto255, SHA-512) test vector of RFC 9591, appendix E.4
+    #[test]
+    fn matches_rfc_9591_test_vector() {
+        let public_key = GroupElement::from_bytes(&bytes(
+            "e2a62f39eede11269e3bd5a7d97554f5ca384f9f6d3dd9c3c0d05083c7254f57",
+        ))
+        .unwrap();
+        let signer = |index: usize, share: &str| {
+            let share = Share {
+                x: Ed25519Scalar::from(index as u64),
+                y: scalar(share),
+            };
+            Signer::new(index, share, public_key.clone()).unwrap()
+        };
+        let p1 = signer(
+            1,
+            "5c3430d391552f6e60ecdc093ff9f6f4488756aa6cebdbad75a768010b8f830e",
+        );
+        let p3 = signer(
+            3,
+            "f17e505f0e2581c6acfe54d3846a622834b5e7b50cad9a2109a97ba7a80d5c04",
+        );
+        let (n1, c1) = p1.commit_with(
+            &bytes("f595a133b4d95c6e1f79887220c8b275ce6277e7f68a6640e1e7140f9be2fb5c"),
+            &bytes("34dd1001360e3513cb37bebfabe7be4a32c5bb91ba19fbd4360d039111f0fbdc"),
+        );
+        let (n3, c3) = p3.commit_with(
+            &bytes("daa0cf42a32617786d390e0c7edfbf2efbd428037069357b5173ae61d6dd5d5e"),
+            &bytes("b4387e72b2e4108ce4168931cc2c7fcce5f345a5297368952c18b5fc8473f050"),
+        );
+        assert_eq!(
+            n1.hiding,
+            scalar("214f2cabb86ed71427ea7ad4283b0fae26b6746c801ce824b83ceb2b99278c03")
+        );
+        assert_eq!(
+            c1.hiding.to_bytes(),
+            bytes("965def4d0958398391fc06d8c2d72932608b1e6255226de4fb8d972dac15fd57")
+        );
+        let message = b"test";
+        let commitments = [c1, c3];
+        let z1 = p1.sign(n1, message, &commitments).unwrap();
+        let z3 = p3.sign(n3, message, &commitments).unwrap();
+        assert_eq!(
+            z1,
+            scalar("9285f875923ce7e0c491a592e9ea1865ec1b823ead4854b48c8a46287749ee09")
+        );
+        assert_eq!(
+            z3,
+            scalar("7cb211fe0e3d59d25db6e36b3fb32344794139602a7b24f1ae0dc4e26ad7b908")
+        );
+        let signature = aggregate(message, &commitments, &[z1, z3], &public_key).unwrap();
+        let expected = "fc45655fbc66bbffad654ea4ce5fdae253a49a64ace25d9adb62010dd9fb2555\
+                        2164141787162e5b4cab915b4aa45d94655dbb9ed7c378a53b980a0be220a802";
+        let encoded: String = signature
+            .to_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert_eq!(encoded, expected);
+        assert!(signature.verify(&public_key, message));
+        assert_eq!(
+            Signature::from_bytes(&signature.to_bytes()).unwrap(),
+            signature
+        );
+    }
+
+    #[test]
+    fn threshold_signers_sign_and_bad_shares_are_identified() {
+        let mut rng = ChaCha20Rng::from_seed([13; 32]);
+        let (shares, commitments) = deal(Ed25519Scalar::random(&mut rng), 2, 3, &mut rng).unwrap();
+        let public_key = commitments.secret().clone();
+        let signers: Vec<Signer> = shares
+            .iter()
+            .zip(1..)
+            .map(|(share, index)| Signer::new(index, share.clone(), public_key.clone()).unwrap())
+            .skip(1)
+            .collect();
+        let (nonces, session): (Vec<SigningNonces>, Vec<SigningCommitment>) =
+            signers.iter().map(|s| s.commit(&mut rng)).unzip();
+        let message = b"message";
+        let mut partials: Vec<Ed25519Scalar> = signers
+            .iter()
+            .zip(nonces)
+            .map(|(s, n)| s.sign(n, message, &session).unwrap())
+            .collect();
+        let signature = aggregate(message, &session, &partials, &public_key).unwrap();
+        assert!(signature.verify(&public_key, message));
+        assert!(!signature.verify(&public_key, b"other message"));
+
+        partials[1] += Ed25519Scalar::one();
+        for (i, c) in session.iter().enumerate() {
+            let verification_share =
+                commitments.share_commitment(&Ed25519Scalar::from(c.index as u64));
+            let valid = verify_partial(
+                &partials[i],
+                c.index,
+                &verification_share,
+                message,
+                &session,
+                &public_key,
+            )
+            .unwrap();
+            assert_eq!(valid, i == 0);
+        }
+        let reversed: Vec<SigningCommitment> = session.iter().rev().cloned().collect();
+        assert!(aggregate(message, &reversed, &partials, &public_key).is_err());
+    }
+
+    #[test]
+    fn substituted_commitments_are_refused() {
+        let mut rng = ChaCha20Rng::from_seed([14; 32]);
+        let (shares, commitments) = deal(Ed25519Scalar::random(&mut rng), 2, 2, &mut rng).unwrap();
+        let public_key = commitments.secret().clone();
+        let signer = Signer::new(1, shares[0].clone(), public_key.clone()).unwrap();
+        let other = Signer::new(2, shares[1].clone(), public_key).unwrap();
+        let (_, theirs) = other.commit(&mut rng);
+        for substitute in 0..2 {
+            let (nonces, mut ours) = signer.commit(&mut rng);
+            let (_, fake) = signer.commit(&mut rng);
+            if substitute == 0 {
+                ours.hiding = fake.hiding;
+            } else {
+                ours.binding = fake.binding;
+            }
+            assert_eq!(
+                signer
+                    .sign(nonces, b"message", &[ours, theirs.clone()])
+                    .unwrap_err(),
+                Error::UnexpectedMessage
+            );
+        }
+    }
+}
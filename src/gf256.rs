@@ -0,0 +1,157 @@
+//! Shamir secret sharing over GF(2^8), sharing each byte of the secret independently.
+//!
+//! A share is its x-coordinate followed by one byte per secret byte, so shares are one byte
+//! longer than the secret. The field is GF(2)[x] / (x^8 + x^4 + x^3 + x + 1) as in AES, and any
+//! t of the shares reconstruct the secret.
+
+use crate::error::Error;
+use alloc::{vec, vec::Vec};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use zeroize::Zeroizing;
+
+/// Secret bytes whose coefficients are drawn at a time, bounding the memory of `split`
+const BLOCK_BYTES: usize = 1 << 16;
+
+/// Multiplies in GF(2^8) without table lookups
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (carry & 0x1b);
+        b >>= 1;
+    }
+    product
+}
+
+/// Inverts a non-zero element as a^254
+fn inv(a: u8) -> u8 {
+    let mut result = 1;
+    let mut power = a;
+    let mut e = 254u8;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mul(result, power);
+        }
+        power = mul(power, power);
+        e >>= 1;
+    }
+    result
+}
+
+/// Split the secret into n shares of secret.len() + 1 bytes, t of which reconstruct it
+/// The shares are computed concurrently with the `parallel` feature
+/// Fails for t < 2, where every share would contain the secret itself
+pub fn split<R: RngCore + CryptoRng>(
+    secret: &[u8],
+    t: u8,
+    n: u8,
+    rng: &mut R,
+) -> Result<Vec<Vec<u8>>, Error> {
+    if t < 2 {
+        return Err(Error::DegreeTooLow);
+    }
+    if t > n {
+        return Err(Error::InvalidThreshold);
+    }
+    let t = t as usize;
+    let mut shares: Vec<Vec<u8>> = (1..=n)
+        .map(|x| {
+            let mut share = Vec::with_capacity(secret.len() + 1);
+            share.push(x);
+            share
+        })
+        .collect();
+    let mut coefficients = Zeroizing::new(vec![0u8; BLOCK_BYTES * t]);
+    for block in secret.chunks(BLOCK_BYTES) {
+        // the randomness is drawn in order, only the evaluation runs in parallel
+        for (c, &s) in coefficients.chunks_mut(t).zip(block) {
+            c[0] = s;
+            rng.fill_bytes(&mut c[1..]);
+        }
+        let coefficients = &coefficients[..block.len() * t];
+        #[cfg(feature = "parallel")]
+        let holders = shares.par_iter_mut();
+        #[cfg(not(feature = "parallel"))]
+        let holders = shares.iter_mut();
+        holders.for_each(|share| {
+            let x = share[0];
+            share.extend(
+                coefficients
+                    .chunks(t)
+                    .map(|c| c.iter().rev().fold(0, |acc, &c| mul(acc, x) ^ c)),
+            );
+        });
+    }
+    Ok(shares)
+}
+
+/// Reconstruct the secret from at least t shares by interpolating each byte at 0, concurrently
+/// with the `parallel` feature
+pub fn reconstruct(shares: &[Vec<u8>]) -> Result<Vec<u8>, Error> {
+    let len = match shares.first() {
+        Some(share) if !share.is_empty() => share.len(),
+        _ => return Err(Error::InsufficientShares { needed: 1, got: 0 }),
+    };
+    if shares.iter().any(|share| share.len() != len) {
+        return Err(Error::LengthMismatch);
+    }
+    let xs: Vec<u8> = shares.iter().map(|share| share[0]).collect();
+    for (i, x) in xs.iter().enumerate() {
+        if *x == 0 {
+            return Err(Error::ZeroX);
+        }
+        if xs[..i].contains(x) {
+            return Err(Error::DuplicateX);
+        }
+    }
+    // l_i(0) = prod_{j != i} x_j / (x_j - x_i), where subtraction is xor
+    let coefficients: Vec<u8> = xs
+        .iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            let (num, den) = xs
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold((1, 1), |(num, den), (_, &xj)| {
+                    (mul(num, xj), mul(den, xj ^ xi))
+                });
+            mul(num, inv(den))
+        })
+        .collect();
+    #[cfg(feature = "parallel")]
+    let positions = (1..len).into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let positions = 1..len;
+    Ok(positions
+        .map(|k| {
+            shares
+                .iter()
+                .zip(&coefficients)
+                .fold(0, |acc, (share, &l)| acc ^ mul(share[k], l))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn split_rejects_thresholds_below_two() {
+        let mut rng = ChaCha20Rng::from_seed([6; 32]);
+        for t in 0..2 {
+            assert_eq!(
+                split(b"secret", t, 3, &mut rng).unwrap_err(),
+                Error::DegreeTooLow
+            );
+        }
+        let shares = split(b"secret", 2, 3, &mut rng).unwrap();
+        assert_eq!(reconstruct(&shares[1..]).unwrap(), b"secret");
+    }
+}
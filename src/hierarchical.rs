@@ -0,0 +1,166 @@
+//! Tassa's hierarchical threshold sharing, where participants belong to levels with increasing
+//! thresholds k_0 < k_1 < .. < k_m. A set of shares is authorized if for every level i it has at
+//! least k_i shares of level i or above, e.g. thresholds [1, 4] need an executive (level 0) and
+//! 4 shares in total, so 1 executive and 3 managers (level 1) or 4 executives.
+//!
+//! Level 0 gets f(x) and level i gets the k_{i-1}-th derivative of f at x, where f has degree
+//! k_m - 1 and f(0) = s. Reconstruction solves for f(0) by Birkhoff interpolation.
+
+use crate::error::Error;
+use crate::field::FieldElement;
+use crate::polynomial::{falling_factorial, Polynomial};
+use crate::share::Share;
+use alloc::vec::Vec;
+use core::fmt;
+use ff::Field;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// A share together with the level of its holder
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HierarchicalShare {
+    pub level: usize,
+    pub share: Share,
+}
+
+/// Retains the polynomial to deal hierarchical shares
+pub struct HierarchicalDealer {
+    polynomial: Polynomial,
+    thresholds: Vec<u64>,
+}
+
+impl fmt::Debug for HierarchicalDealer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HierarchicalDealer")
+            .field("polynomial", &self.polynomial)
+            .field("thresholds", &self.thresholds)
+            .finish()
+    }
+}
+
+impl HierarchicalDealer {
+    /// Create random degree k_m - 1 polynomial with f(0)=s for the thresholds k_0 < .. < k_m
+    pub fn new<R: RngCore + CryptoRng>(
+        s: FieldElement,
+        thresholds: &[u64],
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        check_thresholds(thresholds)?;
+        let top = thresholds[thresholds.len() - 1];
+        Ok(HierarchicalDealer {
+            polynomial: Polynomial::new(top, s, rng)?,
+            thresholds: thresholds.to_vec(),
+        })
+    }
+
+    /// Deal counts[i] shares to level i, at x = 1, 2, .. in level order
+    /// Giving lower levels the lower x keeps the authorized sets solvable
+    pub fn deal(&self, counts: &[u64]) -> Result<Vec<HierarchicalShare>, Error> {
+        if counts.len() != self.thresholds.len() {
+            return Err(Error::LengthMismatch);
+        }
+        let mut total = 0;
+        for (count, threshold) in counts.iter().zip(&self.thresholds) {
+            total += count;
+            if total < *threshold {
+                return Err(Error::InvalidThreshold);
+            }
+        }
+        let mut shares = Vec::with_capacity(total as usize);
+        let mut x = 0;
+        for (level, count) in counts.iter().enumerate() {
+            let derivative = self.polynomial.derivative(order(&self.thresholds, level));
+            for _ in 0..*count {
+                x += 1;
+                let x = FieldElement::new(x);
+                shares.push(HierarchicalShare {
+                    level,
+                    share: Share {
+                        x,
+                        y: derivative.evaluate(&x),
+                    },
+                });
+            }
+        }
+        Ok(shares)
+    }
+}
+
+/// Reconstruct s from hierarchical shares dealt with the thresholds
+/// Branches only on the x-coordinates and levels, which are public, and never on the y-values
+pub fn reconstruct_hierarchical(
+    shares: &[HierarchicalShare],
+    thresholds: &[u64],
+) -> Result<FieldElement, Error> {
+    check_thresholds(thresholds)?;
+    let k = thresholds[thresholds.len() - 1] as usize;
+    if shares.len() < k {
+        return Err(Error::InsufficientShares {
+            needed: k,
+            got: shares.len(),
+        });
+    }
+    // rows of the Birkhoff system: the order-th derivative of x^i at x, then y
+    let mut rows: Zeroizing<Vec<Vec<FieldElement>>> = Zeroizing::new(Vec::new());
+    for s in shares {
+        if s.level >= thresholds.len() {
+            return Err(Error::UnknownLevel { level: s.level });
+        }
+        let d = order(thresholds, s.level);
+        let mut row = Vec::with_capacity(k + 1);
+        let mut power = FieldElement::one();
+        for i in 0..k as u64 {
+            if i < d {
+                row.push(FieldElement::zero());
+            } else {
+                row.push(falling_factorial::<FieldElement>(i, d) * power);
+                power *= s.share.x;
+            }
+        }
+        row.push(s.share.y);
+        rows.push(row);
+    }
+
+    // Gauss-Jordan elimination, all coefficients have to be determined
+    for col in 0..k {
+        let pivot = (col..rows.len())
+            .find(|r| !rows[*r][col].is_zero())
+            .ok_or(Error::Unauthorized)?;
+        rows.swap(col, pivot);
+        let inv =
+            Option::<FieldElement>::from(rows[col][col].invert()).ok_or(Error::Unauthorized)?;
+        for c in col..=k {
+            rows[col][c] *= inv;
+        }
+        for r in 0..rows.len() {
+            if r != col && !rows[r][col].is_zero() {
+                let factor = rows[r][col];
+                for c in col..=k {
+                    let v = rows[col][c];
+                    rows[r][c] -= factor * v;
+                }
+            }
+        }
+    }
+    Ok(rows[0][k])
+}
+
+/// Thresholds have to be nonzero and strictly increasing
+fn check_thresholds(thresholds: &[u64]) -> Result<(), Error> {
+    if thresholds.is_empty() || thresholds[0] == 0 || thresholds.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(Error::InvalidThreshold);
+    }
+    Ok(())
+}
+
+/// The derivative the shares of a level are taken of, k_{level-1} and 0 for level 0
+fn order(thresholds: &[u64], level: usize) -> u64 {
+    if level == 0 {
+        0
+    } else {
+        thresholds[level - 1]
+    }
+}
@@ -0,0 +1,59 @@
+//! Hybrid sharing of large secrets: the payload is encrypted with XChaCha20-Poly1305 under a
+//! random 256-bit key, and only the key is shared.
+
+use crate::encoding::{reconstruct, split};
+use crate::error::Error;
+use crate::share::Share;
+use alloc::vec::Vec;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// Length of the shared key
+pub const KEY_BYTES: usize = 32;
+/// Length of the random XChaCha20 nonce
+pub const NONCE_BYTES: usize = 24;
+
+/// The encrypted payload, which can be stored publicly next to the shares
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Sealed {
+    pub nonce: [u8; NONCE_BYTES],
+    /// Ciphertext followed by the 16-byte Poly1305 tag
+    pub ciphertext: Vec<u8>,
+}
+
+/// Encrypt the secret under a random key and split the key into n shares per encoded element,
+/// t of which open the ciphertext
+pub fn seal<R: RngCore + CryptoRng>(
+    secret: &[u8],
+    t: u64,
+    n: u64,
+    rng: &mut R,
+) -> Result<(Sealed, Vec<Vec<Share>>), Error> {
+    let mut key = Zeroizing::new([0u8; KEY_BYTES]);
+    rng.fill_bytes(&mut key[..]);
+    let mut nonce = [0u8; NONCE_BYTES];
+    rng.fill_bytes(&mut nonce);
+    let ciphertext = XChaCha20Poly1305::new(key[..].into())
+        .encrypt(XNonce::from_slice(&nonce), secret)
+        .map_err(|_| Error::InvalidEncoding)?;
+    let key = Zeroizing::new(key.to_vec());
+    let shares = split(&*key, t, n, rng)?;
+    Ok((Sealed { nonce, ciphertext }, shares))
+}
+
+/// Reconstruct the key from the shares of each encoded element and decrypt the ciphertext
+/// Fails if the shares or the ciphertext are wrong
+pub fn open(sealed: &Sealed, shares: &[Vec<Share>]) -> Result<Vec<u8>, Error> {
+    let key: Zeroizing<Vec<u8>> = Zeroizing::new(reconstruct(shares)?);
+    if key.len() != KEY_BYTES {
+        return Err(Error::InvalidEncoding);
+    }
+    XChaCha20Poly1305::new(key[..].into())
+        .decrypt(XNonce::from_slice(&sealed.nonce), &sealed.ciphertext[..])
+        .map_err(|_| Error::DecryptionFailed)
+}
@@ -0,0 +1,100 @@
+//! Shamir secret sharing over the greatest 128-bit prime field, and questionnairs that recover
+//! a secret from the answers to a set of questions.
+//!
+//! Without the default `std` feature the crate is `no_std` and needs `alloc`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod additive;
+mod attempts;
+mod authenticated;
+mod beaver;
+mod commitment;
+#[cfg(any(feature = "ed25519", feature = "secp256k1"))]
+pub mod curves;
+#[cfg(feature = "vss")]
+pub mod dkg;
+mod encoding;
+#[cfg(feature = "envelope")]
+pub mod envelope;
+mod error;
+mod field;
+mod fixed;
+#[cfg(feature = "formats")]
+pub mod format;
+#[cfg(feature = "vss")]
+pub mod frost;
+pub mod gf256;
+mod hierarchical;
+#[cfg(feature = "hybrid")]
+pub mod hybrid;
+mod matrix;
+mod multi;
+#[cfg(feature = "net")]
+pub mod net;
+pub mod ntt;
+mod packed;
+mod polynomial;
+pub mod prelude;
+#[cfg(feature = "vss")]
+pub mod pvss;
+mod questionnair;
+mod replicated;
+#[cfg(feature = "service")]
+pub mod service;
+mod share;
+mod shares;
+#[cfg(feature = "std")]
+mod stream;
+pub mod test_vectors;
+#[cfg(feature = "vss")]
+pub mod threshold_encryption;
+#[cfg(feature = "vss")]
+pub mod vss;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod weighted;
+
+pub use additive::AdditiveSharing;
+pub use attempts::{AttemptGuard, BackoffPolicy};
+pub use authenticated::{
+    reconstruct_authenticated, AuthenticatedShare, AUTHENTICATED_SHARE_BYTES, CHECKSUM_BYTES,
+    GROUP_ID_BYTES,
+};
+pub use beaver::{mul_open, BeaverTriple, TripleDealer};
+pub use commitment::SecretCommitment;
+pub use encoding::{
+    reconstruct, split, split_bytes, split_checked, Secret, SecretEncoding, CHUNK_BYTES,
+};
+pub use error::Error;
+pub use ff::{Field, PrimeField};
+pub use field::{
+    check_randomness, random_element, FieldElement, FieldElementRepr, FIELD_ELEMENT_BYTES,
+    REPR_BYTES,
+};
+pub use fixed::{combine, deal};
+pub use hierarchical::{reconstruct_hierarchical, HierarchicalDealer, HierarchicalShare};
+pub use matrix::ShareMatrix;
+pub use multi::{combine_multi, deal_multi, MultiShare};
+pub use packed::PackedDealer;
+pub use polynomial::{
+    interpolate_group, lagrange_coefficients, reconstruct_with, Dealer, FixedSetReconstructor,
+    LagrangeCoefficients, Polynomial, SecretValidator,
+};
+pub use questionnair::{
+    answer, reconstruct_from_points, AnswerKdf, AnswerNormalizer, AnswerReport, GradeReport,
+    LocaleCode, Questionnair, QuestionnairBuilder, MAX_POW_DIFFICULTY,
+};
+pub use replicated::{ReplicatedShare, ReplicatedSharing};
+pub use share::{Share, SHARE_BYTES};
+pub use shares::{
+    FramedShare, ShareHealth, ShareMetadata, Shares, BINARY_FIELD_ID, BINARY_MAGIC, BINARY_VERSION,
+    CONTAINER_MAGIC, MAC_BYTES, MAC_KEY_BYTES, SPLIT_ID_BYTES,
+};
+#[cfg(feature = "std")]
+pub use stream::{CombineReader, SplitWriter};
+pub use weighted::{
+    combine_weighted, deal_weighted, reconstruct_weighted, split_weighted, WeightedShare,
+};
@@ -1,224 +1,236 @@
-use ff::Field;
-use ff::PrimeField;
-use rand_core::RngCore;
-use sha2::{Digest, Sha256};
-use std::convert::TryInto;
-
-/// This prime field has the greatest 128-bit prime as modulus. Because of the ff crate, each field
-/// element is 192bit (3*8 bytes) instead of 128 (2*8) bytes: take care when sampling random bytes.
-#[derive(PrimeField)]
-#[PrimeFieldModulus = "340282366920938463463374607431768211297"]
-#[PrimeFieldGenerator = "7"]
-#[PrimeFieldReprEndianness = "little"]
-struct FieldElement([u64; 3]);
-impl FieldElement {
-    /// Create a field element from a u64
-    fn new(v: u64) -> Self {
-        let mut bytes = [0u8; 3 * 8];
-        bytes[0..8].copy_from_slice(&v.to_le_bytes());
-        let repr = FieldElementRepr(bytes);
-        let elm: FieldElement = PrimeField::from_repr(repr).expect("can create field elm from u64");
-        elm
-    }
+use rand::rngs::OsRng;
+use secret_sharing::{
+    AnswerKdf, AnswerNormalizer, Dealer, FieldElement, Questionnair, Secret, Share, SHARE_BYTES,
+};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::io::{self, BufRead, Read, Write};
+use std::{env, fs, process};
+
+const USAGE: &str = "Usage:
+  sss split --threshold <t> --shares <n> [--out-dir <dir>] <secret-file>
+  sss combine [--out <file>] <share-file>..
+  sss questionnair create [--threshold <t>] <questions-file> <secret-file>
+  sss questionnair answer [--out <file>] <questionnair-file>
+
+split writes share-1.hex, .., share-n.hex with one hex encoded share per line.
+The questions file has one question and its answer per line, separated by a tab.
+The secret of a questionnair is 16 bytes below the field modulus, answers are stretched with
+Argon2id and the questionnair is printed as JSON. answer asks the questions on stderr, an empty answer skips the question.";
 
-    fn hash(x: &str) -> Self {
-        let mut bytes = [0u8; 3 * 8];
-
-        let mut to_hash = x.as_bytes().to_vec();
-        let max_fill = 2 * 8;
-        loop {
-            let mut hasher = Sha256::new();
-            hasher.update(&to_hash[..]);
-            let hash: [u8; 32] = hasher
-                .finalize()
-                .as_slice()
-                .try_into()
-                .expect("Should be a 256-bit hash");
-            bytes[..max_fill].clone_from_slice(&hash[..max_fill]);
-
-            // Rejection Sampling
-            let repr = FieldElementRepr(bytes);
-            if let Some(e) = PrimeField::from_repr(repr) {
-                return e;
-            }
-            to_hash = hash.to_vec();
-        }
+pub fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("split") => split(&args[1..]),
+        Some("combine") => combine(&args[1..]),
+        Some("questionnair") => match args.get(1).map(String::as_str) {
+            Some("create") => create_questionnair(&args[2..]),
+            Some("answer") => answer_questionnair(&args[2..]),
+            _ => Err(USAGE.into()),
+        },
+        _ => Err(USAGE.into()),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
     }
+}
 
-    fn random() -> Self {
-        let mut rng = rand::thread_rng();
-        let mut bytes = [0u8; 3 * 8];
-        let max_fill = 2 * 8;
-        loop {
-            rng.fill_bytes(&mut bytes[0..max_fill]);
-            let repr = FieldElementRepr(bytes);
-            if let Some(e) = PrimeField::from_repr(repr) {
-                return e;
+/// Options given as `--name value` and the remaining positional arguments
+struct Args {
+    options: Vec<(String, String)>,
+    positional: Vec<String>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Vec::new();
+        let mut positional = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Missing value for --{}", name))?;
+                options.push((name.to_string(), value.clone()));
+            } else {
+                positional.push(arg.clone());
             }
         }
+        Ok(Args {
+            options,
+            positional,
+        })
     }
-}
 
-/// Represents a Questionnair
-#[derive(Debug)]
-struct Questionnair {
-    questions: Vec<&'static str>,
-    tags: Vec<[u8; 32]>,
-    points: Vec<FieldElement>,
-}
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
 
-impl Questionnair {
-    /// Create random polynomial
-    /// Get Share
-    fn new(s: FieldElement, questions: Vec<&'static str>, answers: Vec<&'static str>) -> Self {
-        let degree = questions.len();
-        let polynomial = Polynomial::new(degree as u64, s);
-        let shares = polynomial.share(degree as u64);
-        let mut tags = Vec::new();
-        let mut points = Vec::new();
-
-        for ans in 0..degree {
-            let key = FieldElement::hash(&answers[ans]);
-            points.push(shares[ans].y + key);
-
-            let tag = tag_from_answer(answers[ans]);
-            tags.push(tag);
-        }
-        Questionnair {
-            questions,
-            tags,
-            points,
-        }
+    fn number(&self, name: &str) -> Result<Option<u64>, String> {
+        self.option(name)
+            .map(|v| v.parse().map_err(|_| format!("Invalid --{}: {}", name, v)))
+            .transpose()
     }
 }
 
-/// Generates Authenticity tag by H(H(a_i));
-fn tag_from_answer(ans: &'static str) -> [u8; 32] {
-    let mut hasher = Sha256::new();
-    hasher.update(ans);
-    let answer_hash = hasher.finalize_reset();
-    hasher.update(&answer_hash);
-    hasher
-        .finalize()
-        .as_slice()
-        .try_into()
-        .expect("Should be a 256-bit hash")
+fn split(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(args)?;
+    let t = args.number("threshold")?.ok_or(USAGE)?;
+    let n = args.number("shares")?.ok_or(USAGE)?;
+    if t < 2 || t > n {
+        return Err("Need 2 <= threshold <= shares".into());
+    }
+    let path = match args.positional.as_slice() {
+        [path] => path,
+        _ => return Err(USAGE.into()),
+    };
+    let secret = Secret::from_bytes(&read(path)?);
+    let dir = args.option("out-dir").unwrap_or(".");
+    let shares = secret.split(t, n, &mut OsRng)?;
+    for i in 0..n as usize {
+        let lines: String = shares
+            .iter()
+            .map(|element| to_hex(&element[i].to_bytes()) + "\n")
+            .collect();
+        let file = format!("{}/share-{}.hex", dir, i + 1);
+        fs::write(&file, lines).map_err(|e| format!("{}: {}", file, e))?;
+    }
+    Ok(())
 }
 
-/// Lets user answer the questionnair
-/// First check if answers are correct
-/// Compute shares by calculating keys and decrypt points
-/// interpolation of shares to get secret
-fn answer(questionnair: Questionnair, answers: Vec<&'static str>) -> Result<FieldElement, String> {
-    let mut shares: Vec<Share> = Vec::with_capacity(answers.len());
-    // check each answer is
-    for (i, ans) in answers.iter().enumerate() {
-        let tag = tag_from_answer(*ans);
-        if tag != questionnair.tags[i] {
-            return Err("Wrong answer".to_string());
-        } else {
-            // key to decrypt points
-            let key = FieldElement::hash(ans);
-            shares.push(Share {
-                // x point starts at 1, not 0 as f(0) is the secret
-                x: FieldElement::new(i as u64 + 1),
-                y: questionnair.points[i] - key,
-            });
+fn combine(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(args)?;
+    if args.positional.is_empty() {
+        return Err(USAGE.into());
+    }
+    let mut shares: Vec<Vec<Share>> = Vec::new();
+    for path in &args.positional {
+        let text = String::from_utf8(read(path)?).map_err(|_| format!("{}: not hex", path))?;
+        let participant = text
+            .lines()
+            .map(|line| {
+                let bytes = from_hex(line.trim())?;
+                let bytes = <[u8; SHARE_BYTES]>::try_from(bytes.as_slice())
+                    .map_err(|_| format!("Expected {} bytes per share", SHARE_BYTES))?;
+                Share::from_bytes(bytes).map_err(|e| e.to_string())
+            })
+            .collect::<Result<Vec<Share>, String>>()
+            .map_err(|e| format!("{}: {}", path, e))?;
+        if shares.is_empty() {
+            shares = vec![Vec::new(); participant.len()];
+        }
+        if participant.len() != shares.len() {
+            return Err(format!("{}: unexpected number of shares", path).into());
+        }
+        for (element, share) in shares.iter_mut().zip(participant) {
+            element.push(share);
         }
     }
-    let interpolated = Polynomial::reconstruct(&shares);
-    Ok(interpolated)
+    let secret = Secret::reconstruct(&shares)?;
+    write(args.option("out"), &secret.to_bytes())
 }
 
-/// Represents a polynomial over the finite field
-#[derive(Debug)]
-struct Polynomial {
-    degree: u64,
-    coefficients: Vec<FieldElement>,
-}
-
-/// Represents a point on the polynomial
-#[derive(Debug)]
-struct Share {
-    x: FieldElement,
-    y: FieldElement,
+fn create_questionnair(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(args)?;
+    let (questions_path, secret_path) = match args.positional.as_slice() {
+        [q, s] => (q, s),
+        _ => return Err(USAGE.into()),
+    };
+    let secret = <[u8; 16]>::try_from(read(secret_path)?.as_slice())
+        .map_err(|_| format!("{}: expected 16 bytes", secret_path))?;
+    let secret = FieldElement::from_bytes(secret)?;
+
+    let text = String::from_utf8(read(questions_path)?)
+        .map_err(|_| format!("{}: not UTF-8", questions_path))?;
+    let mut questions = Vec::new();
+    let mut answers = Vec::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let (q, a) = line
+            .split_once('\t')
+            .ok_or_else(|| format!("{}: expected question<TAB>answer", questions_path))?;
+        questions.push(q);
+        answers.push(a);
+    }
+    let t = args.number("threshold")?.unwrap_or(questions.len() as u64);
+    let dealer = Dealer::new(t, secret, &mut OsRng)?;
+    let normalizers = vec![AnswerNormalizer::default(); questions.len()];
+    let kdf = AnswerKdf::recommended(&mut OsRng);
+    let questionnair =
+        Questionnair::with_kdf(&dealer, questions, answers, normalizers, kdf, &mut OsRng)?;
+    let json = serde_json::to_string_pretty(&questionnair).map_err(|e| e.to_string())?;
+    println!("{}", json);
+    Ok(())
 }
 
-impl Polynomial {
-    /// Create random degree t-1 polynomial with f(0)=s
-    fn new(t: u64, s: FieldElement) -> Self {
-        let mut coef = vec![s];
-        for _ in 1..t - 1 {
-            let fe = FieldElement::random();
-            coef.push(fe);
-        }
-        coef.reverse();
-
-        Polynomial {
-            degree: t - 1,
-            coefficients: coef,
+fn answer_questionnair(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(args)?;
+    let path = match args.positional.as_slice() {
+        [path] => path,
+        _ => return Err(USAGE.into()),
+    };
+    let json = String::from_utf8(read(path)?).map_err(|e| format!("{}: {}", path, e))?;
+    let questionnair = Questionnair::from_json(&json).map_err(|e| format!("{}: {}", path, e))?;
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut answers = Vec::new();
+    for (i, question) in questionnair.questions().iter().enumerate() {
+        eprint!("{} ", question);
+        let answer = match lines.next() {
+            Some(line) => line.map_err(|e| e.to_string())?,
+            None => break,
+        };
+        if !answer.is_empty() {
+            answers.push((i, answer));
         }
     }
-
-    /// Evaluate polynomial at f(x)
-    fn evaluate(&self, x: &FieldElement) -> FieldElement {
-        let mut result = self.coefficients[0];
-        for i in 1..self.degree as usize {
-            result = result * x + self.coefficients[i];
-        }
-        result
+    let answers: Vec<(usize, &str)> = answers.iter().map(|(i, a)| (*i, a.as_str())).collect();
+    let report = questionnair.answer_report(&answers)?;
+    match report.secret {
+        Some(secret) => write(args.option("out"), &secret.to_bytes()),
+        None => Err(format!(
+            "Wrong answers to questions {:?}, {} of {} needed answers are correct",
+            report.failed.iter().map(|i| i + 1).collect::<Vec<_>>(),
+            report.passed.len(),
+            questionnair.threshold()
+        )
+        .into()),
     }
+}
 
-    /// Evaluate polynomial at f(1), .., f(n)
-    fn share(&self, n: u64) -> Vec<Share> {
-        let mut shares = Vec::new();
-        for i in 1..=n {
-            let x = FieldElement::new(i);
-            let y = self.evaluate(&x);
-            shares.push(Share { x, y })
-        }
-        shares
+/// Reads a file, or stdin for "-"
+fn read(path: &str) -> Result<Vec<u8>, String> {
+    if path == "-" {
+        let mut bytes = Vec::new();
+        io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|e| e.to_string())?;
+        return Ok(bytes);
     }
+    fs::read(path).map_err(|e| format!("{}: {}", path, e))
+}
 
-    /// Compute f(0) by interpolation
-    fn reconstruct(shares: &[Share]) -> FieldElement {
-        // how do I use a closure?
-        // let lagrange_basis_eval = |j: usize, x: FieldElement| unimplemented!();
-        let num_keys = shares.len();
-        let mut val = FieldElement::zero();
-        for i in 0..num_keys - 1 {
-            let y = shares[i].y;
-            let mut d = FieldElement::one();
-            let mut n = FieldElement::one();
-            for j in 0..num_keys - 1 {
-                if i != j {
-                    d *= -shares[j].x;
-                    n *= shares[i].x - shares[j].x;
-                }
-            }
-            val += y * d * n.invert().unwrap();
-        }
-        val
+/// Writes to the file, or stdout if there is none
+fn write(path: Option<&str>, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+    match path {
+        Some(path) => Ok(fs::write(path, bytes).map_err(|e| format!("{}: {}", path, e))?),
+        None => Ok(io::stdout().write_all(bytes)?),
     }
 }
 
-pub fn main() {
-    let answers = vec!["d", "e", "d", "e", "a"];
-    let secret = FieldElement::new(42);
-
-    println!("Creating new Questionnair");
-    let questionair = Questionnair::new(secret, vec!["a", "b", "c", "b", "c"], answers.clone());
-
-    println!("Answering Questions");
-    let interpolated = answer(questionair, answers).unwrap();
-
-    println!("Checking secret is correct:");
-    println!(
-        "  {}",
-        if interpolated == secret {
-            "YAY HURRAY"
-        } else {
-            "NOPE TRY AGAIN"
-        }
-    );
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err("Invalid hex".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| "Invalid hex".to_string()))
+        .collect()
 }
@@ -0,0 +1,169 @@
+use crate::error::Error;
+use crate::field::FieldElement;
+use crate::polynomial::{FixedSetReconstructor, Polynomial};
+use crate::share::Share;
+use alloc::vec::Vec;
+use core::fmt;
+use rand_core::{CryptoRng, RngCore};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Shares of many secrets among the same n participants, storing the x-coordinates once and
+/// the y-values contiguously per secret
+pub struct ShareMatrix {
+    xs: Vec<FieldElement>,
+    /// ys[j * n + i] is participant i's y-value for secret j
+    ys: Vec<FieldElement>,
+}
+
+impl Zeroize for ShareMatrix {
+    fn zeroize(&mut self) {
+        self.xs.zeroize();
+        self.ys.zeroize();
+    }
+}
+
+impl Drop for ShareMatrix {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for ShareMatrix {}
+
+/// Prints only the x-coordinates, the y-values are the shares
+impl fmt::Debug for ShareMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShareMatrix")
+            .field("xs", &self.xs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ShareMatrix {
+    /// Split each secret into n shares, t of which reconstruct it
+    /// Fails if n is 0 or t > n
+    pub fn split<R: RngCore + CryptoRng>(
+        secrets: &[FieldElement],
+        t: u64,
+        n: u64,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        if n == 0 || t > n {
+            return Err(Error::InvalidThreshold);
+        }
+        let xs: Vec<FieldElement> = (1..=n).map(FieldElement::new).collect();
+        let mut ys = Vec::with_capacity(secrets.len() * n as usize);
+        for s in secrets {
+            let polynomial = Polynomial::new(t, *s, rng)?;
+            ys.extend(polynomial.evaluate_batch(&xs));
+        }
+        Ok(ShareMatrix { xs, ys })
+    }
+
+    /// The number of secrets
+    pub fn secrets(&self) -> usize {
+        self.ys.len() / self.xs.len()
+    }
+
+    /// The shares of secret j
+    pub fn secret_shares(&self, j: usize) -> Vec<Share> {
+        let n = self.xs.len();
+        self.xs
+            .iter()
+            .zip(&self.ys[j * n..(j + 1) * n])
+            .map(|(x, y)| Share { x: *x, y: *y })
+            .collect()
+    }
+
+    /// The shares of participant i, one per secret
+    pub fn participant_shares(&self, i: usize) -> Vec<Share> {
+        self.ys
+            .iter()
+            .skip(i)
+            .step_by(self.xs.len())
+            .map(|y| Share {
+                x: self.xs[i],
+                y: *y,
+            })
+            .collect()
+    }
+
+    /// Reconstruct all secrets from the shares of the given participants, indices into 0..n
+    pub fn reconstruct(&self, participants: &[usize]) -> Result<Vec<FieldElement>, Error> {
+        let n = self.xs.len();
+        let xs = participants
+            .iter()
+            .map(|&index| {
+                self.xs
+                    .get(index)
+                    .copied()
+                    .ok_or(Error::UnknownParty { index })
+            })
+            .collect::<Result<Vec<FieldElement>, Error>>()?;
+        let reconstructor = FixedSetReconstructor::new(&xs)?;
+        (0..self.secrets())
+            .map(|j| {
+                let ys: Vec<FieldElement> =
+                    participants.iter().map(|i| self.ys[j * n + i]).collect();
+                reconstructor.reconstruct(&ys)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::size_of;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn reconstructs_the_same_secrets_as_separate_shares() {
+        let secrets: Vec<FieldElement> = (1..=4).map(|s| FieldElement::new(s * 1000)).collect();
+        let matrix =
+            ShareMatrix::split(&secrets, 3, 5, &mut ChaCha20Rng::from_seed([4; 32])).unwrap();
+        assert_eq!(matrix.secrets(), secrets.len());
+
+        let participants = [0, 2, 4];
+        let naive: Vec<FieldElement> = (0..matrix.secrets())
+            .map(|j| {
+                let shares = matrix.secret_shares(j);
+                let subset: Vec<Share> = participants.iter().map(|i| shares[*i].clone()).collect();
+                Polynomial::reconstruct(&subset).unwrap()
+            })
+            .collect();
+        assert_eq!(naive, secrets);
+        assert_eq!(matrix.reconstruct(&participants).unwrap(), secrets);
+
+        for (j, share) in matrix.participant_shares(2).iter().enumerate() {
+            assert_eq!(
+                (share.x, share.y),
+                (matrix.xs[2], matrix.secret_shares(j)[2].y)
+            );
+        }
+
+        // one x per participant instead of one per share
+        let naive_bytes = secrets.len() * 5 * size_of::<Share>();
+        let matrix_bytes = (matrix.xs.len() + matrix.ys.len()) * size_of::<FieldElement>();
+        assert!(matrix_bytes < naive_bytes);
+    }
+
+    #[test]
+    fn rejects_bad_thresholds_and_participants() {
+        let secrets = [FieldElement::new(1), FieldElement::new(2)];
+        let mut rng = ChaCha20Rng::from_seed([4; 32]);
+        for (t, n) in [(2, 0), (4, 3)] {
+            assert_eq!(
+                ShareMatrix::split(&secrets, t, n, &mut rng).unwrap_err(),
+                Error::InvalidThreshold
+            );
+        }
+        let matrix = ShareMatrix::split(&secrets, 2, 3, &mut rng).unwrap();
+        assert_eq!(
+            matrix.reconstruct(&[0, 3]).unwrap_err(),
+            Error::UnknownParty { index: 3 }
+        );
+        assert_eq!(matrix.reconstruct(&[0, 2]).unwrap(), secrets);
+    }
+}
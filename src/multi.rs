@@ -0,0 +1,75 @@
+use crate::error::Error;
+use crate::field::FieldElement;
+use crate::polynomial::{reconstruct_with, LagrangeCoefficients, Polynomial};
+use alloc::vec::Vec;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A participant's shares of several secrets, one y-value per secret at the same x
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MultiShare {
+    pub x: FieldElement,
+    /// f_k(x) for the polynomial of the k-th secret
+    pub ys: Vec<FieldElement>,
+}
+
+impl Zeroize for MultiShare {
+    fn zeroize(&mut self) {
+        self.x.zeroize();
+        self.ys.zeroize();
+    }
+}
+
+impl Drop for MultiShare {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for MultiShare {}
+
+/// Split each secret with its own degree t-1 polynomial among the same n participants at
+/// x = 1..=n, so each participant holds a single `MultiShare`
+pub fn deal_multi<R: RngCore + CryptoRng>(
+    secrets: &[FieldElement],
+    t: u64,
+    n: u64,
+    rng: &mut R,
+) -> Result<Vec<MultiShare>, Error> {
+    let mut shares: Vec<MultiShare> = (1..=n)
+        .map(|x| MultiShare {
+            x: FieldElement::new(x),
+            ys: Vec::with_capacity(secrets.len()),
+        })
+        .collect();
+    for s in secrets {
+        let ys = Polynomial::new(t, *s, rng)?.evaluate_range(n);
+        for (share, y) in shares.iter_mut().zip(ys) {
+            share.ys.push(y);
+        }
+    }
+    Ok(shares)
+}
+
+/// Reconstruct all secrets from the `MultiShare`s of at least t participants, computing the
+/// Lagrange coefficients once for all of them
+pub fn combine_multi(shares: &[MultiShare]) -> Result<Vec<FieldElement>, Error> {
+    let k = match shares.first() {
+        Some(share) => share.ys.len(),
+        None => return Err(Error::InsufficientShares { needed: 1, got: 0 }),
+    };
+    if shares.iter().any(|share| share.ys.len() != k) {
+        return Err(Error::LengthMismatch);
+    }
+    let xs: Vec<FieldElement> = shares.iter().map(|share| share.x).collect();
+    let coefficients = LagrangeCoefficients::precompute(&xs)?;
+    (0..k)
+        .map(|i| {
+            let ys: Vec<FieldElement> = shares.iter().map(|share| share.ys[i]).collect();
+            reconstruct_with(&coefficients, &ys)
+        })
+        .collect()
+}
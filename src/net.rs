@@ -0,0 +1,577 @@
+//! Dealing to and collecting from share holders over TCP, with tokio.
+//!
+//! Every message is a frame of a 4-byte big-endian length and at most `MAX_FRAME_BYTES` of
+//! payload, whose first byte is the message type. A connection starts with the client's hello,
+//! the range of protocol versions it speaks, which the holder answers with the highest version
+//! both speak or rejects. Then the client sends one request: the dealer deposits a share, or
+//! the combiner asks for the share of a group id. Every step of a connection has to finish
+//! within the timeout.
+//!
+//! Shares only travel as `Envelope`s: the dealer seals each share to its holder's X25519 key,
+//! and the holder reseals it to the X25519 key the combiner names in its request, if that key
+//! is one of the combiners the holder was configured with. The sender signs the group id and
+//! the envelope with its Ed25519 key, so a holder only stores deposits of its dealer and a
+//! combiner only accepts replies from the holders it knows. A holder keeps the first deposit
+//! of a group id and rejects later ones.
+
+use crate::authenticated::{reconstruct_authenticated, AuthenticatedShare, GROUP_ID_BYTES};
+use crate::envelope::{
+    Envelope, PublicKey, SigningKey, StaticSecret, VerifyingKey, ENVELOPE_BYTES,
+};
+use crate::error::Error;
+use crate::field::FieldElement;
+use crate::share::Share;
+use ed25519_dalek::{Signature, Signer};
+use rand_core::OsRng;
+use std::collections::hash_map::{Entry, HashMap};
+use std::convert::TryInto;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use zeroize::Zeroizing;
+
+/// The newest protocol version, spoken by this crate
+pub const PROTOCOL_VERSION: u16 = 2;
+/// The oldest protocol version this crate speaks, version 1 sent shares in plaintext
+pub const MIN_PROTOCOL_VERSION: u16 = 2;
+/// Upper bound on the payload of a frame, longer frames are rejected before reading them
+pub const MAX_FRAME_BYTES: usize = 1 << 16;
+/// Length of a signed envelope, group id || envelope || Ed25519 signature
+pub const SIGNED_ENVELOPE_BYTES: usize = GROUP_ID_BYTES + ENVELOPE_BYTES + 64;
+
+const HELLO: u8 = 0;
+const VERSION: u8 = 1;
+const UNSUPPORTED: u8 = 2;
+const DEPOSIT: u8 = 3;
+const STORED: u8 = 4;
+const REQUEST: u8 = 5;
+const REPLY: u8 = 6;
+const NOT_FOUND: u8 = 7;
+const REJECTED: u8 = 8;
+
+fn invalid_data(e: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Write the payload as one frame
+pub async fn write_frame<W: AsyncWrite + Unpin>(writer: &mut W, payload: &[u8]) -> io::Result<()> {
+    if payload.len() > MAX_FRAME_BYTES {
+        return Err(invalid_data(Error::InvalidEncoding));
+    }
+    writer
+        .write_all(&(payload.len() as u32).to_be_bytes())
+        .await?;
+    writer.write_all(payload).await?;
+    writer.flush().await
+}
+
+/// Read the payload of one frame
+pub async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Zeroizing<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len).await?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Err(invalid_data(Error::InvalidEncoding));
+    }
+    let mut payload = Zeroizing::new(vec![0u8; len]);
+    reader.read_exact(&mut payload).await?;
+    Ok(payload)
+}
+
+/// Holds the shares deposited by its dealer, one per group id, and hands them to its combiners
+pub struct ShareHolder {
+    /// Opens the envelopes of deposits
+    secret: StaticSecret,
+    /// Signs the envelopes of replies
+    identity: SigningKey,
+    dealer: VerifyingKey,
+    combiners: Vec<PublicKey>,
+    shares: Mutex<HashMap<[u8; GROUP_ID_BYTES], AuthenticatedShare>>,
+}
+
+impl fmt::Debug for ShareHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShareHolder")
+            .field("public_key", &self.public_key())
+            .field("identity", &self.identity.verifying_key())
+            .field("dealer", &self.dealer)
+            .field("combiners", &self.combiners)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ShareHolder {
+    /// A holder without shares, accepting deposits signed by the dealer and sealed to the
+    /// secret key and answering requests of the combiners
+    pub fn new(
+        secret: StaticSecret,
+        identity: SigningKey,
+        dealer: VerifyingKey,
+        combiners: Vec<PublicKey>,
+    ) -> Self {
+        ShareHolder {
+            secret,
+            identity,
+            dealer,
+            combiners,
+            shares: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The key dealers seal shares to
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from(&self.secret)
+    }
+
+    /// The key combiners verify replies with
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.identity.verifying_key()
+    }
+
+    /// The share deposited for the group id, if any
+    pub fn share(&self, group_id: &[u8; GROUP_ID_BYTES]) -> Option<AuthenticatedShare> {
+        self.shares
+            .lock()
+            .expect("not poisoned")
+            .get(group_id)
+            .cloned()
+    }
+
+    /// Serve every connection accepted on the listener in its own task
+    pub async fn serve(
+        self: Arc<Self>,
+        listener: TcpListener,
+        timeout: Duration,
+    ) -> io::Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let holder = self.clone();
+            tokio::spawn(async move {
+                // a failed connection only concerns its client
+                let _ = holder.handle(stream, timeout).await;
+            });
+        }
+    }
+
+    /// Negotiate the version and answer the request of one connection
+    pub async fn handle(&self, mut stream: TcpStream, timeout: Duration) -> io::Result<()> {
+        let hello = within(timeout, read_frame(&mut stream)).await?;
+        let version = match hello[..] {
+            [HELLO, a, b, c, d] => {
+                negotiate(u16::from_be_bytes([a, b]), u16::from_be_bytes([c, d]))
+            }
+            _ => None,
+        };
+        let version = match version {
+            Some(version) => version,
+            None => {
+                within(timeout, write_frame(&mut stream, &[UNSUPPORTED])).await?;
+                return Err(invalid_data(Error::UnsupportedVersion {
+                    version: PROTOCOL_VERSION,
+                }));
+            }
+        };
+        let mut reply = vec![VERSION];
+        reply.extend_from_slice(&version.to_be_bytes());
+        within(timeout, write_frame(&mut stream, &reply)).await?;
+
+        let request = within(timeout, read_frame(&mut stream)).await?;
+        let reply = match request.split_first() {
+            Some((&DEPOSIT, bytes)) => match self.deposit(bytes) {
+                Ok(()) => vec![STORED],
+                Err(_) => vec![REJECTED],
+            },
+            Some((&REQUEST, bytes)) => match self.reply(bytes) {
+                Ok(Some(signed)) => {
+                    let mut reply = vec![REPLY];
+                    reply.extend_from_slice(&signed);
+                    reply
+                }
+                Ok(None) => vec![NOT_FOUND],
+                Err(_) => vec![REJECTED],
+            },
+            _ => vec![REJECTED],
+        };
+        within(timeout, write_frame(&mut stream, &reply)).await
+    }
+
+    /// Open a deposit of the dealer and store it unless the group id already has a share
+    fn deposit(&self, bytes: &[u8]) -> Result<(), Error> {
+        let (group_id, envelope) = open_signed(DEPOSIT_DOMAIN, bytes, &self.dealer)?;
+        let share = Share::open_with(&envelope, &self.secret, &self.dealer)?;
+        match self.shares.lock().expect("not poisoned").entry(group_id) {
+            Entry::Occupied(_) => Err(Error::InvalidEncoding),
+            Entry::Vacant(entry) => {
+                entry.insert(AuthenticatedShare::new(share, group_id, envelope.threshold));
+                Ok(())
+            }
+        }
+    }
+
+    /// The signed envelope of the share of the group id, sealed to the combiner of the request
+    fn reply(&self, bytes: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        if bytes.len() != GROUP_ID_BYTES + 32 {
+            return Err(Error::InvalidEncoding);
+        }
+        let (group_id, combiner) = bytes.split_at(GROUP_ID_BYTES);
+        let group_id: [u8; GROUP_ID_BYTES] = group_id.try_into().expect("length checked");
+        let combiner: [u8; 32] = combiner.try_into().expect("length checked");
+        let combiner = self
+            .combiners
+            .iter()
+            .find(|key| key.as_bytes() == &combiner)
+            .ok_or(Error::InvalidSignature)?;
+        let share = match self.share(&group_id) {
+            Some(share) => share,
+            None => return Ok(None),
+        };
+        let envelope = share.share.seal_to(
+            combiner,
+            &self.identity,
+            index_of(&share.share.x)?,
+            share.threshold,
+            &mut OsRng,
+        )?;
+        Ok(Some(sign(
+            REPLY_DOMAIN,
+            &group_id,
+            &envelope,
+            &self.identity,
+        )))
+    }
+}
+
+/// Seal share i to holder i and deposit it, failing on the first holder that does not store it
+/// Each holder is given with the key shares are sealed to, its `ShareHolder::public_key`
+pub async fn distribute<A: ToSocketAddrs>(
+    holders: &[(A, PublicKey)],
+    shares: &[AuthenticatedShare],
+    dealer: &SigningKey,
+    timeout: Duration,
+) -> io::Result<()> {
+    if holders.len() != shares.len() {
+        return Err(invalid_data(Error::LengthMismatch));
+    }
+    let mut deposits = Vec::with_capacity(shares.len());
+    for ((_, public_key), share) in holders.iter().zip(shares) {
+        let envelope = share
+            .share
+            .seal_to(
+                public_key,
+                dealer,
+                index_of(&share.share.x).map_err(invalid_data)?,
+                share.threshold,
+                &mut OsRng,
+            )
+            .map_err(invalid_data)?;
+        deposits.push(sign(DEPOSIT_DOMAIN, &share.group_id, &envelope, dealer));
+    }
+    for (index, ((holder, _), deposit)) in holders.iter().zip(deposits).enumerate() {
+        let mut stream = connect(holder, timeout).await?;
+        let mut request = vec![DEPOSIT];
+        request.extend_from_slice(&deposit);
+        within(timeout, write_frame(&mut stream, &request)).await?;
+        let reply = within(timeout, read_frame(&mut stream)).await?;
+        if reply[..] != [STORED] {
+            return Err(invalid_data(Error::CorruptShare { index }));
+        }
+    }
+    Ok(())
+}
+
+/// Ask the holders in order for their share of the group, sealed to the combiner's key, until
+/// threshold valid ones are collected, skipping holders that are unreachable, time out or have
+/// no valid share
+/// The threshold is the one the combiner expects, shares dealt with another are skipped, so a
+/// holder cannot claim a lower one
+/// Each holder is given with the key its replies are signed with, its
+/// `ShareHolder::verifying_key`
+pub async fn collect<A: ToSocketAddrs>(
+    holders: &[(A, VerifyingKey)],
+    group_id: [u8; GROUP_ID_BYTES],
+    threshold: u64,
+    combiner: &StaticSecret,
+    timeout: Duration,
+) -> io::Result<Vec<AuthenticatedShare>> {
+    if threshold == 0 {
+        return Err(invalid_data(Error::InvalidThreshold));
+    }
+    let mut shares: Vec<AuthenticatedShare> = Vec::new();
+    for (holder, verifying_key) in holders {
+        let share = match request(holder, verifying_key, &group_id, combiner, timeout).await {
+            Ok(Some(share)) => share,
+            Ok(None) | Err(_) => continue,
+        };
+        if !share.verify()
+            || share.group_id != group_id
+            || share.threshold != threshold
+            || shares.iter().any(|s| s.share.x == share.share.x)
+        {
+            continue;
+        }
+        shares.push(share);
+        if shares.len() as u64 >= threshold {
+            return Ok(shares);
+        }
+    }
+    Err(invalid_data(Error::InsufficientShares {
+        needed: threshold as usize,
+        got: shares.len(),
+    }))
+}
+
+/// Collect threshold shares of the group from the holders and reconstruct the secret
+pub async fn combine<A: ToSocketAddrs>(
+    holders: &[(A, VerifyingKey)],
+    group_id: [u8; GROUP_ID_BYTES],
+    threshold: u64,
+    combiner: &StaticSecret,
+    timeout: Duration,
+) -> io::Result<FieldElement> {
+    let shares = collect(holders, group_id, threshold, combiner, timeout).await?;
+    reconstruct_authenticated(&shares).map_err(invalid_data)
+}
+
+/// The holder's share of the group, or None if it has none
+async fn request<A: ToSocketAddrs>(
+    holder: &A,
+    verifying_key: &VerifyingKey,
+    group_id: &[u8; GROUP_ID_BYTES],
+    combiner: &StaticSecret,
+    timeout: Duration,
+) -> io::Result<Option<AuthenticatedShare>> {
+    let mut stream = connect(holder, timeout).await?;
+    let mut request = vec![REQUEST];
+    request.extend_from_slice(group_id);
+    request.extend_from_slice(PublicKey::from(combiner).as_bytes());
+    within(timeout, write_frame(&mut stream, &request)).await?;
+    let reply = within(timeout, read_frame(&mut stream)).await?;
+    match reply.split_first() {
+        Some((&REPLY, bytes)) => {
+            let (id, envelope) =
+                open_signed(REPLY_DOMAIN, bytes, verifying_key).map_err(invalid_data)?;
+            if id != *group_id {
+                return Err(invalid_data(Error::UnexpectedMessage));
+            }
+            let share =
+                Share::open_with(&envelope, combiner, verifying_key).map_err(invalid_data)?;
+            Ok(Some(AuthenticatedShare::new(share, id, envelope.threshold)))
+        }
+        Some((&NOT_FOUND, [])) => Ok(None),
+        _ => Err(invalid_data(Error::UnexpectedMessage)),
+    }
+}
+
+const DEPOSIT_DOMAIN: &[u8] = b"polynomials net deposit";
+const REPLY_DOMAIN: &[u8] = b"polynomials net reply";
+
+/// group id || envelope || signature over the domain, the group id and the envelope
+fn sign(
+    domain: &[u8],
+    group_id: &[u8; GROUP_ID_BYTES],
+    envelope: &Envelope,
+    key: &SigningKey,
+) -> Vec<u8> {
+    let mut signed = Vec::with_capacity(SIGNED_ENVELOPE_BYTES);
+    signed.extend_from_slice(group_id);
+    signed.extend_from_slice(&envelope.to_bytes());
+    let mut message = domain.to_vec();
+    message.extend_from_slice(&signed);
+    signed.extend_from_slice(&key.sign(&message).to_bytes());
+    signed
+}
+
+/// Verify a signed envelope written by `sign` and read its group id and envelope
+fn open_signed(
+    domain: &[u8],
+    bytes: &[u8],
+    key: &VerifyingKey,
+) -> Result<([u8; GROUP_ID_BYTES], Envelope), Error> {
+    if bytes.len() != SIGNED_ENVELOPE_BYTES {
+        return Err(Error::InvalidEncoding);
+    }
+    let (signed, signature) = bytes.split_at(GROUP_ID_BYTES + ENVELOPE_BYTES);
+    let mut message = domain.to_vec();
+    message.extend_from_slice(signed);
+    let signature: [u8; 64] = signature.try_into().expect("length checked");
+    key.verify_strict(&message, &Signature::from_bytes(&signature))
+        .map_err(|_| Error::InvalidSignature)?;
+    let (group_id, envelope) = signed.split_at(GROUP_ID_BYTES);
+    Ok((
+        group_id.try_into().expect("length checked"),
+        Envelope::from_bytes(envelope)?,
+    ))
+}
+
+/// The envelope index of a share, its x as an integer
+fn index_of(x: &FieldElement) -> Result<u64, Error> {
+    let bytes = x.to_bytes();
+    if bytes[8..].iter().any(|&b| b != 0) {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(u64::from_le_bytes(bytes[..8].try_into().expect("8 bytes")))
+}
+
+/// Connect and negotiate the protocol version
+async fn connect<A: ToSocketAddrs>(holder: &A, timeout: Duration) -> io::Result<TcpStream> {
+    let mut stream = within(timeout, TcpStream::connect(holder)).await?;
+    let mut hello = vec![HELLO];
+    hello.extend_from_slice(&MIN_PROTOCOL_VERSION.to_be_bytes());
+    hello.extend_from_slice(&PROTOCOL_VERSION.to_be_bytes());
+    within(timeout, write_frame(&mut stream, &hello)).await?;
+    let reply = within(timeout, read_frame(&mut stream)).await?;
+    match reply[..] {
+        [VERSION, a, b]
+            if (MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&u16::from_be_bytes([a, b])) =>
+        {
+            Ok(stream)
+        }
+        [UNSUPPORTED] => Err(invalid_data(Error::UnsupportedVersion {
+            version: PROTOCOL_VERSION,
+        })),
+        _ => Err(invalid_data(Error::UnexpectedMessage)),
+    }
+}
+
+/// The highest version in both min..=max and the versions this crate speaks
+fn negotiate(min: u16, max: u16) -> Option<u16> {
+    let version = max.min(PROTOCOL_VERSION);
+    if version >= min.max(MIN_PROTOCOL_VERSION) {
+        Some(version)
+    } else {
+        None
+    }
+}
+
+async fn within<T, F: Future<Output = io::Result<T>>>(timeout: Duration, f: F) -> io::Result<T> {
+    tokio::time::timeout(timeout, f)
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "timed out"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polynomial::Polynomial;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    type Holders = (
+        Vec<(std::net::SocketAddr, PublicKey)>,
+        Vec<(std::net::SocketAddr, VerifyingKey)>,
+    );
+
+    /// Serve three holders of the dealer answering the combiner, with the addresses and keys
+    /// the dealer and combiner use for them
+    async fn spawn_holders(dealer: &SigningKey, combiner: &StaticSecret) -> Holders {
+        let mut holders = Vec::new();
+        let mut verifying_keys = Vec::new();
+        for i in 0..3u8 {
+            let holder = Arc::new(ShareHolder::new(
+                StaticSecret::from([10 + i; 32]),
+                SigningKey::from_bytes(&[20 + i; 32]),
+                dealer.verifying_key(),
+                vec![PublicKey::from(combiner)],
+            ));
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            holders.push((addr, holder.public_key()));
+            verifying_keys.push((addr, holder.verifying_key()));
+            tokio::spawn(holder.serve(listener, TIMEOUT));
+        }
+        (holders, verifying_keys)
+    }
+
+    fn shares(secret: FieldElement, group_id: [u8; GROUP_ID_BYTES]) -> Vec<AuthenticatedShare> {
+        let mut rng = ChaCha20Rng::from_seed([4; 32]);
+        Polynomial::new(2, secret, &mut rng)
+            .unwrap()
+            .share(3)
+            .into_iter()
+            .map(|share| AuthenticatedShare::new(share, group_id, 2))
+            .collect()
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn deposits_and_replies_are_authenticated() {
+        runtime().block_on(async {
+            let dealer = SigningKey::from_bytes(&[1; 32]);
+            let combiner = StaticSecret::from([2; 32]);
+            let stranger = StaticSecret::from([3; 32]);
+            let (holders, verifying_keys) = spawn_holders(&dealer, &combiner).await;
+
+            let secret = FieldElement::new(42);
+            let group_id = [7; GROUP_ID_BYTES];
+            let shares = shares(secret, group_id);
+            distribute(&holders, &shares, &dealer, TIMEOUT)
+                .await
+                .unwrap();
+            let combined = combine(&verifying_keys, group_id, 2, &combiner, TIMEOUT).await;
+            assert_eq!(combined.unwrap(), secret);
+
+            // deposits never overwrite, whoever signs them
+            assert!(distribute(&holders, &shares, &dealer, TIMEOUT)
+                .await
+                .is_err());
+            let other = [8; GROUP_ID_BYTES];
+            let forged: Vec<AuthenticatedShare> = shares
+                .iter()
+                .map(|s| AuthenticatedShare::new(s.share.clone(), other, 2))
+                .collect();
+            let impostor = SigningKey::from_bytes(&[5; 32]);
+            assert!(distribute(&holders, &forged, &impostor, TIMEOUT)
+                .await
+                .is_err());
+            assert!(combine(&verifying_keys, other, 2, &combiner, TIMEOUT)
+                .await
+                .is_err());
+
+            // only configured combiners are answered
+            assert!(combine(&verifying_keys, group_id, 2, &stranger, TIMEOUT)
+                .await
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn replies_with_another_threshold_are_skipped() {
+        runtime().block_on(async {
+            let dealer = SigningKey::from_bytes(&[1; 32]);
+            let combiner = StaticSecret::from([2; 32]);
+            let (holders, verifying_keys) = spawn_holders(&dealer, &combiner).await;
+            let secret = FieldElement::new(42);
+            let group_id = [7; GROUP_ID_BYTES];
+            let mut shares = shares(secret, group_id);
+            // the first holder claims a threshold of 1, so its y alone would be the secret
+            shares[0] = AuthenticatedShare::new(shares[0].share.clone(), group_id, 1);
+            distribute(&holders, &shares, &dealer, TIMEOUT)
+                .await
+                .unwrap();
+            let collected = collect(&verifying_keys, group_id, 2, &combiner, TIMEOUT)
+                .await
+                .unwrap();
+            assert_eq!(collected.len(), 2);
+            assert!(collected.iter().all(|s| s.threshold == 2));
+            let combined = combine(&verifying_keys, group_id, 2, &combiner, TIMEOUT).await;
+            assert_eq!(combined.unwrap(), secret);
+            assert!(
+                combine(&verifying_keys[..2], group_id, 2, &combiner, TIMEOUT)
+                    .await
+                    .is_err()
+            );
+        });
+    }
+}
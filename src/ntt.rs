@@ -0,0 +1,211 @@
+//! Radix-2 number theoretic transforms, evaluating and interpolating polynomials of degree below
+//! n on the n-th roots of unity in O(n log n) instead of O(n^2).
+//!
+//! The transforms need a subgroup of order n = 2^k, so a field with a large 2-adicity S. The
+//! default `FieldElement` has S = 5, `NttScalar` is the scalar field of BLS12-381 with S = 32.
+//! `Polynomial` multiplication and `Polynomial::from_points` switch to the transforms above
+//! `NTT_CUTOFF` coefficients when the field supports the size.
+
+use crate::error::Error;
+use alloc::vec::Vec;
+use ff::PrimeField;
+use zeroize::Zeroize;
+
+pub use scalar::{NttScalar, NttScalarRepr};
+
+/// Number of coefficients from which multiplication and interpolation use the transforms
+pub const NTT_CUTOFF: usize = 64;
+
+// ff_derive generates items that clash with those of other fields in the same module, and a
+// Montgomery reduction taking 2 * 4 limbs
+#[allow(clippy::too_many_arguments)]
+mod scalar {
+    use ff::PrimeField;
+    use zeroize::Zeroize;
+
+    /// The scalar field of BLS12-381, of prime order
+    /// r = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
+    /// with 2^32 dividing r - 1, so it has roots of unity of every order up to 2^32
+    #[derive(PrimeField)]
+    #[PrimeFieldModulus = "52435875175126190479447740508185965837690552500527637822603658699938581184513"]
+    #[PrimeFieldGenerator = "7"]
+    #[PrimeFieldReprEndianness = "little"]
+    pub struct NttScalar([u64; 4]);
+
+    /// NttScalar is Copy and so cannot zeroize on drop, containers of secret scalars do
+    impl Zeroize for NttScalar {
+        fn zeroize(&mut self) {
+            self.0.zeroize();
+        }
+    }
+}
+
+/// A primitive n-th root of unity, for n a power of two up to 2^S
+pub fn root_of_unity<F: PrimeField>(n: usize) -> Result<F, Error> {
+    if !n.is_power_of_two() || n.trailing_zeros() > F::S {
+        return Err(Error::UnsupportedDomain { size: n });
+    }
+    let mut root = F::root_of_unity();
+    for _ in n.trailing_zeros()..F::S {
+        root = root.square();
+    }
+    Ok(root)
+}
+
+/// The evaluation domain 1, w, .., w^{n-1} for a primitive n-th root of unity w
+pub fn domain<F: PrimeField>(n: usize) -> Result<Vec<F>, Error> {
+    let root = root_of_unity::<F>(n)?;
+    Ok(core::iter::successors(Some(F::one()), |x| Some(*x * root))
+        .take(n)
+        .collect())
+}
+
+/// Evaluate the polynomial with the coefficients, lowest first, on the domain of their number
+pub fn ntt<F: PrimeField>(values: &mut [F]) -> Result<(), Error> {
+    let root = root_of_unity::<F>(values.len())?;
+    transform(values, root);
+    Ok(())
+}
+
+/// The coefficients, lowest first, of the polynomial with the values on the domain of their
+/// number
+pub fn intt<F: PrimeField>(values: &mut [F]) -> Result<(), Error> {
+    let root = root_of_unity::<F>(values.len())?;
+    let inverse = Option::<F>::from(root.invert()).ok_or(Error::NotInvertible)?;
+    transform(values, inverse);
+    let scale =
+        Option::<F>::from(F::from(values.len() as u64).invert()).ok_or(Error::NotInvertible)?;
+    for v in values.iter_mut() {
+        *v *= scale;
+    }
+    Ok(())
+}
+
+/// The product of polynomials given in either order of coefficients as a cyclic convolution,
+/// or None if the field has no domain of the size
+pub(crate) fn multiply<F: PrimeField + Zeroize>(a: &[F], b: &[F]) -> Option<Vec<F>> {
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    let root = root_of_unity::<F>(n).ok()?;
+    let mut fa = a.to_vec();
+    let mut fb = b.to_vec();
+    fa.resize(n, F::zero());
+    fb.resize(n, F::zero());
+    transform(&mut fa, root);
+    transform(&mut fb, root);
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x *= y;
+    }
+    fb.zeroize();
+    intt(&mut fa).ok()?;
+    fa.truncate(len);
+    Some(fa)
+}
+
+/// Iterative Cooley-Tukey over the powers of the n-th root of unity `root`
+fn transform<F: PrimeField>(values: &mut [F], root: F) {
+    let n = values.len();
+    if n < 2 {
+        return;
+    }
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+    let mut m = 1;
+    while m < n {
+        let step = root.pow_vartime([(n / (2 * m)) as u64]);
+        for k in (0..n).step_by(2 * m) {
+            let mut w = F::one();
+            for j in 0..m {
+                let t = w * values[k + j + m];
+                let u = values[k + j];
+                values[k + j] = u + t;
+                values[k + j + m] = u - t;
+                w *= step;
+            }
+        }
+        m *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::FieldElement;
+    use crate::polynomial::Polynomial;
+    use crate::share::Share;
+    use ff::Field;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::from_seed([5; 32])
+    }
+
+    #[test]
+    fn intt_inverts_ntt() {
+        let mut rng = rng();
+        for n in [1, 2, 8, NTT_CUTOFF, 4 * NTT_CUTOFF] {
+            let coefficients: Vec<NttScalar> =
+                (0..n).map(|_| NttScalar::random(&mut rng)).collect();
+            let mut values = coefficients.clone();
+            ntt(&mut values).unwrap();
+            intt(&mut values).unwrap();
+            assert_eq!(values, coefficients);
+        }
+    }
+
+    #[test]
+    fn transforms_match_naive_evaluation_and_interpolation() {
+        let mut rng = rng();
+        for n in [NTT_CUTOFF / 2, NTT_CUTOFF, 2 * NTT_CUTOFF] {
+            let polynomial =
+                Polynomial::new(n as u64, NttScalar::random(&mut rng), &mut rng).unwrap();
+            let xs = domain::<NttScalar>(n).unwrap();
+            let naive: Vec<NttScalar> = xs.iter().map(|x| polynomial.evaluate(x)).collect();
+            assert_eq!(polynomial.evaluate_domain(n).unwrap(), naive);
+            assert_eq!(polynomial.evaluate_batch(&xs), naive);
+
+            let points: Vec<Share<NttScalar>> = xs
+                .iter()
+                .zip(&naive)
+                .map(|(x, y)| Share { x: *x, y: *y })
+                .collect();
+            let interpolated = Polynomial::from_points(&points).unwrap();
+            assert_eq!(interpolated.coefficients, polynomial.coefficients);
+            let from_domain = Polynomial::interpolate_domain(&naive).unwrap();
+            assert_eq!(from_domain.coefficients, polynomial.coefficients);
+        }
+    }
+
+    #[test]
+    fn unsupported_sizes_are_rejected() {
+        let polynomial = Polynomial::new(3, FieldElement::new(7), &mut rng()).unwrap();
+        for n in [0, 3, 48] {
+            assert_eq!(
+                polynomial.evaluate_domain(n).unwrap_err(),
+                Error::UnsupportedDomain { size: n }
+            );
+        }
+        // FieldElement has 2-adicity 5
+        let n = 1 << (FieldElement::S + 1);
+        assert_eq!(
+            domain::<FieldElement>(n).unwrap_err(),
+            Error::UnsupportedDomain { size: n }
+        );
+        let mut values = vec![FieldElement::zero(); n];
+        assert_eq!(
+            ntt(&mut values).unwrap_err(),
+            Error::UnsupportedDomain { size: n }
+        );
+        assert_eq!(
+            intt(&mut values[..6]).unwrap_err(),
+            Error::UnsupportedDomain { size: 6 }
+        );
+        assert!(domain::<FieldElement>(1 << FieldElement::S).is_ok());
+    }
+}
@@ -0,0 +1,105 @@
+//! Packed (Franklin-Yung) sharing: k secrets sit at x = -1, .., -k of a single degree t+k-1
+//! polynomial, so each participant holds one share for all k secrets. Any t shares reveal
+//! nothing about the secrets and t+k shares reconstruct all of them. Shares of the same points
+//! add up to packed shares of the sums, position by position, as in SIMD-style MPC.
+
+use crate::error::Error;
+use crate::field::random_element;
+use crate::polynomial::Polynomial;
+use crate::share::Share;
+use alloc::{vec, vec::Vec};
+use ff::PrimeField;
+use rand_core::{CryptoRng, RngCore};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Dealer of packed secret sharing
+pub struct PackedDealer;
+
+impl PackedDealer {
+    /// Split the k secrets into n shares at x = 1..=n, any t of which reveal nothing and t+k
+    /// of which reconstruct all secrets
+    pub fn deal<F: PrimeField + Zeroize, R: RngCore + CryptoRng>(
+        secrets: &[F],
+        t: u64,
+        n: u64,
+        rng: &mut R,
+    ) -> Result<Vec<Share<F>>, Error> {
+        if secrets.is_empty() {
+            return Err(Error::LengthMismatch);
+        }
+        if t < 1 {
+            return Err(Error::DegreeTooLow);
+        }
+        let k = secrets.len() as u64;
+        if n < t + k {
+            return Err(Error::InvalidThreshold);
+        }
+        let positions = PackedDealer::positions::<F>(secrets.len());
+        // f = L + Z r for L interpolating the secrets, Z vanishing on the positions and r random
+        // of degree t-1, coefficients lowest first
+        let vanishing = positions
+            .iter()
+            .fold(vec![F::one()], |acc, p| multiply_linear(&acc, p));
+        let mut coefficients: Zeroizing<Vec<F>> = Zeroizing::new(vec![F::zero(); (t + k) as usize]);
+        for (j, (p, s)) in positions.iter().zip(secrets).enumerate() {
+            let basis = divide_linear(&vanishing, p);
+            let denominator = positions
+                .iter()
+                .enumerate()
+                .filter(|(m, _)| *m != j)
+                .fold(F::one(), |acc, (_, q)| acc * (*p - q));
+            let scale = *s * Option::<F>::from(denominator.invert()).ok_or(Error::NotInvertible)?;
+            for (c, b) in coefficients.iter_mut().zip(&basis) {
+                *c += scale * b;
+            }
+        }
+        for i in 0..t as usize {
+            let mut r: F = random_element(rng);
+            for (c, z) in coefficients[i..].iter_mut().zip(&vanishing) {
+                *c += r * z;
+            }
+            r.zeroize();
+        }
+        let mut coefficients = coefficients.to_vec();
+        coefficients.reverse();
+        let polynomial = Polynomial {
+            degree: t + k - 1,
+            coefficients,
+        };
+        Ok(polynomial.share(n))
+    }
+
+    /// Reconstruct the k secrets from at least t+k shares
+    pub fn reconstruct<F: PrimeField + Zeroize>(
+        shares: &[Share<F>],
+        k: usize,
+    ) -> Result<Vec<F>, Error> {
+        Polynomial::reconstruct_packed(shares, &PackedDealer::positions(k))
+    }
+
+    /// The packing positions -1, .., -k of the secrets
+    pub fn positions<F: PrimeField>(k: usize) -> Vec<F> {
+        (1..=k as u64).map(|j| -F::from(j)).collect()
+    }
+}
+
+/// (x - p) times the polynomial, coefficients lowest first
+fn multiply_linear<F: PrimeField>(polynomial: &[F], p: &F) -> Vec<F> {
+    let mut product = vec![F::zero(); polynomial.len() + 1];
+    for (i, c) in polynomial.iter().enumerate() {
+        product[i + 1] += c;
+        product[i] -= *c * p;
+    }
+    product
+}
+
+/// The polynomial divided by (x - p), which has to be a root, coefficients lowest first
+fn divide_linear<F: PrimeField>(polynomial: &[F], p: &F) -> Vec<F> {
+    let mut quotient = vec![F::zero(); polynomial.len() - 1];
+    let mut carry = F::zero();
+    for i in (0..quotient.len()).rev() {
+        carry = polynomial[i + 1] + carry * p;
+        quotient[i] = carry;
+    }
+    quotient
+}
@@ -0,0 +1,1312 @@
+use crate::error::Error;
+use crate::field::{batch_invert, random_element, FieldElement};
+use crate::ntt::{self, domain, intt, ntt, root_of_unity, NTT_CUTOFF};
+use crate::share::Share;
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::ops::{Add, Mul, Sub};
+use ff::PrimeField;
+use hkdf::Hkdf;
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use sha2::Sha256;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Number of points `evaluate_batch` evaluates together, small enough to stay in cache
+const BATCH_BLOCK: usize = 64;
+
+/// Checks that a secret is valid in the domain it is used in, e.g. a nonzero scalar
+pub type SecretValidator<F = FieldElement> = fn(&F) -> bool;
+
+/// Represents a polynomial over the finite field
+pub struct Polynomial<F: PrimeField + Zeroize = FieldElement> {
+    pub(crate) degree: u64,
+    /// The degree+1 coefficients, highest first so f(0) is the last
+    pub(crate) coefficients: Vec<F>,
+}
+
+/// Prints only the degree, the coefficients include the secret
+impl<F: PrimeField + Zeroize> fmt::Debug for Polynomial<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Polynomial")
+            .field("degree", &self.degree)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: PrimeField + Zeroize> Polynomial<F> {
+    /// Create random degree t-1 polynomial with f(0)=s
+    /// Fails for t < 2, where the shares would be the secret itself
+    pub fn new<R: RngCore + CryptoRng>(t: u64, s: F, rng: &mut R) -> Result<Self, Error> {
+        if t < 2 {
+            return Err(Error::DegreeTooLow);
+        }
+        let mut coef = vec![s];
+        for _ in 1..t {
+            let fe = random_element(rng);
+            coef.push(fe);
+        }
+        coef.reverse();
+
+        Ok(Polynomial {
+            degree: t - 1,
+            coefficients: coef,
+        })
+    }
+
+    /// Same as `new`, failing if the validator rejects s, e.g. one checking that s is a valid
+    /// private key of a curve
+    pub fn new_validated<R: RngCore + CryptoRng>(
+        t: u64,
+        s: F,
+        validator: SecretValidator<F>,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        if !validator(&s) {
+            return Err(Error::InvalidSecretForDomain);
+        }
+        Polynomial::new(t, s, rng)
+    }
+
+    /// Add a random leading coefficient, keeping f(0)
+    pub fn raise_degree<R: RngCore + CryptoRng>(&mut self, rng: &mut R) {
+        self.coefficients.insert(0, random_element(rng));
+        self.degree += 1;
+    }
+
+    /// Drop leading coefficients down to `degree`, keeping f(0)
+    /// This is a testing and analysis aid: the result is a different polynomial, so its shares
+    /// are not shares of the original and fewer of them reconstruct f(0)
+    pub fn truncate(&self, degree: u64) -> Self {
+        let degree = degree.min(self.degree);
+        Polynomial {
+            degree,
+            coefficients: self.coefficients[(self.degree - degree) as usize..].to_vec(),
+        }
+    }
+
+    /// Evaluate polynomial at f(x) with Horner's rule over all degree+1 coefficients
+    /// Loops only depend on the degree, never on the coefficients
+    pub fn evaluate(&self, x: &F) -> F {
+        self.coefficients
+            .iter()
+            .fold(F::zero(), |acc, c| acc * x + c)
+    }
+
+    /// Evaluate polynomial at each of the xs, running Horner's rule over blocks of them at once
+    /// From `NTT_CUTOFF` points on, points forming `ntt::domain` are evaluated with the NTT
+    pub fn evaluate_batch(&self, xs: &[F]) -> Vec<F> {
+        if xs.len() >= NTT_CUTOFF && is_domain(xs) {
+            if let Ok(ys) = self.evaluate_domain(xs.len()) {
+                return ys;
+            }
+        }
+        let mut ys = vec![F::zero(); xs.len()];
+        for (ys, xs) in ys.chunks_mut(BATCH_BLOCK).zip(xs.chunks(BATCH_BLOCK)) {
+            for c in self.coefficients.iter() {
+                for (y, x) in ys.iter_mut().zip(xs) {
+                    *y = *y * x + c;
+                }
+            }
+        }
+        ys
+    }
+
+    /// Evaluate polynomial on `ntt::domain(n)`, the n-th roots of unity, with the NTT
+    /// The field needs a root of unity of the power of two order n
+    pub fn evaluate_domain(&self, n: usize) -> Result<Vec<F>, Error> {
+        root_of_unity::<F>(n)?;
+        // reduce mod x^n - 1, which does not change the values on the domain
+        let mut values = Zeroizing::new(vec![F::zero(); n]);
+        for (i, c) in self.coefficients.iter().rev().enumerate() {
+            values[i % n] += c;
+        }
+        ntt(&mut values)?;
+        Ok(values.to_vec())
+    }
+
+    /// Evaluate polynomial on `ntt::domain(n)`, whose points are never zero
+    pub fn share_domain(&self, n: usize) -> Result<Vec<Share<F>>, Error> {
+        Ok(domain::<F>(n)?
+            .into_iter()
+            .zip(self.evaluate_domain(n)?)
+            .map(|(x, y)| Share { x, y })
+            .collect())
+    }
+
+    /// The polynomial of degree below n with the values on `ntt::domain(n)`, in domain order
+    pub fn interpolate_domain(ys: &[F]) -> Result<Self, Error> {
+        let mut coefficients = ys.to_vec();
+        intt(&mut coefficients)?;
+        coefficients.reverse();
+        Ok(Polynomial::from_coefficients(coefficients))
+    }
+
+    /// Evaluate polynomial at 1, .., n with forward differences: after the first degree+1
+    /// values, each value takes `degree` additions and no multiplications
+    pub fn evaluate_range(&self, n: u64) -> Vec<F> {
+        let d = self.degree as usize;
+        if n as usize <= d + 1 {
+            let xs: Vec<F> = (1..=n).map(F::from).collect();
+            return self.evaluate_batch(&xs);
+        }
+        let xs: Vec<F> = (1..=d as u64 + 1).map(F::from).collect();
+        // differences[k] = Δ^k f(i) for the current i
+        let mut differences = Zeroizing::new(self.evaluate_batch(&xs));
+        for k in 1..=d {
+            for i in (k..=d).rev() {
+                let previous = differences[i - 1];
+                differences[i] -= previous;
+            }
+        }
+        let mut ys = Vec::with_capacity(n as usize);
+        for _ in 0..n {
+            ys.push(differences[0]);
+            for k in 0..d {
+                let next = differences[k + 1];
+                differences[k] += next;
+            }
+        }
+        ys
+    }
+
+    /// The `order`-th derivative, the zero polynomial if the order is above the degree
+    pub fn derivative(&self, order: u64) -> Polynomial<F> {
+        if order > self.degree {
+            return Polynomial {
+                degree: 0,
+                coefficients: vec![F::zero()],
+            };
+        }
+        let degree = self.degree - order;
+        let coefficients = self.coefficients[..=degree as usize]
+            .iter()
+            .enumerate()
+            .map(|(j, c)| *c * falling_factorial::<F>(self.degree - j as u64, order))
+            .collect();
+        Polynomial {
+            degree,
+            coefficients,
+        }
+    }
+
+    /// Evaluate polynomial at f(1), .., f(n)
+    pub fn share(&self, n: u64) -> Vec<Share<F>> {
+        self.evaluate_range(n)
+            .into_iter()
+            .zip(1..=n)
+            .map(|(y, i)| Share { x: F::from(i), y })
+            .collect()
+    }
+
+    /// Evaluate polynomial at the given x-coordinates, which have to be distinct and nonzero
+    pub fn share_at(&self, xs: &[F]) -> Result<Vec<Share<F>>, Error> {
+        for (i, x) in xs.iter().enumerate() {
+            if x.is_zero() {
+                return Err(Error::ZeroX);
+            }
+            if xs[..i].contains(x) {
+                return Err(Error::DuplicateX);
+            }
+        }
+        Ok(xs
+            .iter()
+            .map(|x| Share {
+                x: *x,
+                y: self.evaluate(x),
+            })
+            .collect())
+    }
+
+    /// Re-randomize shares dealt with threshold t by adding a random polynomial with g(0)=0
+    /// The refreshed shares encode the same secret, but cannot be combined with old ones, so all
+    /// holders have to refresh and discard their old shares together
+    pub fn refresh<R: RngCore + CryptoRng>(
+        shares: &[Share<F>],
+        t: u64,
+        rng: &mut R,
+    ) -> Result<Vec<Share<F>>, Error> {
+        let zero = Polynomial::new(t, F::zero(), rng)?;
+        Ok(shares
+            .iter()
+            .map(|share| Share {
+                x: share.x,
+                y: share.y + zero.evaluate(&share.x),
+            })
+            .collect())
+    }
+
+    /// Re-share the secret of the first `old_t` shares to `new_n` holders with threshold `new_t`
+    /// Runs both sides of the protocol in one place, which distributed holders do with
+    /// `reshare_share` and `combine_subshares` so the secret is never reconstructed
+    pub fn reshare<R: RngCore + CryptoRng>(
+        old_shares: &[Share<F>],
+        old_t: usize,
+        new_t: u64,
+        new_n: u64,
+        rng: &mut R,
+    ) -> Result<Vec<Share<F>>, Error> {
+        if old_t == 0 || old_shares.len() < old_t {
+            return Err(Error::InsufficientShares {
+                needed: old_t.max(1),
+                got: old_shares.len(),
+            });
+        }
+        let old_shares = &old_shares[..old_t];
+        let old_xs: Vec<F> = old_shares.iter().map(|s| s.x).collect();
+        let subshares = old_shares
+            .iter()
+            .map(|share| Polynomial::reshare_share(share, new_t, new_n, rng))
+            .collect::<Result<Vec<_>, Error>>()?;
+        (0..new_n as usize)
+            .map(|j| {
+                let received: Vec<Share<F>> = subshares.iter().map(|s| s[j].clone()).collect();
+                Polynomial::combine_subshares(&old_xs, &received)
+            })
+            .collect()
+    }
+
+    /// An old holder's part of `reshare`: a sharing of its own share among the new holders
+    /// at x = 1..=new_n, the sub-share of new holder j at position j - 1
+    /// Fails if new_t > new_n, as the new holders could never combine their shares
+    pub fn reshare_share<R: RngCore + CryptoRng>(
+        share: &Share<F>,
+        new_t: u64,
+        new_n: u64,
+        rng: &mut R,
+    ) -> Result<Vec<Share<F>>, Error> {
+        if new_t > new_n {
+            return Err(Error::InvalidThreshold);
+        }
+        Ok(Polynomial::new(new_t, share.y, rng)?.share(new_n))
+    }
+
+    /// A new holder's part of `reshare`: its new share from the sub-shares it received from the
+    /// old holders at old_xs, in the same order
+    pub fn combine_subshares(old_xs: &[F], subshares: &[Share<F>]) -> Result<Share<F>, Error> {
+        if old_xs.len() != subshares.len() {
+            return Err(Error::LengthMismatch);
+        }
+        let x = subshares
+            .first()
+            .map(|s| s.x)
+            .ok_or(Error::InsufficientShares { needed: 1, got: 0 })?;
+        if let Some(index) = subshares.iter().position(|s| s.x != x) {
+            return Err(Error::GroupMismatch { index });
+        }
+        let y = lagrange_coefficients(old_xs, &F::zero())?
+            .iter()
+            .zip(subshares)
+            .fold(F::zero(), |acc, (l, s)| acc + *l * s.y);
+        Ok(Share { x, y })
+    }
+
+    /// Compute f(0) by interpolation
+    pub fn reconstruct(shares: &[Share<F>]) -> Result<F, Error> {
+        Polynomial::reconstruct_at(shares, &F::zero())
+    }
+
+    /// Same as `reconstruct`, failing if the validator rejects the result
+    pub fn reconstruct_validated(
+        shares: &[Share<F>],
+        validator: SecretValidator<F>,
+    ) -> Result<F, Error> {
+        let s = Polynomial::reconstruct(shares)?;
+        if !validator(&s) {
+            return Err(Error::InvalidSecretForDomain);
+        }
+        Ok(s)
+    }
+
+    /// Compute f(x) by Lagrange interpolation over all given shares, which can have arbitrary
+    /// distinct x-coordinates
+    pub fn reconstruct_at(shares: &[Share<F>], x: &F) -> Result<F, Error> {
+        Ok(Polynomial::reconstruct_with_coefficients(shares, x)?.0)
+    }
+
+    /// Compute f(0) from `threshold` shares that always include the fixed (e.g. server-held)
+    /// share, completed with the first of the others
+    pub fn reconstruct_with_fixed(
+        fixed: &Share<F>,
+        others: &[Share<F>],
+        threshold: usize,
+    ) -> Result<F, Error> {
+        if others.iter().any(|s| s.x == fixed.x) {
+            return Err(Error::DuplicateX);
+        }
+        if threshold == 0 || others.len() < threshold - 1 {
+            return Err(Error::InsufficientShares {
+                needed: threshold.max(1),
+                got: others.len() + 1,
+            });
+        }
+        let mut shares = vec![fixed.clone()];
+        shares.extend_from_slice(&others[..threshold - 1]);
+        Polynomial::reconstruct(&shares)
+    }
+
+    /// Indices of the shares off the degree t-1 polynomial most of the shares lie on, e.g. to
+    /// tell which shares are wrong when the wrong secret comes out
+    /// Tries every subset of t shares, so this is only practical for small n. The wrong shares
+    /// are identified if there are at most (n - t) / 2 of them
+    pub fn inconsistent_shares(shares: &[Share<F>], t: usize) -> Result<Vec<usize>, Error> {
+        let n = shares.len();
+        if t == 0 || n < t {
+            return Err(Error::InsufficientShares {
+                needed: t.max(1),
+                got: n,
+            });
+        }
+        let mut best: Option<Vec<usize>> = None;
+        let mut subset: Vec<usize> = (0..t).collect();
+        loop {
+            let chosen: Vec<Share<F>> = subset.iter().map(|i| shares[*i].clone()).collect();
+            let mut wrong = Vec::new();
+            for (j, share) in shares.iter().enumerate() {
+                if !subset.contains(&j) && Polynomial::reconstruct_at(&chosen, &share.x)? != share.y
+                {
+                    wrong.push(j);
+                }
+            }
+            if best.as_ref().is_none_or(|b| wrong.len() < b.len()) {
+                best = Some(wrong);
+            }
+            if !next_subset(&mut subset, n) {
+                break;
+            }
+        }
+        let wrong = best.expect("there is at least one subset");
+        if 2 * wrong.len() > n - t {
+            return Err(Error::TooManyErrors);
+        }
+        Ok(wrong)
+    }
+
+    /// Compute f(0) of the degree t-1 polynomial by Berlekamp-Welch decoding, correcting up to
+    /// (n - t) / 2 wrong shares. Unlike `reconstruct` this branches on the y-values
+    pub fn reconstruct_robust(shares: &[Share<F>], t: usize) -> Result<F, Error> {
+        let n = shares.len();
+        if t == 0 || n < t {
+            return Err(Error::InsufficientShares {
+                needed: t.max(1),
+                got: n,
+            });
+        }
+        for (i, share) in shares.iter().enumerate() {
+            if share.x.is_zero() {
+                return Err(Error::ZeroX);
+            }
+            if shares[..i].iter().any(|s| s.x == share.x) {
+                return Err(Error::DuplicateX);
+            }
+        }
+        // Q(x_i) = y_i E(x_i) with deg Q < t + e and E monic of degree e vanishing at the errors
+        let e = (n - t) / 2;
+        let mut rows: Vec<Vec<F>> = shares
+            .iter()
+            .map(|share| {
+                let powers: Vec<F> = (0..=t + e)
+                    .scan(F::one(), |power, _| {
+                        let current = *power;
+                        *power *= share.x;
+                        Some(current)
+                    })
+                    .collect();
+                let mut row = powers[..t + e].to_vec();
+                row.extend(powers[..e].iter().map(|p| -(*p * share.y)));
+                row.push(powers[e] * share.y);
+                row
+            })
+            .collect();
+        let solution = solve_linear(&mut rows, t + 2 * e).ok_or(Error::TooManyErrors)?;
+        let mut error_locator = solution[t + e..].to_vec();
+        error_locator.push(F::one());
+        let (p, remainder) = divide(&solution[..t + e], &error_locator);
+        if remainder.iter().any(|c| !c.is_zero()) {
+            return Err(Error::TooManyErrors);
+        }
+        let evaluate = |x: &F| p.iter().rev().fold(F::zero(), |acc, c| acc * x + c);
+        if shares.iter().filter(|s| evaluate(&s.x) != s.y).count() > e {
+            return Err(Error::TooManyErrors);
+        }
+        Ok(p.first().copied().unwrap_or_else(F::zero))
+    }
+
+    /// Compute the packed secrets f(p) for each packing position p (e.g. 0, -1, -2, ..)
+    /// by interpolation over all given shares
+    pub fn reconstruct_packed(shares: &[Share<F>], positions: &[F]) -> Result<Vec<F>, Error> {
+        positions
+            .iter()
+            .map(|p| Polynomial::reconstruct_at(shares, p))
+            .collect()
+    }
+
+    /// Compute f(at) by interpolation over all given shares, also returning the (x, coefficient)
+    /// pairs used so they can be reused, e.g. on group elements for threshold signing
+    /// Branches only on the x-coordinates, which are public, and never on the y-values
+    pub fn reconstruct_with_coefficients(
+        shares: &[Share<F>],
+        at: &F,
+    ) -> Result<(F, Vec<(F, F)>), Error> {
+        if shares.is_empty() {
+            return Err(Error::InsufficientShares { needed: 1, got: 0 });
+        }
+        let xs: Vec<F> = shares.iter().map(|s| s.x).collect();
+        let coefficients = lagrange_coefficients(&xs, at)?;
+        let val = coefficients
+            .iter()
+            .zip(shares)
+            .fold(F::zero(), |acc, (l, s)| acc + *l * s.y);
+        Ok((val, xs.into_iter().zip(coefficients).collect()))
+    }
+}
+
+impl<F: PrimeField + Zeroize> Polynomial<F> {
+    /// The polynomial with the coefficients, highest first so the constant term is the last
+    /// Leading zeros are dropped, no coefficients give the zero polynomial
+    pub fn from_coefficients(coefficients: Vec<F>) -> Self {
+        let leading = coefficients
+            .iter()
+            .position(|c| !c.is_zero())
+            .unwrap_or(coefficients.len());
+        let mut coefficients = coefficients;
+        coefficients.drain(..leading);
+        if coefficients.is_empty() {
+            coefficients.push(F::zero());
+        }
+        Polynomial {
+            degree: coefficients.len() as u64 - 1,
+            coefficients,
+        }
+    }
+
+    /// The polynomial of lowest degree through the points, of degree below their number
+    pub fn from_points(points: &[Share<F>]) -> Result<Self, Error> {
+        if points.is_empty() {
+            return Err(Error::InsufficientShares { needed: 1, got: 0 });
+        }
+        let xs: Vec<F> = points.iter().map(|p| p.x).collect();
+        if points.len() >= NTT_CUTOFF && is_domain(&xs) {
+            let ys: Zeroizing<Vec<F>> = Zeroizing::new(points.iter().map(|p| p.y).collect());
+            return Polynomial::interpolate_domain(&ys);
+        }
+        // Z = (x - x_1) .. (x - x_n), and point j contributes y_j Z / ((x - x_j) Z'(x_j))
+        let vanishing = points
+            .iter()
+            .fold(vec![F::one()], |acc, p| multiply(&acc, &[F::one(), -p.x]));
+        let mut denominators: Vec<F> = points
+            .iter()
+            .enumerate()
+            .map(|(j, p)| {
+                points
+                    .iter()
+                    .enumerate()
+                    .filter(|(m, _)| *m != j)
+                    .fold(F::one(), |acc, (_, q)| acc * (p.x - q.x))
+            })
+            .collect();
+        batch_invert(&mut denominators).map_err(|_| Error::DuplicateX)?;
+        let mut coefficients = vec![F::zero(); points.len()];
+        for (p, d) in points.iter().zip(&denominators) {
+            let (basis, _) = divide_highest_first(&vanishing, &[F::one(), -p.x]);
+            let scale = p.y * d;
+            for (c, b) in coefficients.iter_mut().zip(&basis) {
+                *c += scale * b;
+            }
+        }
+        Ok(Polynomial::from_coefficients(coefficients))
+    }
+
+    /// The degree, 0 for constants and the zero polynomial
+    pub fn degree(&self) -> u64 {
+        self.degree
+    }
+
+    /// The degree+1 coefficients, highest first so f(0) is the last
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    /// Divide by a nonzero divisor, returning the quotient and the remainder of lower degree
+    pub fn div_rem(&self, divisor: &Polynomial<F>) -> Result<(Self, Self), Error> {
+        let divisor = Polynomial::from_coefficients(divisor.coefficients.clone());
+        let inverse =
+            Option::<F>::from(divisor.coefficients[0].invert()).ok_or(Error::NotInvertible)?;
+        let monic: Vec<F> = divisor.coefficients.iter().map(|c| *c * inverse).collect();
+        let (quotient, remainder) = divide_highest_first(&self.coefficients, &monic);
+        let quotient = quotient.into_iter().map(|c| c * inverse).collect();
+        Ok((
+            Polynomial::from_coefficients(quotient),
+            Polynomial::from_coefficients(remainder),
+        ))
+    }
+}
+
+impl Polynomial<FieldElement> {
+    /// Evaluate polynomial at x = H(id) for each participant identifier
+    pub fn share_for(&self, ids: &[&str]) -> Result<Vec<Share>, Error> {
+        let xs: Vec<FieldElement> = ids.iter().map(|id| FieldElement::hash(id)).collect();
+        self.share_at(&xs)
+    }
+}
+
+impl<F: PrimeField + Zeroize> Zeroize for Polynomial<F> {
+    fn zeroize(&mut self) {
+        self.coefficients.zeroize();
+    }
+}
+
+impl<F: PrimeField + Zeroize> Drop for Polynomial<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<F: PrimeField + Zeroize> ZeroizeOnDrop for Polynomial<F> {}
+
+impl<'a, F: PrimeField + Zeroize> Add<&'a Polynomial<F>> for &'a Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn add(self, other: &'a Polynomial<F>) -> Polynomial<F> {
+        let (long, short) = if self.degree >= other.degree {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let offset = long.coefficients.len() - short.coefficients.len();
+        let mut coefficients = long.coefficients.clone();
+        for (c, s) in coefficients[offset..].iter_mut().zip(&short.coefficients) {
+            *c += s;
+        }
+        Polynomial::from_coefficients(coefficients)
+    }
+}
+
+impl<'a, F: PrimeField + Zeroize> Sub<&'a Polynomial<F>> for &'a Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn sub(self, other: &'a Polynomial<F>) -> Polynomial<F> {
+        self + &(other * -F::one())
+    }
+}
+
+impl<'a, F: PrimeField + Zeroize> Mul<&'a Polynomial<F>> for &'a Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(self, other: &'a Polynomial<F>) -> Polynomial<F> {
+        Polynomial::from_coefficients(multiply(&self.coefficients, &other.coefficients))
+    }
+}
+
+/// Multiplication by a scalar
+impl<F: PrimeField + Zeroize> Mul<F> for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(self, c: F) -> Polynomial<F> {
+        Polynomial::from_coefficients(self.coefficients.iter().map(|a| *a * c).collect())
+    }
+}
+
+/// Retains the polynomial so shares can be issued on demand, e.g. as participants enroll
+pub struct Dealer<F: PrimeField + Zeroize = FieldElement> {
+    pub(crate) polynomial: Polynomial<F>,
+    issued: Vec<u64>,
+}
+
+impl<F: PrimeField + Zeroize> fmt::Debug for Dealer<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dealer")
+            .field("polynomial", &self.polynomial)
+            .field("issued", &self.issued)
+            .finish()
+    }
+}
+
+impl<F: PrimeField + Zeroize> Dealer<F> {
+    /// Create random degree t-1 polynomial with f(0)=s
+    pub fn new<R: RngCore + CryptoRng>(t: u64, s: F, rng: &mut R) -> Result<Self, Error> {
+        Ok(Dealer {
+            polynomial: Polynomial::new(t, s, rng)?,
+            issued: Vec::new(),
+        })
+    }
+
+    /// Create the degree t-1 polynomial with f(0)=s whose other coefficients are drawn from
+    /// ChaCha20 keyed by HKDF-SHA256 of the seed, so the same seed always deals the same shares
+    /// Anyone with the seed and s can recompute every share, keep it as secret as s
+    pub fn from_seed(t: u64, s: F, seed: [u8; 32]) -> Result<Self, Error> {
+        let mut key = Zeroizing::new([0u8; 32]);
+        Hkdf::<Sha256>::new(None, &seed)
+            .expand(b"polynomials dealer seed", &mut key[..])
+            .expect("32 bytes is a valid length for HKDF-SHA256");
+        Dealer::new(t, s, &mut ChaCha20Rng::from_seed(*key))
+    }
+
+    /// Evaluate the polynomial at a new x, each x is only issued once
+    pub fn issue(&mut self, x: u64) -> Result<Share<F>, Error> {
+        if x == 0 {
+            return Err(Error::ZeroX);
+        }
+        if self.issued.contains(&x) {
+            return Err(Error::AlreadyIssued { x });
+        }
+        self.issued.push(x);
+        let x = F::from(x);
+        Ok(Share {
+            x,
+            y: self.polynomial.evaluate(&x),
+        })
+    }
+}
+
+/// Interpolate at `at` where the y-values are elements of a group that field elements act on,
+/// e.g. to aggregate signature shares in threshold signing
+pub fn interpolate_group<F: PrimeField, G>(shares: &[(F, G)], at: &F) -> Result<G, Error>
+where
+    G: Copy + Add<Output = G> + Mul<F, Output = G>,
+{
+    let xs: Vec<F> = shares.iter().map(|(x, _)| *x).collect();
+    lagrange_coefficients(&xs, at)?
+        .into_iter()
+        .zip(shares)
+        .map(|(l, (_, g))| *g * l)
+        .reduce(|acc, g| acc + g)
+        .ok_or(Error::InsufficientShares { needed: 1, got: 0 })
+}
+
+/// The Lagrange coefficients l_i(0) of a fixed set of x-coordinates, computed once for a
+/// holder set that reconstructs or partially signs repeatedly
+#[derive(Debug, Clone)]
+pub struct LagrangeCoefficients<F: PrimeField = FieldElement> {
+    xs: Vec<F>,
+    coefficients: Vec<F>,
+}
+
+impl<F: PrimeField> LagrangeCoefficients<F> {
+    /// Compute the coefficients for the xs with a single batched inversion
+    /// Fails if the xs are not distinct
+    pub fn precompute(xs: &[F]) -> Result<Self, Error> {
+        Ok(LagrangeCoefficients {
+            xs: xs.to_vec(),
+            coefficients: lagrange_coefficients(xs, &F::zero())?,
+        })
+    }
+
+    /// The x-coordinates, in the order they were given
+    pub fn xs(&self) -> &[F] {
+        &self.xs
+    }
+
+    /// The coefficients l_i(0), in the order of the xs
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    /// The coefficient of the holder at x, e.g. for its partial signature
+    pub fn coefficient(&self, x: &F) -> Option<F> {
+        self.xs
+            .iter()
+            .position(|xi| xi == x)
+            .map(|i| self.coefficients[i])
+    }
+}
+
+/// Compute f(0) from the y-values, given in the order of the precomputed xs, as a single inner
+/// product
+pub fn reconstruct_with<F: PrimeField>(
+    coefficients: &LagrangeCoefficients<F>,
+    ys: &[F],
+) -> Result<F, Error> {
+    if ys.len() != coefficients.coefficients.len() {
+        return Err(Error::LengthMismatch);
+    }
+    Ok(coefficients
+        .coefficients
+        .iter()
+        .zip(ys)
+        .fold(F::zero(), |acc, (c, y)| acc + *c * y))
+}
+
+/// Reconstructs f(0) for a fixed set of x-coordinates reused across many reconstructions
+/// The coefficients are the first row of the inverse Vandermonde matrix of xs, so each
+/// reconstruction is a single inner product with the y-values
+#[derive(Debug)]
+pub struct FixedSetReconstructor<F: PrimeField = FieldElement> {
+    coefficients: LagrangeCoefficients<F>,
+}
+
+impl<F: PrimeField> FixedSetReconstructor<F> {
+    /// Precompute the reconstruction coefficients for xs
+    pub fn new(xs: &[F]) -> Result<Self, Error> {
+        Ok(FixedSetReconstructor {
+            coefficients: LagrangeCoefficients::precompute(xs)?,
+        })
+    }
+
+    /// Compute f(0) from the y-values, given in the same order as the xs
+    pub fn reconstruct(&self, ys: &[F]) -> Result<F, Error> {
+        reconstruct_with(&self.coefficients, ys)
+    }
+}
+
+/// Lagrange basis polynomials l_i for the points xs, evaluated at `at`
+/// Fails if the xs are not distinct
+pub fn lagrange_coefficients<F: PrimeField>(xs: &[F], at: &F) -> Result<Vec<F>, Error> {
+    let mut ns = Vec::with_capacity(xs.len());
+    let mut ds = Vec::with_capacity(xs.len());
+    for (i, xi) in xs.iter().enumerate() {
+        let mut n = F::one();
+        let mut d = F::one();
+        for (j, xj) in xs.iter().enumerate() {
+            if i != j {
+                n *= *at - xj;
+                d *= *xi - xj;
+            }
+        }
+        ns.push(n);
+        ds.push(d);
+    }
+    batch_invert(&mut ds).map_err(|_| Error::DuplicateX)?;
+    let coefficients: Vec<F> = ns.iter().zip(ds).map(|(n, d)| *n * d).collect();
+    #[cfg(feature = "debug-audit")]
+    {
+        // the basis polynomials interpolate the constant 1
+        let sum = coefficients.iter().fold(F::zero(), |acc, l| acc + l);
+        if !xs.is_empty() && sum != F::one() {
+            tracing::warn!(len = xs.len(), "lagrange coefficients do not sum to 1");
+        }
+    }
+    Ok(coefficients)
+}
+
+/// Solve the linear system given as rows of coefficients followed by the right-hand side, with
+/// free unknowns set to zero, or None if it is inconsistent
+fn solve_linear<F: PrimeField>(rows: &mut [Vec<F>], unknowns: usize) -> Option<Vec<F>> {
+    let mut pivots = Vec::new();
+    for col in 0..unknowns {
+        let rank = pivots.len();
+        let pivot = match (rank..rows.len()).find(|r| !rows[*r][col].is_zero()) {
+            Some(pivot) => pivot,
+            None => continue,
+        };
+        rows.swap(rank, pivot);
+        let inv = Option::<F>::from(rows[rank][col].invert())?;
+        for v in rows[rank][col..].iter_mut() {
+            *v *= inv;
+        }
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != rank && !row[col].is_zero() {
+                let factor = row[col];
+                for (v, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                    *v -= factor * p;
+                }
+            }
+        }
+        pivots.push(col);
+    }
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[unknowns].is_zero())
+    {
+        return None;
+    }
+    let mut solution = vec![F::zero(); unknowns];
+    for (row, col) in pivots.iter().enumerate() {
+        solution[*col] = rows[row][unknowns];
+    }
+    Some(solution)
+}
+
+/// Divide polynomials given lowest coefficient first by a monic divisor, returning the quotient
+/// and the remainder
+fn divide<F: PrimeField>(numerator: &[F], divisor: &[F]) -> (Vec<F>, Vec<F>) {
+    let d = divisor.len() - 1;
+    if numerator.len() <= d {
+        return (Vec::new(), numerator.to_vec());
+    }
+    let mut remainder = numerator.to_vec();
+    let mut quotient = vec![F::zero(); numerator.len() - d];
+    for i in (0..quotient.len()).rev() {
+        let c = remainder[i + d];
+        quotient[i] = c;
+        for (j, dc) in divisor.iter().enumerate() {
+            remainder[i + j] -= c * dc;
+        }
+    }
+    remainder.truncate(d);
+    (quotient, remainder)
+}
+
+/// Multiply polynomials given in either order of coefficients, the product is in the same order
+/// Uses the NTT from `NTT_CUTOFF` coefficients on both sides if the field supports the size
+fn multiply<F: PrimeField + Zeroize>(a: &[F], b: &[F]) -> Vec<F> {
+    if a.len().min(b.len()) >= NTT_CUTOFF {
+        if let Some(product) = ntt::multiply(a, b) {
+            return product;
+        }
+    }
+    let mut product = vec![F::zero(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] += *x * y;
+        }
+    }
+    product
+}
+
+/// Whether the points are `ntt::domain` of their number, in order
+fn is_domain<F: PrimeField>(xs: &[F]) -> bool {
+    match root_of_unity::<F>(xs.len()) {
+        Ok(root) => {
+            let mut x = F::one();
+            xs.iter().all(|xi| {
+                let matches = *xi == x;
+                x *= root;
+                matches
+            })
+        }
+        Err(_) => false,
+    }
+}
+
+/// Divide polynomials given highest coefficient first by a monic divisor, returning the
+/// quotient and the remainder
+fn divide_highest_first<F: PrimeField>(numerator: &[F], divisor: &[F]) -> (Vec<F>, Vec<F>) {
+    let d = divisor.len() - 1;
+    if numerator.len() <= d {
+        return (Vec::new(), numerator.to_vec());
+    }
+    let mut remainder = numerator.to_vec();
+    let mut quotient = vec![F::zero(); numerator.len() - d];
+    for i in 0..quotient.len() {
+        let c = remainder[i];
+        quotient[i] = c;
+        for (j, dc) in divisor.iter().enumerate() {
+            remainder[i + j] -= c * dc;
+        }
+    }
+    (quotient, remainder.split_off(numerator.len() - d))
+}
+
+/// Advance the sorted subset of 0..n to the next one in lexicographic order, false after the
+/// last one
+pub(crate) fn next_subset(subset: &mut [usize], n: usize) -> bool {
+    let k = subset.len();
+    match (0..k).rev().find(|i| subset[*i] < n - k + i) {
+        Some(i) => {
+            subset[i] += 1;
+            for j in i + 1..k {
+                subset[j] = subset[j - 1] + 1;
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// i (i-1) .. (i-k+1), the factor x^i gains from its k-th derivative
+pub(crate) fn falling_factorial<F: PrimeField>(i: u64, k: u64) -> F {
+    (0..k).fold(F::one(), |acc, j| acc * F::from(i - j))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::Field;
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::from_seed([7; 32])
+    }
+
+    #[test]
+    fn reconstruct_packed_recovers_each_position() {
+        let secrets = [
+            FieldElement::new(11),
+            FieldElement::new(22),
+            FieldElement::new(33),
+        ];
+        let positions = [
+            FieldElement::zero(),
+            -FieldElement::one(),
+            -FieldElement::new(2),
+        ];
+        let mut points: Vec<Share> = positions
+            .iter()
+            .zip(&secrets)
+            .map(|(x, y)| Share { x: *x, y: *y })
+            .collect();
+        points.push(Share {
+            x: FieldElement::new(5),
+            y: FieldElement::random(&mut rng()),
+        });
+        let polynomial = Polynomial::from_points(&points).unwrap();
+        let shares = polynomial.share(4);
+        assert_eq!(
+            Polynomial::reconstruct_packed(&shares, &positions).unwrap(),
+            secrets.to_vec()
+        );
+    }
+
+    #[test]
+    fn fixed_set_reconstructor_matches_reconstruct() {
+        let mut rng = rng();
+        let xs: Vec<FieldElement> = [2, 5, 7, 11]
+            .iter()
+            .map(|x| FieldElement::new(*x))
+            .collect();
+        let reconstructor = FixedSetReconstructor::new(&xs).unwrap();
+        for _ in 0..5 {
+            let secret = FieldElement::random(&mut rng);
+            let shares = Polynomial::new(4, secret, &mut rng)
+                .unwrap()
+                .share_at(&xs)
+                .unwrap();
+            let ys: Vec<FieldElement> = shares.iter().map(|s| s.y).collect();
+            assert_eq!(reconstructor.reconstruct(&ys).unwrap(), secret);
+            assert_eq!(Polynomial::reconstruct(&shares).unwrap(), secret);
+        }
+    }
+
+    /// The vector space F^2 as a toy group, acted on componentwise
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Toy(FieldElement, FieldElement);
+
+    impl Add for Toy {
+        type Output = Toy;
+
+        fn add(self, other: Toy) -> Toy {
+            Toy(self.0 + other.0, self.1 + other.1)
+        }
+    }
+
+    impl Mul<FieldElement> for Toy {
+        type Output = Toy;
+
+        fn mul(self, c: FieldElement) -> Toy {
+            Toy(self.0 * c, self.1 * c)
+        }
+    }
+
+    #[test]
+    fn interpolate_group_aggregates_group_shares() {
+        let mut rng = rng();
+        let generator = Toy(FieldElement::new(3), FieldElement::new(5));
+        let secret = FieldElement::random(&mut rng);
+        let shares = Polynomial::new(3, secret, &mut rng).unwrap().share(5);
+        let group_shares: Vec<(FieldElement, Toy)> = shares[1..4]
+            .iter()
+            .map(|s| (s.x, generator * s.y))
+            .collect();
+        assert_eq!(
+            interpolate_group(&group_shares, &FieldElement::zero()).unwrap(),
+            generator * secret
+        );
+    }
+
+    #[test]
+    fn dealer_issues_shares_on_demand() {
+        let secret = FieldElement::new(77);
+        let mut dealer = Dealer::new(3, secret, &mut rng()).unwrap();
+        let mut shares = Vec::new();
+        for x in [4, 9, 2] {
+            shares.push(dealer.issue(x).unwrap());
+        }
+        assert_eq!(dealer.issue(9).unwrap_err(), Error::AlreadyIssued { x: 9 });
+        assert_eq!(dealer.issue(0).unwrap_err(), Error::ZeroX);
+        assert_eq!(Polynomial::reconstruct(&shares).unwrap(), secret);
+    }
+
+    #[test]
+    fn validator_rejects_zero_secrets() {
+        let nonzero: SecretValidator = |s| !s.is_zero();
+        assert_eq!(
+            Polynomial::new_validated(2, FieldElement::zero(), nonzero, &mut rng()).unwrap_err(),
+            Error::InvalidSecretForDomain
+        );
+        let shares = Polynomial::new_validated(2, FieldElement::new(5), nonzero, &mut rng())
+            .unwrap()
+            .share(2);
+        assert_eq!(
+            Polynomial::reconstruct_validated(&shares, nonzero).unwrap(),
+            FieldElement::new(5)
+        );
+        let zero = Polynomial::new(2, FieldElement::zero(), &mut rng())
+            .unwrap()
+            .share(2);
+        assert_eq!(
+            Polynomial::reconstruct_validated(&zero, nonzero).unwrap_err(),
+            Error::InvalidSecretForDomain
+        );
+    }
+
+    #[test]
+    fn reshared_shares_reconstruct_the_old_secret() {
+        let mut rng = rng();
+        let secret = FieldElement::new(77);
+        let old = Polynomial::new(3, secret, &mut rng).unwrap().share(5);
+        assert_eq!(
+            Polynomial::reshare(&old[1..], 3, 4, 3, &mut rng).unwrap_err(),
+            Error::InvalidThreshold
+        );
+        let new = Polynomial::reshare(&old[1..], 3, 2, 4, &mut rng).unwrap();
+        assert_eq!(new.len(), 4);
+        assert_eq!(Polynomial::reconstruct(&new[2..]).unwrap(), secret);
+        assert_eq!(Polynomial::reconstruct(&new[..2]).unwrap(), secret);
+        // old shares are not shares of the new polynomial
+        let mixed = [old[0].clone(), new[1].clone()];
+        assert_ne!(Polynomial::reconstruct(&mixed).unwrap(), secret);
+        let mixed = [old[0].clone(), old[2].clone(), new[3].clone()];
+        assert_ne!(Polynomial::reconstruct(&mixed).unwrap(), secret);
+    }
+
+    /// n = 7 shares of a degree 2 polynomial with the shares at the indices corrupted
+    fn corrupted(secret: FieldElement, indices: &[usize]) -> Vec<Share> {
+        let mut shares = Polynomial::new(3, secret, &mut rng()).unwrap().share(7);
+        for i in indices {
+            shares[*i].y += FieldElement::new(*i as u64 + 1);
+        }
+        shares
+    }
+
+    #[test]
+    fn reconstruct_robust_corrects_up_to_half_the_redundancy() {
+        let secret = FieldElement::new(31);
+        // n = t + 2e for e = 2
+        for indices in [&[][..], &[4], &[1, 5], &[0, 6]] {
+            let shares = corrupted(secret, indices);
+            assert_eq!(Polynomial::reconstruct_robust(&shares, 3).unwrap(), secret);
+        }
+        let shares = corrupted(secret, &[0, 3, 5]);
+        assert_eq!(
+            Polynomial::reconstruct_robust(&shares, 3),
+            Err(Error::TooManyErrors)
+        );
+        // with n = t + 1 no error can be corrected
+        let shares = corrupted(secret, &[1]);
+        assert_eq!(
+            Polynomial::reconstruct_robust(&shares[..4], 3),
+            Err(Error::TooManyErrors)
+        );
+        assert_eq!(
+            Polynomial::reconstruct_robust(&shares[..2], 3),
+            Err(Error::InsufficientShares { needed: 3, got: 2 })
+        );
+    }
+
+    #[test]
+    fn inconsistent_shares_finds_exactly_the_tampered_ones() {
+        let secret = FieldElement::new(31);
+        for indices in [&[][..], &[2], &[1, 5], &[0, 6]] {
+            let shares = corrupted(secret, indices);
+            assert_eq!(
+                Polynomial::inconsistent_shares(&shares, 3).unwrap(),
+                indices.to_vec()
+            );
+        }
+        let shares = corrupted(secret, &[0, 3, 5]);
+        assert_eq!(
+            Polynomial::inconsistent_shares(&shares, 3),
+            Err(Error::TooManyErrors)
+        );
+    }
+
+    #[test]
+    fn reconstruct_rejects_no_shares() {
+        let none = Err(Error::InsufficientShares { needed: 1, got: 0 });
+        assert_eq!(Polynomial::<FieldElement>::reconstruct(&[]), none);
+        assert_eq!(
+            Polynomial::<FieldElement>::reconstruct_at(&[], &FieldElement::one()),
+            none
+        );
+    }
+
+    #[test]
+    fn reconstruct_with_coefficients_sums_to_secret() {
+        let mut rng = rng();
+        let secret = FieldElement::random(&mut rng);
+        let shares = Polynomial::new(3, secret, &mut rng).unwrap().share(3);
+        let (value, coefficients) =
+            Polynomial::reconstruct_with_coefficients(&shares, &FieldElement::zero()).unwrap();
+        assert_eq!(value, secret);
+        let sum =
+            coefficients
+                .iter()
+                .zip(&shares)
+                .fold(FieldElement::zero(), |acc, ((x, l), s)| {
+                    assert_eq!(*x, s.x);
+                    acc + *l * s.y
+                });
+        assert_eq!(sum, secret);
+    }
+
+    #[test]
+    fn truncated_polynomial_needs_fewer_shares() {
+        let secret = FieldElement::new(8);
+        let polynomial = Polynomial::new(5, secret, &mut rng()).unwrap();
+        let truncated = polynomial.truncate(1);
+        assert_eq!(truncated.degree(), 1);
+        assert_eq!(truncated.evaluate(&FieldElement::zero()), secret);
+        assert_eq!(
+            Polynomial::reconstruct(&truncated.share(2)).unwrap(),
+            secret
+        );
+        assert_ne!(
+            Polynomial::reconstruct(&polynomial.share(2)).unwrap(),
+            secret
+        );
+        assert_eq!(polynomial.truncate(9).degree(), 4);
+    }
+
+    #[test]
+    fn reconstruct_with_fixed_share() {
+        let secret = FieldElement::new(1000);
+        let shares = Polynomial::new(3, secret, &mut rng()).unwrap().share(4);
+        let (server, users) = (&shares[0], &shares[1..]);
+        assert_eq!(
+            Polynomial::reconstruct_with_fixed(server, &users[1..], 3).unwrap(),
+            secret
+        );
+        assert_eq!(
+            Polynomial::reconstruct_with_fixed(server, &shares[..2], 3).unwrap_err(),
+            Error::DuplicateX
+        );
+        assert_eq!(
+            Polynomial::reconstruct_with_fixed(server, &users[..1], 3).unwrap_err(),
+            Error::InsufficientShares { needed: 3, got: 2 }
+        );
+    }
+
+    #[test]
+    fn debug_redacts_coefficients() {
+        let dealer = Dealer::from_seed(3, FieldElement::new(1234), [11; 32]).unwrap();
+        let printed = format!("{:?}", dealer);
+        assert!(printed.contains("degree: 2"));
+        for c in &dealer.polynomial.coefficients {
+            assert!(!printed.contains(&format!("{:?}", c)));
+        }
+    }
+
+    #[test]
+    fn seeded_rng_reproduces_shares() {
+        let shares = |seed| {
+            let polynomial =
+                Polynomial::new(3, FieldElement::new(42), &mut ChaCha20Rng::from_seed(seed))
+                    .unwrap();
+            polynomial
+                .share(5)
+                .iter()
+                .map(|s| (s.x, s.y))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shares([7; 32]), shares([7; 32]));
+        assert_ne!(shares([7; 32]), shares([8; 32]));
+        assert_eq!(
+            FieldElement::random(&mut rng()),
+            FieldElement::random(&mut rng())
+        );
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// (t, n, start) for the t consecutive shares from `start` of n
+        fn window() -> impl Strategy<Value = (u64, u64, u64)> {
+            (2u64..8)
+                .prop_flat_map(|t| (Just(t), t..12))
+                .prop_flat_map(|(t, n)| (Just(t), Just(n), 0..=n - t))
+        }
+
+        proptest! {
+            #[test]
+            fn evaluates_to_the_secret_at_zero(
+                seed: [u8; 32],
+                secret: [u8; 32],
+                t in 2u64..10,
+            ) {
+                let s = FieldElement::hash_bytes(&secret);
+                let mut rng = ChaCha20Rng::from_seed(seed);
+                let polynomial = Polynomial::new(t, s, &mut rng).unwrap();
+                prop_assert_eq!(polynomial.evaluate(&FieldElement::zero()), s);
+                prop_assert_eq!(polynomial.coefficients.len() as u64, t);
+            }
+
+            #[test]
+            fn any_t_shares_reconstruct(
+                seed: [u8; 32],
+                secret: [u8; 32],
+                (t, n, start) in window(),
+            ) {
+                let s = FieldElement::hash_bytes(&secret);
+                let mut rng = ChaCha20Rng::from_seed(seed);
+                let polynomial = Polynomial::new(t, s, &mut rng).unwrap();
+                let shares = polynomial.share(n);
+                let subset = &shares[start as usize..(start + t) as usize];
+                prop_assert_eq!(Polynomial::reconstruct(subset).unwrap(), s);
+                let xs: Vec<FieldElement> = shares.iter().map(|s| s.x).collect();
+                let ys: Vec<FieldElement> = shares.iter().map(|s| s.y).collect();
+                prop_assert_eq!(polynomial.evaluate_batch(&xs), ys);
+            }
+        }
+    }
+
+    #[cfg(all(feature = "debug-audit", feature = "std"))]
+    mod audit {
+        use super::*;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Level, Metadata, Subscriber};
+
+        /// Counts the warnings the audit checks log
+        struct WarnCounter(Arc<AtomicUsize>);
+
+        impl Subscriber for WarnCounter {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                if *event.metadata().level() == Level::WARN {
+                    self.0.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        fn warnings(f: impl FnOnce()) -> usize {
+            let count = Arc::new(AtomicUsize::new(0));
+            tracing::subscriber::with_default(WarnCounter(count.clone()), f);
+            count.load(Ordering::SeqCst)
+        }
+
+        #[test]
+        fn counter_sees_warnings() {
+            assert_eq!(warnings(|| tracing::warn!("anomaly")), 1);
+        }
+
+        #[test]
+        fn correct_run_passes_the_audit() {
+            let runs = warnings(|| {
+                let secret = FieldElement::new(42);
+                let polynomial = Polynomial::new(4, secret, &mut rng()).unwrap();
+                let shares = polynomial.share(6);
+                assert_eq!(Polynomial::reconstruct(&shares[1..5]).unwrap(), secret);
+                assert_eq!(Polynomial::reconstruct(&shares).unwrap(), secret);
+
+                let mut elements: Vec<FieldElement> = (1..=8).map(FieldElement::new).collect();
+                FieldElement::batch_invert(&mut elements).unwrap();
+                for (x, inv) in (1..=8).zip(&elements) {
+                    assert_eq!(FieldElement::new(x) * inv, FieldElement::one());
+                }
+            });
+            assert_eq!(runs, 0);
+        }
+    }
+}
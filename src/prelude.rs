@@ -0,0 +1,28 @@
+//! The commonly used types and traits, for `use secret_sharing::prelude::*;`
+//!
+//! Free functions such as `split` and `combine` are left out, their names are too generic to
+//! glob-import without clashes.
+//!
+//! ```
+//! use secret_sharing::prelude::*;
+//! use secret_sharing::{reconstruct, split};
+//! use rand_chacha::ChaCha20Rng;
+//! use rand_core::SeedableRng;
+//!
+//! let mut rng = ChaCha20Rng::from_seed([0; 32]);
+//! let secret = String::from("correct horse battery staple");
+//! assert_eq!(String::decode(&secret.encode())?, secret);
+//! let shares = split(&secret, 2, 3, &mut rng)?;
+//! let subset: Vec<Vec<Share>> = shares.iter().map(|s| s[1..].to_vec()).collect();
+//! assert_eq!(reconstruct::<String>(&subset)?, secret);
+//! # Ok::<(), Error>(())
+//! ```
+
+pub use crate::encoding::{Secret, SecretEncoding};
+pub use crate::error::Error;
+pub use crate::field::FieldElement;
+pub use crate::polynomial::{Dealer, Polynomial};
+pub use crate::questionnair::{AnswerNormalizer, Questionnair, QuestionnairBuilder};
+pub use crate::share::Share;
+pub use crate::shares::{FramedShare, Shares};
+pub use ff::{Field, PrimeField};
@@ -0,0 +1,283 @@
+//! Publicly verifiable secret sharing after Schoenmakers: the dealer encrypts every share to its
+//! holder's public key, and anyone can check the encrypted shares against the commitments of
+//! `vss`, without a complaint round.
+//!
+//! Holder i has the key pair (sk_i, y_i = h^{sk_i}) for the second generator h. The dealer
+//! publishes Feldman commitments to f, the encrypted shares Y_i = y_i^{f(i)} and a proof that
+//! log_g X_i = log_{y_i} Y_i for X_i = g^{f(i)}, the commitment to share i. Holder i decrypts
+//! S_i = Y_i^{1/sk_i} = h^{f(i)} with a proof that it used its key, and t decrypted shares
+//! interpolate the secret h^s in the exponent. The secret is a group element, `shared_key`
+//! turns it into a symmetric key.
+
+use crate::curves::Ed25519Scalar;
+use crate::error::Error;
+use crate::polynomial::{lagrange_coefficients, Polynomial};
+use crate::vss::{Commitments, GroupElement};
+use ff::Field;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+/// Everything the dealer publishes, holder j's values at position j - 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Dealing {
+    pub commitments: Commitments,
+    pub encrypted_shares: Vec<GroupElement>,
+    /// The common challenge of the proofs
+    pub challenge: Ed25519Scalar,
+    /// The responses of the proofs, one per holder
+    pub responses: Vec<Ed25519Scalar>,
+}
+
+/// Holder `index`'s decrypted share h^{f(index)}, with its proof of correct decryption
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecryptedShare {
+    pub index: usize,
+    pub share: GroupElement,
+    pub challenge: Ed25519Scalar,
+    pub response: Ed25519Scalar,
+}
+
+/// The public key h^{sk} of a holder's secret key
+pub fn public_key(secret_key: &Ed25519Scalar) -> GroupElement {
+    GroupElement::blinding_generator().pow(secret_key)
+}
+
+/// Share a random secret h^s among the holders of the public keys, in holder order, t of which
+/// recover it. Returns the secret for the dealer and the dealing to publish
+pub fn deal<R: RngCore + CryptoRng>(
+    t: u64,
+    public_keys: &[GroupElement],
+    rng: &mut R,
+) -> Result<(GroupElement, Dealing), Error> {
+    if t as usize > public_keys.len() {
+        return Err(Error::InvalidThreshold);
+    }
+    let mut s = Ed25519Scalar::random(&mut *rng);
+    let polynomial = Polynomial::new(t, s, rng)?;
+    let secret = GroupElement::blinding_generator().pow(&s);
+    s.zeroize();
+    let commitments = Commitments::new(&polynomial);
+    let g = GroupElement::generator();
+    let mut encrypted_shares = Vec::with_capacity(public_keys.len());
+    let mut rows = Vec::with_capacity(public_keys.len());
+    let mut ys = Vec::with_capacity(public_keys.len());
+    let mut ws = Vec::with_capacity(public_keys.len());
+    for (i, y_i) in public_keys.iter().enumerate() {
+        let y = polynomial.evaluate(&Ed25519Scalar::from(i as u64 + 1));
+        let encrypted = y_i.pow(&y);
+        let w = Ed25519Scalar::random(&mut *rng);
+        rows.push([
+            g.pow(&y),
+            y_i.clone(),
+            encrypted.clone(),
+            g.pow(&w),
+            y_i.pow(&w),
+        ]);
+        encrypted_shares.push(encrypted);
+        ys.push(y);
+        ws.push(w);
+    }
+    let challenge = dealing_challenge(commitments.threshold(), &rows);
+    let responses = ws
+        .iter()
+        .zip(&ys)
+        .map(|(w, y)| *w + challenge * y)
+        .collect();
+    ys.zeroize();
+    ws.zeroize();
+    Ok((
+        secret,
+        Dealing {
+            commitments,
+            encrypted_shares,
+            challenge,
+            responses,
+        },
+    ))
+}
+
+impl Dealing {
+    /// Checks that every encrypted share is the committed share encrypted to its holder, which
+    /// anyone can do before the holders decrypt
+    pub fn verify(&self, public_keys: &[GroupElement]) -> bool {
+        let n = public_keys.len();
+        if self.encrypted_shares.len() != n
+            || self.responses.len() != n
+            || self.commitments.threshold() > n
+        {
+            return false;
+        }
+        let g = GroupElement::generator();
+        let minus_c = -self.challenge;
+        // g^z = a1 X^c and y^z = a2 Y^c, so the announcements are recomputed
+        let rows: Vec<[GroupElement; 5]> = public_keys
+            .iter()
+            .zip(&self.encrypted_shares)
+            .zip(&self.responses)
+            .enumerate()
+            .map(|(i, ((y_i, encrypted), z))| {
+                let x = self
+                    .commitments
+                    .share_commitment(&Ed25519Scalar::from(i as u64 + 1));
+                let a1 = g.pow(z) * x.pow(&minus_c);
+                let a2 = y_i.pow(z) * encrypted.pow(&minus_c);
+                [x, y_i.clone(), encrypted.clone(), a1, a2]
+            })
+            .collect();
+        dealing_challenge(self.commitments.threshold(), &rows) == self.challenge
+    }
+}
+
+/// Holder `index` decrypts its share of the dealing with its secret key, proving
+/// log_h y_i = log_{S_i} Y_i
+pub fn decrypt_share<R: RngCore + CryptoRng>(
+    dealing: &Dealing,
+    index: usize,
+    secret_key: &Ed25519Scalar,
+    rng: &mut R,
+) -> Result<DecryptedShare, Error> {
+    let encrypted = index
+        .checked_sub(1)
+        .and_then(|i| dealing.encrypted_shares.get(i))
+        .ok_or(Error::UnknownParty { index })?;
+    let mut inverse =
+        Option::<Ed25519Scalar>::from(secret_key.invert()).ok_or(Error::NotInvertible)?;
+    let share = encrypted.pow(&inverse);
+    inverse.zeroize();
+    let h = GroupElement::blinding_generator();
+    let mut w = Ed25519Scalar::random(&mut *rng);
+    let challenge = decryption_challenge(
+        &public_key(secret_key),
+        encrypted,
+        &share,
+        &h.pow(&w),
+        &share.pow(&w),
+    );
+    let response = w + challenge * secret_key;
+    w.zeroize();
+    Ok(DecryptedShare {
+        index,
+        share,
+        challenge,
+        response,
+    })
+}
+
+/// Checks the proof of the decrypted share against the dealing and the holder's public key
+pub fn verify_decrypted_share(
+    decrypted: &DecryptedShare,
+    dealing: &Dealing,
+    public_key: &GroupElement,
+) -> bool {
+    let encrypted = match decrypted
+        .index
+        .checked_sub(1)
+        .and_then(|i| dealing.encrypted_shares.get(i))
+    {
+        Some(encrypted) => encrypted,
+        None => return false,
+    };
+    let minus_c = -decrypted.challenge;
+    let a1 = GroupElement::blinding_generator().pow(&decrypted.response) * public_key.pow(&minus_c);
+    let a2 = decrypted.share.pow(&decrypted.response) * encrypted.pow(&minus_c);
+    decryption_challenge(public_key, encrypted, &decrypted.share, &a1, &a2) == decrypted.challenge
+}
+
+/// Recover the secret h^s from at least t decrypted shares, which should be verified first
+pub fn combine(decrypted: &[DecryptedShare]) -> Result<GroupElement, Error> {
+    let xs: Vec<Ed25519Scalar> = decrypted
+        .iter()
+        .map(|s| Ed25519Scalar::from(s.index as u64))
+        .collect();
+    if xs.iter().any(|x| x.is_zero()) {
+        return Err(Error::ZeroX);
+    }
+    lagrange_coefficients(&xs, &Ed25519Scalar::zero())?
+        .iter()
+        .zip(decrypted)
+        .map(|(l, s)| s.share.pow(l))
+        .reduce(|acc, e| acc * e)
+        .ok_or(Error::InsufficientShares { needed: 1, got: 0 })
+}
+
+/// SHA-256(domain || h^s), a 256-bit key from the shared secret
+pub fn shared_key(secret: &GroupElement) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"polynomials pvss key");
+    hasher.update(secret.to_bytes());
+    hasher.finalize().into()
+}
+
+/// c = H(t || X_i, y_i, Y_i, a1_i, a2_i for all i)
+fn dealing_challenge(threshold: usize, rows: &[[GroupElement; 5]]) -> Ed25519Scalar {
+    let mut input = b"polynomials pvss dealing".to_vec();
+    input.extend_from_slice(&(threshold as u64).to_le_bytes());
+    for e in rows.iter().flatten() {
+        let bytes = e.to_bytes();
+        input.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        input.extend_from_slice(&bytes);
+    }
+    Ed25519Scalar::hash_bytes(&input)
+}
+
+/// e = H(y_i || Y_i || S_i || a1 || a2)
+fn decryption_challenge(
+    public_key: &GroupElement,
+    encrypted: &GroupElement,
+    share: &GroupElement,
+    a1: &GroupElement,
+    a2: &GroupElement,
+) -> Ed25519Scalar {
+    let mut input = b"polynomials pvss decryption".to_vec();
+    for e in [public_key, encrypted, share, a1, a2] {
+        let bytes = e.to_bytes();
+        input.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        input.extend_from_slice(&bytes);
+    }
+    Ed25519Scalar::hash_bytes(&input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn verified_decrypted_shares_recover_the_secret() {
+        let mut rng = ChaCha20Rng::from_seed([11; 32]);
+        let secret_keys: Vec<Ed25519Scalar> =
+            (0..4).map(|_| Ed25519Scalar::random(&mut rng)).collect();
+        let public_keys: Vec<GroupElement> = secret_keys.iter().map(public_key).collect();
+        let (secret, dealing) = deal(3, &public_keys, &mut rng).unwrap();
+        assert!(dealing.verify(&public_keys));
+        let decrypted: Vec<DecryptedShare> = secret_keys
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, sk)| decrypt_share(&dealing, i + 1, sk, &mut rng).unwrap())
+            .collect();
+        for share in &decrypted {
+            assert!(verify_decrypted_share(
+                share,
+                &dealing,
+                &public_keys[share.index - 1]
+            ));
+        }
+        assert!(!verify_decrypted_share(
+            &decrypted[0],
+            &dealing,
+            &public_keys[0]
+        ));
+        assert_eq!(combine(&decrypted).unwrap(), secret);
+        assert_eq!(
+            shared_key(&combine(&decrypted).unwrap()),
+            shared_key(&secret)
+        );
+    }
+}
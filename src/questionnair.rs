@@ -0,0 +1,1547 @@
+use crate::error::Error;
+use crate::field::FieldElement;
+use crate::polynomial::{Dealer, Polynomial, SecretValidator};
+use crate::share::Share;
+use alloc::{collections::BTreeMap, string::String, string::ToString, vec, vec::Vec};
+use argon2::{Algorithm, Argon2, Params, Version};
+use ff::Field;
+use hkdf::Hkdf;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use subtle::ConstantTimeEq;
+use unicode_normalization::UnicodeNormalization;
+use zeroize::{Zeroize, Zeroizing};
+
+/// A language tag such as "en" or "pt-BR", as used by the recovery UI
+pub type LocaleCode = String;
+
+/// The largest proof of work difficulty, the bits of a SHA-256 hash
+pub const MAX_POW_DIFFICULTY: u32 = 256;
+
+/// Represents a Questionnair
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Questionnair {
+    questions: Vec<String>,
+    tags: Vec<[u8; 32]>,
+    points: Vec<FieldElement>,
+    /// The x-coordinate of the share behind each point
+    xs: Vec<FieldElement>,
+    /// Maps each question's answer to the canonical form that is hashed
+    normalizers: Vec<AnswerNormalizer>,
+    /// Leading zero bits required of the proof of work hash by `answer_with_pow`
+    pow_difficulty: u32,
+    /// Number of correct answers needed to recover the secret
+    threshold: usize,
+    /// Stretches the canonical answers before the keys and tags are derived
+    kdf: AnswerKdf,
+    /// Random salt of each question, so equal answers to different questions look unrelated
+    salts: Vec<[u8; 16]>,
+    /// Optional hint shown with each question
+    hints: Vec<Option<String>>,
+    /// Questions that have to be answered correctly, whatever the other answers
+    required: Vec<bool>,
+    /// Version of the questionnair set by its author
+    version: u32,
+    /// The question text in other languages, one per question
+    /// Tags and points do not depend on the language, so an answer is accepted whichever text
+    /// it was given to
+    localizations: BTreeMap<LocaleCode, Vec<String>>,
+    /// Rules of a locale applied to answers given in it before the question's normalizer, e.g.
+    /// Turkish case folding, so the canonical answer is the same in every language
+    #[cfg_attr(feature = "serde", serde(default))]
+    locale_normalizers: BTreeMap<LocaleCode, AnswerNormalizer>,
+    /// Checked on the recovered secret, not serialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    validator: Option<SecretValidator>,
+}
+
+impl Questionnair {
+    /// Create random polynomial
+    /// Get Share
+    /// Answers are compared after the default `AnswerNormalizer`
+    pub fn new<R: RngCore + CryptoRng>(
+        s: FieldElement,
+        questions: Vec<&str>,
+        answers: Vec<&str>,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        let normalizers = vec![AnswerNormalizer::default(); questions.len()];
+        Questionnair::with_normalizers(s, questions, answers, normalizers, rng)
+    }
+
+    /// Same as `new`, but any t of the questions answered correctly recover the secret
+    pub fn with_threshold<R: RngCore + CryptoRng>(
+        s: FieldElement,
+        t: u64,
+        questions: Vec<&str>,
+        answers: Vec<&str>,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        let normalizers = vec![AnswerNormalizer::default(); questions.len()];
+        let dealer = Dealer::new(t, s, rng)?;
+        Questionnair::from_dealer(&dealer, questions, answers, normalizers, rng)
+    }
+
+    /// Same as `new`, but the answer to question i is only compared after `normalizers[i]`
+    /// Every answer mapping to the same canonical form is accepted, so a looser normalizer
+    /// lowers the entropy of that answer and makes guessing easier
+    pub fn with_normalizers<R: RngCore + CryptoRng>(
+        s: FieldElement,
+        questions: Vec<&str>,
+        answers: Vec<&str>,
+        normalizers: Vec<AnswerNormalizer>,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        let dealer = Dealer::new(questions.len() as u64, s, rng)?;
+        Questionnair::from_dealer(&dealer, questions, answers, normalizers, rng)
+    }
+
+    /// Same as `with_normalizers` using the dealer's polynomial, whose threshold is the number of
+    /// correct answers needed. Keeping the dealer allows adding questions later with `add_question`
+    pub fn from_dealer<R: RngCore + CryptoRng>(
+        dealer: &Dealer,
+        questions: Vec<&str>,
+        answers: Vec<&str>,
+        normalizers: Vec<AnswerNormalizer>,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        let kdf = AnswerKdf::Sha256;
+        Questionnair::with_kdf(dealer, questions, answers, normalizers, kdf, rng)
+    }
+
+    /// Same as `from_dealer`, stretching every answer with the KDF so that each guess of an
+    /// attacker holding the questionnair costs one KDF evaluation
+    pub fn with_kdf<R: RngCore + CryptoRng>(
+        dealer: &Dealer,
+        questions: Vec<&str>,
+        answers: Vec<&str>,
+        normalizers: Vec<AnswerNormalizer>,
+        kdf: AnswerKdf,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        let questions = questions.into_iter().map(String::from).collect();
+        let required = vec![false; answers.len()];
+        Questionnair::deal(dealer, questions, &answers, normalizers, kdf, required, rng)
+    }
+
+    /// Encrypt the dealer's shares under the answers
+    /// The points of optional questions are additionally masked by the required answers, so no
+    /// set of answers missing a required one recovers the secret
+    fn deal<R: RngCore + CryptoRng>(
+        dealer: &Dealer,
+        questions: Vec<String>,
+        answers: &[&str],
+        normalizers: Vec<AnswerNormalizer>,
+        kdf: AnswerKdf,
+        required: Vec<bool>,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        if answers.len() != questions.len()
+            || normalizers.len() != questions.len()
+            || required.len() != questions.len()
+        {
+            return Err(Error::LengthMismatch);
+        }
+        let threshold = dealer.polynomial.degree as usize + 1;
+        if threshold > questions.len() {
+            return Err(Error::InvalidThreshold);
+        }
+        let degree = questions.len();
+        let shares = dealer.polynomial.share(degree as u64);
+        let mut tags = Vec::new();
+        let mut points = Vec::new();
+        let mut xs = Vec::new();
+        let mut salts = Vec::new();
+        let mut stretched = Vec::new();
+        for ans in 0..degree {
+            let canonical = canonical_answer(normalizers[ans], answers[ans]);
+            let salt = random_salt(rng);
+            stretched.push(kdf.stretch(&salt, &canonical)?);
+            salts.push(salt);
+        }
+        let mask = required_mask(
+            stretched
+                .iter()
+                .zip(&required)
+                .filter(|(_, r)| **r)
+                .map(|(a, _)| &a[..]),
+        );
+
+        for ans in 0..degree {
+            let mut key = FieldElement::hash_bytes(&stretched[ans]);
+            if !required[ans] {
+                key += mask;
+            }
+            points.push(shares[ans].y + key);
+            xs.push(shares[ans].x);
+
+            let tag = tag_from_answer(&stretched[ans]);
+            tags.push(tag);
+        }
+        Ok(Questionnair {
+            hints: vec![None; questions.len()],
+            questions,
+            tags,
+            points,
+            xs,
+            normalizers,
+            pow_difficulty: 0,
+            threshold,
+            kdf,
+            salts,
+            required,
+            version: 0,
+            localizations: BTreeMap::new(),
+            locale_normalizers: BTreeMap::new(),
+            validator: None,
+        })
+    }
+
+    /// The questions, in the order their answers are expected
+    pub fn questions(&self) -> &[String] {
+        &self.questions
+    }
+
+    /// The number of correct answers needed to recover the secret
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// The hint of each question, if any
+    pub fn hints(&self) -> &[Option<String>] {
+        &self.hints
+    }
+
+    /// Whether each question has to be answered correctly
+    pub fn required(&self) -> &[bool] {
+        &self.required
+    }
+
+    /// Check every recovered secret with the validator, answering fails with
+    /// `Error::InvalidSecretForDomain` if it rejects it
+    /// The validator is not serialized, set it again after reading a questionnair
+    pub fn set_validator(&mut self, validator: SecretValidator) {
+        self.validator = Some(validator);
+    }
+
+    /// The version set by the author, 0 unless built with a `QuestionnairBuilder`
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Checks that the per-question data of a questionnair read from untrusted input is
+    /// consistent, so answering it fails with errors instead of panicking
+    pub fn validate(&self) -> Result<(), Error> {
+        let n = self.questions.len();
+        if self.tags.len() != n
+            || self.points.len() != n
+            || self.xs.len() != n
+            || self.normalizers.len() != n
+            || self.salts.len() != n
+            || self.hints.len() != n
+            || self.required.len() != n
+            || self
+                .localizations
+                .values()
+                .any(|questions| questions.len() != n)
+        {
+            return Err(Error::InvalidEncoding);
+        }
+        if self.threshold == 0 || self.threshold > n {
+            return Err(Error::InvalidThreshold);
+        }
+        if self.pow_difficulty > MAX_POW_DIFFICULTY {
+            return Err(Error::InvalidProofOfWork);
+        }
+        self.kdf.check_params()
+    }
+
+    /// Read a questionnair serialized as JSON, rejecting malformed and inconsistent ones
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let questionnair: Questionnair =
+            serde_json::from_str(json).map_err(|_| Error::InvalidEncoding)?;
+        questionnair.validate()?;
+        Ok(questionnair)
+    }
+
+    /// The questions in the locale, or the default questions if there is no text for it
+    pub fn localized(&self, locale: &str) -> &[String] {
+        self.localizations
+            .get(locale)
+            .map(Vec::as_slice)
+            .unwrap_or(&self.questions)
+    }
+
+    /// The locales with question text besides the default
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.localizations.keys().map(String::as_str)
+    }
+
+    /// Add or replace the text of every question in the locale, in question order
+    pub fn add_locale(
+        &mut self,
+        locale: impl Into<LocaleCode>,
+        questions: Vec<String>,
+    ) -> Result<(), Error> {
+        if questions.len() != self.questions.len() {
+            return Err(Error::LengthMismatch);
+        }
+        self.localizations.insert(locale.into(), questions);
+        Ok(())
+    }
+
+    /// Normalize answers given in the locale with the normalizer before the question's own, see
+    /// `answer_report_localized`
+    pub fn set_locale_normalizer(
+        &mut self,
+        locale: impl Into<LocaleCode>,
+        normalizer: AnswerNormalizer,
+    ) {
+        self.locale_normalizers.insert(locale.into(), normalizer);
+    }
+
+    /// Lets user answer some of the questions, given as (question index, answer)
+    /// Wrong answers are skipped, at least threshold answers have to be correct
+    pub fn answer_subset(&self, answers: &[(usize, &str)]) -> Result<FieldElement, Error> {
+        self.secret_from(self.answer_report(answers)?)
+    }
+
+    /// The secret of the report, or why answering failed
+    fn secret_from(&self, report: AnswerReport) -> Result<FieldElement, Error> {
+        match (report.secret, report.failed.first()) {
+            (Some(secret), _) => Ok(secret),
+            (None, Some(index)) => Err(Error::WrongAnswer { index: *index }),
+            (None, None) => match report.unanswered.iter().find(|i| self.required[**i]) {
+                Some(index) => Err(Error::RequiredUnanswered { index: *index }),
+                None => Err(Error::InsufficientShares {
+                    needed: self.threshold,
+                    got: report.passed.len(),
+                }),
+            },
+        }
+    }
+
+    /// Checks every given answer against its tag and reconstructs the secret from the correct
+    /// ones if there are at least threshold of them, including every required question
+    /// Fails only for unknown or repeated question indices, and with `InvalidProofOfWork` if
+    /// the questionnair requires a proof of work, see `answer_with_pow`
+    pub fn answer_report(&self, answers: &[(usize, &str)]) -> Result<AnswerReport, Error> {
+        self.check_no_pow()?;
+        self.report(answers, None)
+    }
+
+    /// `answer_report` for answers given in the locale, normalized first by the locale's
+    /// normalizer if it has one
+    pub fn answer_report_localized(
+        &self,
+        locale: &str,
+        answers: &[(usize, &str)],
+    ) -> Result<AnswerReport, Error> {
+        self.check_no_pow()?;
+        self.report(answers, Some(locale))
+    }
+
+    /// `answer_report` without the proof of work check
+    fn report(
+        &self,
+        answers: &[(usize, &str)],
+        locale: Option<&str>,
+    ) -> Result<AnswerReport, Error> {
+        let mut report = AnswerReport {
+            passed: Vec::new(),
+            failed: Vec::new(),
+            unanswered: Vec::new(),
+            secret: None,
+        };
+        let mut points = Vec::with_capacity(answers.len());
+        let mut keys = Zeroizing::new(Vec::with_capacity(answers.len()));
+        let mut xs = Vec::with_capacity(answers.len());
+        let mut required = Vec::new();
+        for (k, (i, ans)) in answers.iter().enumerate() {
+            if *i >= self.questions.len() {
+                return Err(Error::UnknownQuestionIndex { index: *i });
+            }
+            if answers[..k].iter().any(|(j, _)| j == i) {
+                return Err(Error::RepeatedAnswer { index: *i });
+            }
+            let ans = match self.check(*i, ans, locale)? {
+                Some(ans) => ans,
+                None => {
+                    report.failed.push(*i);
+                    continue;
+                }
+            };
+            report.passed.push(*i);
+            // key to decrypt points
+            points.push(self.points[*i]);
+            keys.push(FieldElement::hash_bytes(&ans));
+            xs.push(self.xs[*i]);
+            if self.required[*i] {
+                required.push((*i, ans));
+            }
+        }
+        report.unanswered = (0..self.questions.len())
+            .filter(|i| !answers.iter().any(|(j, _)| j == i))
+            .collect();
+        let required_count = self.required.iter().filter(|r| **r).count();
+        if report.passed.len() >= self.threshold && required.len() == required_count {
+            required.sort_by_key(|(i, _)| *i);
+            let mask = required_mask(required.iter().map(|(_, a)| &a[..]));
+            for (key, i) in keys.iter_mut().zip(&report.passed) {
+                if !self.required[*i] {
+                    *key += mask;
+                }
+            }
+            let secret = reconstruct_from_points(&points, &keys, &xs)?;
+            if self.validator.is_some_and(|validator| !validator(&secret)) {
+                return Err(Error::InvalidSecretForDomain);
+            }
+            report.secret = Some(secret);
+        }
+        Ok(report)
+    }
+
+    /// Checks every given answer against its tag without reconstructing the secret, reporting
+    /// how many more correct answers are needed and the hints of the questions not yet passed
+    /// Fails only for unknown or repeated question indices, and with `InvalidProofOfWork` if
+    /// the questionnair requires a proof of work, as grading would check answers without it
+    pub fn grade(&self, answers: &[(usize, &str)]) -> Result<GradeReport, Error> {
+        self.check_no_pow()?;
+        self.grade_in(answers, None)
+    }
+
+    /// `grade` for answers given in the locale, see `answer_report_localized`
+    pub fn grade_localized(
+        &self,
+        locale: &str,
+        answers: &[(usize, &str)],
+    ) -> Result<GradeReport, Error> {
+        self.check_no_pow()?;
+        self.grade_in(answers, Some(locale))
+    }
+
+    fn grade_in(
+        &self,
+        answers: &[(usize, &str)],
+        locale: Option<&str>,
+    ) -> Result<GradeReport, Error> {
+        let mut passed = Vec::new();
+        let mut failed = Vec::new();
+        for (k, (i, ans)) in answers.iter().enumerate() {
+            if *i >= self.questions.len() {
+                return Err(Error::UnknownQuestionIndex { index: *i });
+            }
+            if answers[..k].iter().any(|(j, _)| j == i) {
+                return Err(Error::RepeatedAnswer { index: *i });
+            }
+            match self.check(*i, ans, locale)? {
+                Some(_) => passed.push(*i),
+                None => failed.push(*i),
+            }
+        }
+        let unanswered: Vec<usize> = (0..self.questions.len())
+            .filter(|i| !answers.iter().any(|(j, _)| j == i))
+            .collect();
+        let required_missing: Vec<usize> = (0..self.questions.len())
+            .filter(|i| self.required[*i] && !passed.contains(i))
+            .collect();
+        let hints = (0..self.questions.len())
+            .filter(|i| !passed.contains(i))
+            .filter_map(|i| self.hints[i].clone().map(|hint| (i, hint)))
+            .collect();
+        Ok(GradeReport {
+            needed: self
+                .threshold
+                .saturating_sub(passed.len())
+                .max(required_missing.len()),
+            passed,
+            failed,
+            unanswered,
+            required_missing,
+            hints,
+        })
+    }
+
+    /// The stretched answer to question i, given in the locale if any, if it matches the tag
+    fn check(
+        &self,
+        i: usize,
+        ans: &str,
+        locale: Option<&str>,
+    ) -> Result<Option<Zeroizing<Vec<u8>>>, Error> {
+        let localized = locale
+            .and_then(|l| self.locale_normalizers.get(l))
+            .map(|normalize| Zeroizing::new(normalize.apply(ans)));
+        let ans = localized.as_deref().map_or(ans, String::as_str);
+        let ans = canonical_answer(self.normalizers[i], ans);
+        let ans = self.kdf.stretch(&self.salts[i], &ans)?;
+        // constant time, so timing does not reveal how much of a guessed tag matches
+        if bool::from(tag_from_answer(&ans).ct_eq(&self.tags[i])) {
+            Ok(Some(ans))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Lets user answer the questionnair with answers keyed by question text
+    /// Answers are aligned to the questions, which therefore have to be distinct
+    #[cfg(feature = "std")]
+    pub fn answer_map(&self, answers: HashMap<&str, &str>) -> Result<FieldElement, Error> {
+        for (i, q) in self.questions.iter().enumerate() {
+            if self.questions[..i].contains(q) {
+                return Err(Error::DuplicateQuestion(q.clone()));
+            }
+        }
+        if let Some(q) = answers
+            .keys()
+            .find(|q| !self.questions.iter().any(|x| x == *q))
+        {
+            return Err(Error::UnknownQuestion(q.to_string()));
+        }
+        let indexed: Vec<(usize, &str)> = self
+            .questions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, q)| answers.get(q.as_str()).map(|ans| (i, *ans)))
+            .collect();
+        self.answer_subset(&indexed)
+    }
+
+    /// Lets user answer the questionnair, returning a 32-byte key derived from the secret with
+    /// HKDF instead of the secret itself
+    pub fn answer_to_key(&self, answers: Vec<&str>) -> Result<[u8; 32], Error> {
+        let mut secret = answer(self, answers)?;
+        let ikm = Zeroizing::new(secret.to_bytes());
+        secret.zeroize();
+        let hk = Hkdf::<Sha256>::new(None, &*ikm);
+        let mut key = [0u8; 32];
+        hk.expand(b"polynomials questionnair key", &mut key)
+            .expect("32 bytes is a valid length for HKDF-SHA256");
+        Ok(key)
+    }
+
+    /// Add a question, so a t-of-n questionnair becomes (t+1)-of-(n+1): one more correct answer
+    /// is needed. The secret stays the same, but the dealer's polynomial is replaced by a fresh
+    /// one of one degree more and every point is recomputed, so the old and new questionnair
+    /// together reveal nothing beyond either of them.
+    /// This requires the dealer holding the polynomial the questionnair was created from, shares
+    /// it issued before are no longer valid. Fails with `InvalidThreshold` if the dealer's
+    /// degree does not match the threshold, and if any question is required, as the new point
+    /// would need the mask derived from the required answers
+    pub fn add_question<R: RngCore + CryptoRng>(
+        &mut self,
+        dealer: &mut Dealer,
+        question: &str,
+        answer: &str,
+        normalize: AnswerNormalizer,
+        rng: &mut R,
+    ) -> Result<(), Error> {
+        if self.required.contains(&true) {
+            return Err(Error::HasRequiredQuestions);
+        }
+        if dealer.polynomial.degree + 1 != self.threshold as u64 {
+            return Err(Error::InvalidThreshold);
+        }
+        let canonical = canonical_answer(normalize, answer);
+        let salt = random_salt(rng);
+        let stretched = self.kdf.stretch(&salt, &canonical)?;
+        let keys: Zeroizing<Vec<FieldElement>> = self
+            .xs
+            .iter()
+            .zip(&self.points)
+            .map(|(x, point)| *point - dealer.polynomial.evaluate(x))
+            .collect::<Vec<FieldElement>>()
+            .into();
+        let secret = dealer.polynomial.evaluate(&FieldElement::zero());
+        dealer.polynomial = Polynomial::new(self.threshold as u64 + 1, secret, rng)?;
+        for ((point, x), key) in self.points.iter_mut().zip(&self.xs).zip(keys.iter()) {
+            *point = dealer.polynomial.evaluate(x) + key;
+        }
+
+        // the first x from n + 1 on that no question uses
+        let x = (self.points.len() as u64 + 1..)
+            .map(FieldElement::new)
+            .find(|x| !self.xs.contains(x))
+            .expect("fewer questions than field elements");
+        self.points
+            .push(dealer.polynomial.evaluate(&x) + FieldElement::hash_bytes(&stretched));
+        self.xs.push(x);
+        self.tags.push(tag_from_answer(&stretched));
+        self.salts.push(salt);
+        self.hints.push(None);
+        self.required.push(false);
+        self.questions.push(question.to_string());
+        for questions in self.localizations.values_mut() {
+            questions.push(question.to_string());
+        }
+        self.normalizers.push(normalize);
+        self.threshold += 1;
+        Ok(())
+    }
+
+    /// Require a proof of work of `difficulty` leading zero bits, the questionnair can then only
+    /// be answered with `answer_with_pow`
+    /// This makes every guess cost about 2^difficulty hashes. Fails above `MAX_POW_DIFFICULTY`,
+    /// which no SHA-256 hash can meet
+    pub fn require_pow(&mut self, difficulty: u32) -> Result<(), Error> {
+        if difficulty > MAX_POW_DIFFICULTY {
+            return Err(Error::InvalidProofOfWork);
+        }
+        self.pow_difficulty = difficulty;
+        Ok(())
+    }
+
+    /// Find a nonce for the answers satisfying the proof of work
+    pub fn solve_pow(&self, answers: &[&str]) -> u64 {
+        (0..)
+            .find(|nonce| leading_zero_bits(&self.pow_hash(*nonce, answers)) >= self.pow_difficulty)
+            .expect("a nonce exists")
+    }
+
+    /// Lets user answer the questionnair after checking the proof of work for the answers
+    /// Empty answers count as unanswered, as in `answer`
+    pub fn answer_with_pow(&self, answers: Vec<&str>, nonce: u64) -> Result<FieldElement, Error> {
+        if leading_zero_bits(&self.pow_hash(nonce, &answers)) < self.pow_difficulty {
+            return Err(Error::InvalidProofOfWork);
+        }
+        let indexed: Vec<(usize, &str)> = answers
+            .into_iter()
+            .enumerate()
+            .filter(|(_, ans)| !ans.is_empty())
+            .collect();
+        self.secret_from(self.report(&indexed, None)?)
+    }
+
+    /// The answering paths other than `answer_with_pow` are closed once a proof of work is
+    /// required
+    fn check_no_pow(&self) -> Result<(), Error> {
+        if self.pow_difficulty > 0 {
+            return Err(Error::InvalidProofOfWork);
+        }
+        Ok(())
+    }
+
+    /// Computes H(domain || salt_1 || .. || nonce || len(a_1) || a_1 || ..), bound to this
+    /// questionnair by its random salts so a solved nonce cannot be reused for another one
+    fn pow_hash(&self, nonce: u64, answers: &[&str]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(b"polynomials questionnair pow");
+        for salt in &self.salts {
+            hasher.update(salt);
+        }
+        hasher.update(nonce.to_le_bytes());
+        for ans in answers {
+            hasher.update((ans.len() as u64).to_le_bytes());
+            hasher.update(ans);
+        }
+        hasher.finalize().into()
+    }
+}
+
+fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+    for b in hash {
+        bits += b.leading_zeros();
+        if *b != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+/// Applies the question's normalizer followed by Unicode NFC, so that differently encoded but
+/// identical answers (e.g. a composed and a decomposed "é") hash the same
+fn canonical_answer(normalize: AnswerNormalizer, ans: &str) -> Zeroizing<String> {
+    let normalized = Zeroizing::new(normalize.apply(ans));
+    Zeroizing::new(normalized.nfc().collect())
+}
+
+/// Key stretching applied to the canonical answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AnswerKdf {
+    /// No stretching, the answers are hashed once with SHA-256, so low-entropy answers are cheap
+    /// to brute-force offline
+    Sha256,
+    /// Argon2id with the memory cost in KiB, the number of iterations and the degree of
+    /// parallelism, salted with a random salt stored in the questionnair
+    Argon2id {
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+        salt: [u8; 16],
+    },
+}
+
+impl AnswerKdf {
+    /// The largest Argon2id memory cost accepted, 1 GiB
+    pub const MAX_MEMORY_KIB: u32 = 1 << 20;
+    /// The largest Argon2id number of iterations accepted
+    pub const MAX_ITERATIONS: u32 = 64;
+    /// The largest Argon2id degree of parallelism accepted
+    pub const MAX_PARALLELISM: u32 = 16;
+
+    /// Argon2id with the given costs and a random salt
+    pub fn argon2id<R: RngCore + CryptoRng>(
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        let mut salt = [0u8; 16];
+        rng.fill_bytes(&mut salt);
+        let kdf = AnswerKdf::Argon2id {
+            memory_kib,
+            iterations,
+            parallelism,
+            salt,
+        };
+        kdf.check_params()?;
+        Ok(kdf)
+    }
+
+    /// Checks that Argon2 accepts the costs and that they are within the caps, so a
+    /// questionnair read from untrusted input cannot make answering it exhaust memory or time
+    fn check_params(&self) -> Result<(), Error> {
+        match self {
+            AnswerKdf::Sha256 => Ok(()),
+            AnswerKdf::Argon2id {
+                memory_kib,
+                iterations,
+                parallelism,
+                ..
+            } => {
+                if *memory_kib > AnswerKdf::MAX_MEMORY_KIB
+                    || *iterations > AnswerKdf::MAX_ITERATIONS
+                    || *parallelism > AnswerKdf::MAX_PARALLELISM
+                {
+                    return Err(Error::InvalidKdfParams);
+                }
+                Params::new(*memory_kib, *iterations, *parallelism, Some(32))
+                    .map(|_| ())
+                    .map_err(|_| Error::InvalidKdfParams)
+            }
+        }
+    }
+
+    /// Argon2id with the OWASP recommended 19 MiB, 2 iterations and no parallelism
+    pub fn recommended<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        AnswerKdf::argon2id(
+            Params::DEFAULT_M_COST,
+            Params::DEFAULT_T_COST,
+            Params::DEFAULT_P_COST,
+            rng,
+        )
+        .expect("default Argon2 parameters are valid")
+    }
+
+    /// The stretched salted answer the key and tag are derived from
+    fn stretch(&self, salt: &[u8; 16], ans: &str) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let mut salted = Zeroizing::new(Vec::with_capacity(salt.len() + ans.len()));
+        salted.extend_from_slice(salt);
+        salted.extend_from_slice(ans.as_bytes());
+        match self {
+            AnswerKdf::Sha256 => Ok(salted),
+            AnswerKdf::Argon2id {
+                memory_kib,
+                iterations,
+                parallelism,
+                salt,
+            } => {
+                self.check_params()?;
+                let params = Params::new(*memory_kib, *iterations, *parallelism, Some(32))
+                    .map_err(|_| Error::InvalidKdfParams)?;
+                let mut out = Zeroizing::new(vec![0u8; 32]);
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(&salted, salt, &mut out)
+                    .map_err(|_| Error::InvalidKdfParams)?;
+                Ok(out)
+            }
+        }
+    }
+}
+
+/// Builds a questionnair at runtime from owned questions and answers, e.g. read from user input
+/// or a config file
+#[derive(Debug, Default)]
+pub struct QuestionnairBuilder {
+    questions: Vec<BuilderQuestion>,
+    threshold: Option<u64>,
+    kdf: Option<AnswerKdf>,
+    version: u32,
+    validator: Option<SecretValidator>,
+    locale_normalizers: BTreeMap<LocaleCode, AnswerNormalizer>,
+}
+
+#[derive(Debug)]
+struct BuilderQuestion {
+    question: String,
+    answer: Zeroizing<String>,
+    hint: Option<String>,
+    required: bool,
+    normalizer: AnswerNormalizer,
+    translations: Vec<(LocaleCode, String)>,
+}
+
+impl QuestionnairBuilder {
+    /// An empty builder
+    pub fn new() -> Self {
+        QuestionnairBuilder::default()
+    }
+
+    /// Add a question with its answer, compared after the default `AnswerNormalizer`
+    pub fn question(mut self, question: impl Into<String>, answer: impl Into<String>) -> Self {
+        self.questions.push(BuilderQuestion {
+            question: question.into(),
+            answer: Zeroizing::new(answer.into()),
+            hint: None,
+            required: false,
+            normalizer: AnswerNormalizer::default(),
+            translations: Vec::new(),
+        });
+        self
+    }
+
+    /// Add the text of the last added question in the locale
+    /// Questions without text in a locale fall back to their default text there
+    pub fn translation(
+        mut self,
+        locale: impl Into<LocaleCode>,
+        question: impl Into<String>,
+    ) -> Self {
+        if let Some(q) = self.questions.last_mut() {
+            q.translations.push((locale.into(), question.into()));
+        }
+        self
+    }
+
+    /// Normalize answers given in the locale with the normalizer before the question's own
+    pub fn locale_normalizer(
+        mut self,
+        locale: impl Into<LocaleCode>,
+        normalizer: AnswerNormalizer,
+    ) -> Self {
+        self.locale_normalizers.insert(locale.into(), normalizer);
+        self
+    }
+
+    /// Set the hint of the last added question
+    pub fn hint(mut self, hint: impl Into<String>) -> Self {
+        if let Some(q) = self.questions.last_mut() {
+            q.hint = Some(hint.into());
+        }
+        self
+    }
+
+    /// Make the last added question required, no answers recover the secret without it
+    pub fn required(mut self) -> Self {
+        if let Some(q) = self.questions.last_mut() {
+            q.required = true;
+        }
+        self
+    }
+
+    /// Set the normalizer of the last added question
+    pub fn normalizer(mut self, normalizer: AnswerNormalizer) -> Self {
+        if let Some(q) = self.questions.last_mut() {
+            q.normalizer = normalizer;
+        }
+        self
+    }
+
+    /// Number of correct answers needed, all questions by default
+    pub fn threshold(mut self, t: u64) -> Self {
+        self.threshold = Some(t);
+        self
+    }
+
+    /// Stretch the answers with the KDF, `AnswerKdf::Sha256` by default
+    pub fn kdf(mut self, kdf: AnswerKdf) -> Self {
+        self.kdf = Some(kdf);
+        self
+    }
+
+    /// Set the version of the questionnair
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Check the secret with the validator before splitting and when it is recovered, e.g. that
+    /// it is a valid private key of a curve
+    pub fn validator(mut self, validator: SecretValidator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Create the questionnair recovering s
+    /// The dealer is not kept, so questions cannot be added later
+    pub fn build<R: RngCore + CryptoRng>(
+        self,
+        s: FieldElement,
+        rng: &mut R,
+    ) -> Result<Questionnair, Error> {
+        if self.validator.is_some_and(|validator| !validator(&s)) {
+            return Err(Error::InvalidSecretForDomain);
+        }
+        let t = self.threshold.unwrap_or(self.questions.len() as u64);
+        let dealer = Dealer::new(t, s, rng)?;
+        let mut questionnair = Questionnair::deal(
+            &dealer,
+            self.questions.iter().map(|q| q.question.clone()).collect(),
+            &self
+                .questions
+                .iter()
+                .map(|q| q.answer.as_str())
+                .collect::<Vec<_>>(),
+            self.questions.iter().map(|q| q.normalizer).collect(),
+            self.kdf.unwrap_or(AnswerKdf::Sha256),
+            self.questions.iter().map(|q| q.required).collect(),
+            rng,
+        )?;
+        questionnair.hints = self.questions.iter().map(|q| q.hint.clone()).collect();
+        questionnair.version = self.version;
+        questionnair.validator = self.validator;
+        questionnair.locale_normalizers = self.locale_normalizers.clone();
+        let defaults = &questionnair.questions;
+        for (i, q) in self.questions.iter().enumerate() {
+            for (locale, text) in &q.translations {
+                questionnair
+                    .localizations
+                    .entry(locale.clone())
+                    .or_insert_with(|| defaults.clone())[i] = text.clone();
+            }
+        }
+        Ok(questionnair)
+    }
+}
+
+/// Outcome of answering a questionnair, by question index
+#[derive(Debug, Clone)]
+pub struct AnswerReport {
+    /// Answers matching their tag
+    pub passed: Vec<usize>,
+    /// Wrong answers
+    pub failed: Vec<usize>,
+    /// Questions without an answer
+    pub unanswered: Vec<usize>,
+    /// The secret, if at least threshold answers passed, including every required one
+    pub secret: Option<FieldElement>,
+}
+
+/// Partial credit for answers to a questionnair, by question index, see `Questionnair::grade`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GradeReport {
+    /// Answers matching their tag
+    pub passed: Vec<usize>,
+    /// Wrong answers
+    pub failed: Vec<usize>,
+    /// Questions without an answer
+    pub unanswered: Vec<usize>,
+    /// Required questions not answered correctly
+    pub required_missing: Vec<usize>,
+    /// Further correct answers needed, at least one per missing required question
+    pub needed: usize,
+    /// The hints of the questions not answered correctly
+    pub hints: Vec<(usize, String)>,
+}
+
+/// How an answer is normalized before it is hashed
+/// The steps run in the order NFKC, lowercase, whitespace handling
+/// Locale-specific rules such as `turkic_case` belong in a locale normalizer of the questionnair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnswerNormalizer {
+    /// Apply Unicode compatibility normalization, e.g. fullwidth "ＡＢ" becomes "AB"
+    pub nfkc: bool,
+    /// Ignore case
+    pub lowercase: bool,
+    /// Remove leading and trailing whitespace
+    pub trim: bool,
+    /// Replace every run of whitespace by a single space
+    pub collapse_spaces: bool,
+    /// Remove all whitespace, e.g. "newyork" matches "New York"
+    pub remove_whitespace: bool,
+    /// Lowercase I to ı and İ to i as in Turkish and Azerbaijani, where `lowercase` is set
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub turkic_case: bool,
+}
+
+impl AnswerNormalizer {
+    /// Case-sensitive, for answers such as passwords
+    pub const EXACT: AnswerNormalizer = AnswerNormalizer {
+        nfkc: false,
+        lowercase: false,
+        trim: false,
+        collapse_spaces: false,
+        remove_whitespace: false,
+        turkic_case: false,
+    };
+
+    /// Ignores case, e.g. for city names
+    pub const CASE_INSENSITIVE: AnswerNormalizer = AnswerNormalizer {
+        lowercase: true,
+        ..AnswerNormalizer::EXACT
+    };
+
+    /// Ignores case and whitespace
+    pub const LOOSE: AnswerNormalizer = AnswerNormalizer {
+        lowercase: true,
+        remove_whitespace: true,
+        ..AnswerNormalizer::EXACT
+    };
+
+    /// Turkish and Azerbaijani case folding, e.g. as the locale normalizer of "tr"
+    pub const TURKIC_CASE: AnswerNormalizer = AnswerNormalizer {
+        lowercase: true,
+        turkic_case: true,
+        ..AnswerNormalizer::EXACT
+    };
+
+    /// The canonical form of the answer
+    pub fn apply(self, ans: &str) -> String {
+        let mut ans = if self.nfkc {
+            ans.nfkc().collect()
+        } else {
+            ans.to_string()
+        };
+        if self.lowercase {
+            if self.turkic_case {
+                ans = ans
+                    .chars()
+                    .map(|c| match c {
+                        'I' => 'ı',
+                        'İ' => 'i',
+                        c => c,
+                    })
+                    .collect();
+            }
+            ans = ans.to_lowercase();
+        }
+        if self.trim {
+            ans = ans.trim().to_string();
+        }
+        if self.remove_whitespace {
+            ans.retain(|c| !c.is_whitespace());
+        } else if self.collapse_spaces {
+            let mut collapsed = String::with_capacity(ans.len());
+            for c in ans.chars() {
+                if !c.is_whitespace() {
+                    collapsed.push(c);
+                } else if !collapsed.ends_with(' ') {
+                    collapsed.push(' ');
+                }
+            }
+            ans = collapsed;
+        }
+        ans
+    }
+}
+
+/// Forgiving of differences that do not change a natural language answer: NFKC, lowercase,
+/// trimmed and with collapsed spaces
+impl Default for AnswerNormalizer {
+    fn default() -> Self {
+        AnswerNormalizer {
+            nfkc: true,
+            lowercase: true,
+            trim: true,
+            collapse_spaces: true,
+            remove_whitespace: false,
+            turkic_case: false,
+        }
+    }
+}
+
+/// The key masking the points of optional questions, H(len(a_1) || a_1 || ..) over the
+/// stretched required answers in question order, and zero without required questions
+fn required_mask<'a, I: Iterator<Item = &'a [u8]>>(required: I) -> FieldElement {
+    let mut buf = Zeroizing::new(b"polynomials questionnair required".to_vec());
+    let mut any = false;
+    for ans in required {
+        any = true;
+        buf.extend_from_slice(&(ans.len() as u64).to_le_bytes());
+        buf.extend_from_slice(ans);
+    }
+    if any {
+        FieldElement::hash_bytes(&buf)
+    } else {
+        FieldElement::zero()
+    }
+}
+
+fn random_salt<R: RngCore + CryptoRng>(rng: &mut R) -> [u8; 16] {
+    let mut salt = [0u8; 16];
+    rng.fill_bytes(&mut salt);
+    salt
+}
+
+/// Generates Authenticity tag by H(H(a_i));
+fn tag_from_answer(ans: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(ans);
+    let answer_hash = hasher.finalize_reset();
+    hasher.update(answer_hash);
+    hasher.finalize().into()
+}
+
+/// Lets user answer the questionnair
+/// First check if answers are correct
+/// Compute shares by calculating keys and decrypt points
+/// interpolation of shares to get secret
+/// Empty answers count as unanswered
+pub fn answer(questionnair: &Questionnair, answers: Vec<&str>) -> Result<FieldElement, Error> {
+    let indexed: Vec<(usize, &str)> = answers
+        .into_iter()
+        .enumerate()
+        .filter(|(_, ans)| !ans.is_empty())
+        .collect();
+    questionnair.answer_subset(&indexed)
+}
+
+/// Decrypt the points y + key with their keys and interpolate the resulting shares
+pub fn reconstruct_from_points(
+    points: &[FieldElement],
+    keys: &[FieldElement],
+    xs: &[FieldElement],
+) -> Result<FieldElement, Error> {
+    if points.len() != keys.len() || points.len() != xs.len() {
+        return Err(Error::LengthMismatch);
+    }
+    let shares: Vec<Share> = points
+        .iter()
+        .zip(keys)
+        .zip(xs)
+        .map(|((point, key), x)| Share {
+            x: *x,
+            y: *point - key,
+        })
+        .collect();
+    Polynomial::reconstruct(&shares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::from_seed([3; 32])
+    }
+
+    fn questionnair(secret: u64) -> Questionnair {
+        Questionnair::new(
+            FieldElement::new(secret),
+            vec!["pet", "city", "school"],
+            vec!["Rex", "Paris", "Hillside"],
+            &mut rng(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn answer_map_aligns_answers_by_question() {
+        let q = questionnair(42);
+        let answers: HashMap<&str, &str> =
+            vec![("school", "Hillside"), ("pet", "Rex"), ("city", "Paris")]
+                .into_iter()
+                .collect();
+        assert_eq!(q.answer_map(answers).unwrap(), FieldElement::new(42));
+    }
+
+    #[test]
+    fn answer_map_rejects_unknown_and_duplicate_questions() {
+        let q = questionnair(42);
+        let answers: HashMap<&str, &str> =
+            vec![("pet", "Rex"), ("car", "Mini")].into_iter().collect();
+        assert_eq!(
+            q.answer_map(answers),
+            Err(Error::UnknownQuestion("car".to_string()))
+        );
+        let q = Questionnair::new(
+            FieldElement::new(42),
+            vec!["pet", "pet"],
+            vec!["Rex", "Tom"],
+            &mut rng(),
+        )
+        .unwrap();
+        let answers: HashMap<&str, &str> = vec![("pet", "Rex")].into_iter().collect();
+        assert_eq!(
+            q.answer_map(answers),
+            Err(Error::DuplicateQuestion("pet".to_string()))
+        );
+    }
+
+    #[test]
+    fn loose_normalizer_matches_spacing_and_case() {
+        let q = Questionnair::with_normalizers(
+            FieldElement::new(7),
+            vec!["city", "pet"],
+            vec!["New York", "Rex"],
+            vec![AnswerNormalizer::LOOSE; 2],
+            &mut rng(),
+        )
+        .unwrap();
+        assert_eq!(
+            answer(&q, vec!["newyork", "rex"]).unwrap(),
+            FieldElement::new(7)
+        );
+        assert_eq!(
+            answer(&q, vec!["new jersey", "rex"]),
+            Err(Error::WrongAnswer { index: 0 })
+        );
+    }
+
+    #[test]
+    fn normalizers_apply_per_question() {
+        let q = Questionnair::with_normalizers(
+            FieldElement::new(9),
+            vec!["password", "city"],
+            vec!["Hunter2", "Lisbon"],
+            vec![AnswerNormalizer::EXACT, AnswerNormalizer::CASE_INSENSITIVE],
+            &mut rng(),
+        )
+        .unwrap();
+        assert_eq!(
+            answer(&q, vec!["Hunter2", "LISBON"]).unwrap(),
+            FieldElement::new(9)
+        );
+        assert_eq!(
+            answer(&q, vec!["hunter2", "LISBON"]),
+            Err(Error::WrongAnswer { index: 0 })
+        );
+    }
+
+    #[test]
+    fn builder_validator_rejects_zero_secrets() {
+        let nonzero: SecretValidator = |s| !s.is_zero();
+        let builder = || {
+            QuestionnairBuilder::new()
+                .question("pet", "Rex")
+                .question("city", "Paris")
+                .validator(nonzero)
+        };
+        assert_eq!(
+            builder()
+                .build(FieldElement::zero(), &mut rng())
+                .unwrap_err(),
+            Error::InvalidSecretForDomain
+        );
+        let q = builder().build(FieldElement::new(3), &mut rng()).unwrap();
+        assert_eq!(
+            answer(&q, vec!["Rex", "Paris"]).unwrap(),
+            FieldElement::new(3)
+        );
+        // a recovered secret the validator rejects
+        let mut q = Questionnair::new(
+            FieldElement::zero(),
+            vec!["pet", "city"],
+            vec!["Rex", "Paris"],
+            &mut rng(),
+        )
+        .unwrap();
+        q.set_validator(nonzero);
+        assert_eq!(
+            answer(&q, vec!["Rex", "Paris"]),
+            Err(Error::InvalidSecretForDomain)
+        );
+    }
+
+    #[test]
+    fn answer_requires_bound_proof_of_work() {
+        let mut q = questionnair(42);
+        q.require_pow(12).unwrap();
+        let answers = vec!["Rex", "Paris", "Hillside"];
+        let nonce = q.solve_pow(&answers);
+        assert_eq!(
+            q.answer_with_pow(answers.clone(), nonce).unwrap(),
+            FieldElement::new(42)
+        );
+        let wrong = (0..)
+            .find(|n| leading_zero_bits(&q.pow_hash(*n, &answers)) < 12)
+            .unwrap();
+        assert_eq!(
+            q.answer_with_pow(answers.clone(), wrong),
+            Err(Error::InvalidProofOfWork)
+        );
+        // the ungated paths are closed
+        assert_eq!(answer(&q, answers.clone()), Err(Error::InvalidProofOfWork));
+        assert_eq!(
+            q.grade(&[(0, "Rex")]).unwrap_err(),
+            Error::InvalidProofOfWork
+        );
+        // the nonce is bound to the questionnair
+        let mut other = Questionnair::new(
+            FieldElement::new(42),
+            vec!["pet", "city", "school"],
+            answers.clone(),
+            &mut ChaCha20Rng::from_seed([4; 32]),
+        )
+        .unwrap();
+        other.require_pow(12).unwrap();
+        assert!(leading_zero_bits(&other.pow_hash(nonce, &answers)) < 12);
+    }
+
+    #[test]
+    fn pow_difficulty_is_capped() {
+        let mut q = questionnair(42);
+        assert_eq!(
+            q.require_pow(MAX_POW_DIFFICULTY + 1),
+            Err(Error::InvalidProofOfWork)
+        );
+        assert_eq!(q.pow_difficulty, 0);
+        assert_eq!(q.require_pow(MAX_POW_DIFFICULTY), Ok(()));
+        assert_eq!(q.validate(), Ok(()));
+        // as if read from untrusted input
+        q.pow_difficulty = u32::MAX;
+        assert_eq!(q.validate(), Err(Error::InvalidProofOfWork));
+    }
+
+    #[test]
+    fn reconstruct_from_points_matches_answer() {
+        let q = questionnair(42);
+        let answers = ["Rex", "Paris", "Hillside"];
+        let keys: Vec<FieldElement> = answers
+            .iter()
+            .enumerate()
+            .map(|(i, ans)| {
+                let canonical = canonical_answer(q.normalizers[i], ans);
+                FieldElement::hash_bytes(&q.kdf.stretch(&q.salts[i], &canonical).unwrap())
+            })
+            .collect();
+        assert_eq!(
+            reconstruct_from_points(&q.points, &keys, &q.xs).unwrap(),
+            answer(&q, answers.to_vec()).unwrap()
+        );
+        assert_eq!(
+            reconstruct_from_points(&q.points, &keys[1..], &q.xs),
+            Err(Error::LengthMismatch)
+        );
+    }
+
+    #[test]
+    fn add_question_keeps_the_secret() {
+        let mut rng = rng();
+        let secret = FieldElement::new(42);
+        let mut dealer = Dealer::new(3, secret, &mut rng).unwrap();
+        let mut q = Questionnair::from_dealer(
+            &dealer,
+            vec!["pet", "city", "school"],
+            vec!["Rex", "Paris", "Hillside"],
+            vec![AnswerNormalizer::default(); 3],
+            &mut rng,
+        )
+        .unwrap();
+        q.add_question(
+            &mut dealer,
+            "car",
+            "Mini",
+            AnswerNormalizer::default(),
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(q.threshold(), 4);
+        assert_eq!(
+            answer(&q, vec!["Rex", "Paris", "Hillside", "Mini"]).unwrap(),
+            secret
+        );
+        assert_eq!(
+            answer(&q, vec!["Rex", "Paris", "Hillside"]),
+            Err(Error::InsufficientShares { needed: 4, got: 3 })
+        );
+    }
+
+    #[test]
+    fn add_question_draws_a_fresh_polynomial() {
+        let mut rng = rng();
+        let secret = FieldElement::new(42);
+        let mut dealer = Dealer::new(2, secret, &mut rng).unwrap();
+        let mut q = Questionnair::from_dealer(
+            &dealer,
+            vec!["pet", "city", "school"],
+            vec!["Rex", "Paris", "Hillside"],
+            vec![AnswerNormalizer::default(); 3],
+            &mut rng,
+        )
+        .unwrap();
+        let old = q.points.clone();
+        let mut wrong = Dealer::new(3, secret, &mut rng).unwrap();
+        assert_eq!(
+            q.add_question(
+                &mut wrong,
+                "car",
+                "Mini",
+                AnswerNormalizer::default(),
+                &mut rng
+            ),
+            Err(Error::InvalidThreshold)
+        );
+        q.add_question(
+            &mut dealer,
+            "car",
+            "Mini",
+            AnswerNormalizer::default(),
+            &mut rng,
+        )
+        .unwrap();
+        // a new leading coefficient r alone would make every difference r x^2
+        let leading: Vec<FieldElement> = (0..3)
+            .map(|i| {
+                let x = q.xs[i];
+                (old[i] - q.points[i]) * (x * x).invert().unwrap()
+            })
+            .collect();
+        assert!(leading[0] != leading[1] || leading[1] != leading[2]);
+        assert_eq!(q.xs[3], FieldElement::new(4));
+        assert_eq!(
+            answer(&q, vec!["Rex", "Paris", "Hillside", "Mini"]).unwrap(),
+            secret
+        );
+    }
+
+    #[test]
+    fn answer_to_key_is_stable_and_fails_on_wrong_answers() {
+        let q = questionnair(42);
+        let key = q.answer_to_key(vec!["Rex", "Paris", "Hillside"]).unwrap();
+        assert_eq!(
+            q.answer_to_key(vec!["rex", "paris", "hillside"]).unwrap(),
+            key
+        );
+        assert_eq!(
+            q.answer_to_key(vec!["Rex", "Rome", "Hillside"]),
+            Err(Error::WrongAnswer { index: 1 })
+        );
+    }
+
+    #[test]
+    fn composed_and_decomposed_answers_match() {
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+        assert_ne!(composed.as_bytes(), decomposed.as_bytes());
+        let tag =
+            |ans| tag_from_answer(canonical_answer(AnswerNormalizer::default(), ans).as_bytes());
+        assert_eq!(tag(composed), tag(decomposed));
+
+        let q = Questionnair::new(
+            FieldElement::new(42),
+            vec!["drink", "city"],
+            vec![composed, "Paris"],
+            &mut rng(),
+        )
+        .unwrap();
+        let key = q.answer_to_key(vec![composed, "Paris"]).unwrap();
+        assert_eq!(q.answer_to_key(vec![decomposed, "Paris"]).unwrap(), key);
+        assert_eq!(
+            answer(&q, vec![decomposed, "Paris"]).unwrap(),
+            FieldElement::new(42)
+        );
+    }
+
+    #[test]
+    fn seeded_rng_reproduces_points_and_salts() {
+        let (a, b) = (questionnair(42), questionnair(42));
+        assert_eq!(
+            (&a.points, &a.salts, &a.tags),
+            (&b.points, &b.salts, &b.tags)
+        );
+        let other = Questionnair::new(
+            FieldElement::new(42),
+            vec!["pet", "city", "school"],
+            vec!["Rex", "Paris", "Hillside"],
+            &mut ChaCha20Rng::from_seed([4; 32]),
+        )
+        .unwrap();
+        assert_ne!(other.points, a.points);
+        assert_ne!(other.salts, a.salts);
+    }
+
+    #[test]
+    fn add_question_rejects_questionnairs_with_required_questions() {
+        let mut rng = rng();
+        let secret = FieldElement::new(42);
+        let mut q = QuestionnairBuilder::new()
+            .question("pet", "Rex")
+            .required()
+            .question("city", "Paris")
+            .question("school", "Hillside")
+            .threshold(2)
+            .build(secret, &mut rng)
+            .unwrap();
+        let mut dealer = Dealer::new(2, secret, &mut rng).unwrap();
+        assert_eq!(
+            q.add_question(
+                &mut dealer,
+                "car",
+                "Mini",
+                AnswerNormalizer::default(),
+                &mut rng
+            ),
+            Err(Error::HasRequiredQuestions)
+        );
+        assert_eq!(q.questions().len(), 3);
+        assert_eq!(
+            q.answer_subset(&[(0, "Rex"), (2, "Hillside")]).unwrap(),
+            secret
+        );
+        assert_eq!(
+            q.answer_subset(&[(1, "Paris"), (2, "Hillside")]),
+            Err(Error::RequiredUnanswered { index: 0 })
+        );
+    }
+
+    #[test]
+    fn locale_normalizer_applies_to_answers_in_its_locale() {
+        let q = QuestionnairBuilder::new()
+            .question("city", "istanbul")
+            .translation("tr", "şehir")
+            .question("river", "Irmak")
+            .translation("tr", "nehir")
+            .locale_normalizer("tr", AnswerNormalizer::TURKIC_CASE)
+            .build(FieldElement::new(42), &mut rng())
+            .unwrap();
+        assert_eq!(q.localized("tr")[0], "şehir");
+        // the default lowercasing of İ leaves a combining dot
+        let answers = [(0, "İSTANBUL"), (1, "irmak")];
+        assert_eq!(q.answer_report(&answers).unwrap().failed, vec![0]);
+        let report = q.answer_report_localized("tr", &answers).unwrap();
+        assert_eq!(report.secret, Some(FieldElement::new(42)));
+        assert_eq!(q.grade_localized("tr", &answers).unwrap().needed, 0);
+        // locales without a normalizer use the question's only
+        assert_eq!(
+            q.answer_report_localized("de", &answers).unwrap().failed,
+            vec![0]
+        );
+    }
+
+    #[test]
+    fn validate_caps_argon2_costs() {
+        let too_much = AnswerKdf::argon2id(AnswerKdf::MAX_MEMORY_KIB + 1, 1, 1, &mut rng());
+        assert_eq!(too_much, Err(Error::InvalidKdfParams));
+        let mut q = QuestionnairBuilder::new()
+            .question("city", "paris")
+            .question("river", "seine")
+            .build(FieldElement::new(42), &mut rng())
+            .unwrap();
+        assert_eq!(q.validate(), Ok(()));
+        // as if read from untrusted input
+        q.kdf = AnswerKdf::Argon2id {
+            memory_kib: 64,
+            iterations: u32::MAX,
+            parallelism: 1,
+            salt: [0; 16],
+        };
+        assert_eq!(q.validate(), Err(Error::InvalidKdfParams));
+        assert_eq!(
+            q.answer_report(&[(0, "paris"), (1, "seine")]).unwrap_err(),
+            Error::InvalidKdfParams
+        );
+    }
+}
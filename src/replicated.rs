@@ -0,0 +1,90 @@
+use crate::additive::AdditiveSharing;
+use crate::error::Error;
+use crate::field::FieldElement;
+use crate::polynomial::next_subset;
+use alloc::{vec, vec::Vec};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The additive sub-shares held by a party, each indexed by the unqualified set of t-1 parties
+/// it is hidden from
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReplicatedShare {
+    pub party: usize,
+    pub subshares: Vec<(Vec<usize>, FieldElement)>,
+}
+
+/// Replicated (CNF) t-of-n sharing: s is split additively into one sub-share per set of t-1
+/// parties, and each party gets every sub-share of the sets it is not in
+/// A party holds C(n-1, t-1) sub-shares, so this is only practical for small committees
+pub struct ReplicatedSharing;
+
+impl ReplicatedSharing {
+    /// Split s among parties 0..n, any t of which reconstruct it
+    pub fn share<R: RngCore + CryptoRng>(
+        s: FieldElement,
+        t: usize,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<Vec<ReplicatedShare>, Error> {
+        if t == 0 || t > n {
+            return Err(Error::InvalidThreshold);
+        }
+        let sets = unqualified_sets(t, n);
+        let values = AdditiveSharing::share(s, sets.len(), rng)?;
+        Ok((0..n)
+            .map(|party| ReplicatedShare {
+                party,
+                subshares: sets
+                    .iter()
+                    .zip(&values)
+                    .filter(|(set, _)| !set.contains(&party))
+                    .map(|(set, v)| (set.clone(), *v))
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Reconstruct s from the shares of at least t parties
+    /// Fails if two parties hold different copies of a sub-share
+    pub fn combine(shares: &[ReplicatedShare], t: usize, n: usize) -> Result<FieldElement, Error> {
+        if t == 0 || t > n {
+            return Err(Error::InvalidThreshold);
+        }
+        let sets = unqualified_sets(t, n);
+        let mut values = Vec::with_capacity(sets.len());
+        for set in &sets {
+            let mut value = None;
+            for (index, share) in shares.iter().enumerate() {
+                if let Some((_, v)) = share.subshares.iter().find(|(s, _)| s == set) {
+                    if value.is_some_and(|value| value != *v) {
+                        return Err(Error::CorruptShare { index });
+                    }
+                    value = Some(*v);
+                }
+            }
+            values.push(value.ok_or_else(|| {
+                let mut parties: Vec<usize> = shares.iter().map(|s| s.party).collect();
+                parties.sort_unstable();
+                parties.dedup();
+                Error::InsufficientShares {
+                    needed: t,
+                    got: parties.len(),
+                }
+            })?);
+        }
+        Ok(AdditiveSharing::combine(&values))
+    }
+}
+
+/// Every set of t-1 of the n parties, in lexicographic order
+fn unqualified_sets(t: usize, n: usize) -> Vec<Vec<usize>> {
+    let mut set: Vec<usize> = (0..t - 1).collect();
+    let mut sets = vec![set.clone()];
+    while next_subset(&mut set, n) {
+        sets.push(set.clone());
+    }
+    sets
+}
@@ -0,0 +1,398 @@
+//! An embeddable axum service running the questionnair flow for a recovery backend.
+//!
+//! Questionnairs are kept as JSON blobs in a `QuestionnairStore`, together with the
+//! `AttemptGuard` limiting the answers to them, so the backend decides where they persist.
+//! The routes take and return JSON:
+//!
+//! - `POST /questionnairs` creates a questionnair from a `CreateRequest` of at most
+//!   `MAX_QUESTIONS` questions, stretching the answers with `AnswerKdf::recommended`, and
+//!   returns its id
+//! - `GET /questionnairs/:id?locale=..` returns the questions, hints and threshold
+//! - `POST /questionnairs/:id/grade` grades an `AnswersRequest` without recovering the secret
+//! - `POST /questionnairs/:id/answer` recovers the secret from an `AnswersRequest`
+//!
+//! Grading and answering count against the attempt limits alike. They lock only the
+//! questionnair answered, and stretch the answers on tokio's blocking threads. Errors are
+//! returned as `{"error": message}` with a matching status.
+
+use crate::attempts::{AttemptGuard, BackoffPolicy};
+use crate::error::Error;
+use crate::field::{FieldElement, FIELD_ELEMENT_BYTES};
+use crate::polynomial::Dealer;
+use crate::questionnair::{AnswerKdf, AnswerNormalizer, GradeReport, Questionnair};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use core::convert::TryFrom;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+use zeroize::Zeroizing;
+
+/// The most questions a created questionnair may have, each costs an Argon2 evaluation
+pub const MAX_QUESTIONS: usize = 32;
+
+/// Where the service keeps the questionnair blobs, by id
+pub trait QuestionnairStore: Send + Sync + 'static {
+    /// The blob stored under the id, if any
+    fn load(&self, id: &str) -> Option<String>;
+    /// Store the blob under the id, replacing an earlier one
+    fn save(&self, id: &str, blob: String);
+}
+
+/// A store in memory, lost with the process
+#[derive(Debug, Default)]
+pub struct MemoryStore(Mutex<HashMap<String, String>>);
+
+impl QuestionnairStore for MemoryStore {
+    fn load(&self, id: &str) -> Option<String> {
+        self.0.lock().expect("not poisoned").get(id).cloned()
+    }
+
+    fn save(&self, id: &str, blob: String) {
+        self.0
+            .lock()
+            .expect("not poisoned")
+            .insert(id.to_string(), blob);
+    }
+}
+
+/// A questionnair to create, secret as its 16 bytes
+#[derive(Debug, Deserialize)]
+pub struct CreateRequest {
+    pub secret: Vec<u8>,
+    pub questions: Vec<String>,
+    pub answers: Vec<String>,
+    /// Number of correct answers needed, all questions if none
+    pub threshold: Option<u64>,
+}
+
+/// The id of a created questionnair
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateResponse {
+    pub id: String,
+}
+
+/// What a recovery UI shows for a questionnair
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuestionsResponse {
+    pub questions: Vec<String>,
+    pub hints: Vec<Option<String>>,
+    pub required: Vec<bool>,
+    pub threshold: usize,
+}
+
+/// Answers given as (question index, answer)
+#[derive(Debug, Deserialize)]
+pub struct AnswersRequest {
+    pub answers: Vec<(usize, String)>,
+}
+
+/// The recovered secret as its 16 bytes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnswerResponse {
+    pub secret: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LocaleQuery {
+    locale: Option<String>,
+}
+
+/// A stored questionnair with the failures of its questions
+#[derive(Serialize, Deserialize)]
+struct Record {
+    questionnair: Questionnair,
+    guard: AttemptGuard,
+}
+
+/// The state of the service: the store, and a lock per questionnair keeping answers to it from
+/// racing past its attempt limits
+struct Service<S> {
+    store: S,
+    policy: BackoffPolicy,
+    locks: Arc<Mutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
+}
+
+/// Held while a questionnair is loaded, answered and saved; the last holder of a lock removes
+/// it, so ids never answered again do not keep their lock
+struct RecordLock {
+    locks: Arc<Mutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
+    id: String,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl Drop for RecordLock {
+    fn drop(&mut self) {
+        let mut locks = self.locks.lock().expect("not poisoned");
+        self.guard = None;
+        if locks
+            .get(&self.id)
+            .is_some_and(|lock| Arc::strong_count(lock) == 1)
+        {
+            locks.remove(&self.id);
+        }
+    }
+}
+
+/// The routes of the service over the store, limiting answers with the policy
+pub fn router<S: QuestionnairStore>(store: S, policy: BackoffPolicy) -> Router {
+    let service = Arc::new(Service {
+        store,
+        policy,
+        locks: Arc::default(),
+    });
+    Router::new()
+        .route("/questionnairs", post(create::<S>))
+        .route("/questionnairs/:id", get(questions::<S>))
+        .route("/questionnairs/:id/grade", post(grade::<S>))
+        .route("/questionnairs/:id/answer", post(answer::<S>))
+        .with_state(service)
+}
+
+/// An error response with a status matching the error
+pub struct ServiceError(StatusCode, String);
+
+impl From<Error> for ServiceError {
+    fn from(e: Error) -> Self {
+        let status = match e {
+            Error::RateLimited { .. } | Error::LockedOut { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Error::WrongAnswer { .. }
+            | Error::InsufficientShares { .. }
+            | Error::RequiredUnanswered { .. } => StatusCode::FORBIDDEN,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        ServiceError(status, e.to_string())
+    }
+}
+
+impl IntoResponse for ServiceError {
+    fn into_response(self) -> Response {
+        let body = Json(HashMap::from([("error", self.1)]));
+        (self.0, body).into_response()
+    }
+}
+
+fn not_found() -> ServiceError {
+    ServiceError(StatusCode::NOT_FOUND, "Unknown questionnair".to_string())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+impl<S: QuestionnairStore> Service<S> {
+    /// Wait for the lock of the questionnair
+    async fn lock(&self, id: &str) -> RecordLock {
+        let lock = self
+            .locks
+            .lock()
+            .expect("not poisoned")
+            .entry(id.to_string())
+            .or_default()
+            .clone();
+        RecordLock {
+            locks: self.locks.clone(),
+            id: id.to_string(),
+            guard: Some(lock.lock_owned().await),
+        }
+    }
+
+    fn load(&self, id: &str) -> Result<Record, ServiceError> {
+        let blob = self.store.load(id).ok_or_else(not_found)?;
+        let record: Record = serde_json::from_str(&blob).map_err(|_| Error::InvalidEncoding)?;
+        record.questionnair.validate()?;
+        Ok(record)
+    }
+
+    fn save(&self, id: &str, record: &Record) -> Result<(), ServiceError> {
+        let blob = serde_json::to_string(record).map_err(|_| Error::InvalidEncoding)?;
+        self.store.save(id, blob);
+        Ok(())
+    }
+}
+
+async fn create<S: QuestionnairStore>(
+    State(service): State<Arc<Service<S>>>,
+    Json(request): Json<CreateRequest>,
+) -> Result<Json<CreateResponse>, ServiceError> {
+    let secret = Zeroizing::new(request.secret);
+    let secret =
+        <[u8; FIELD_ELEMENT_BYTES]>::try_from(&secret[..]).map_err(|_| Error::InvalidEncoding)?;
+    let secret = FieldElement::from_bytes(secret)?;
+    if request.questions.len() > MAX_QUESTIONS {
+        return Err(ServiceError(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("At most {} questions", MAX_QUESTIONS),
+        ));
+    }
+    let answers = Zeroizing::new(request.answers);
+    let questions = request.questions;
+    let threshold = request.threshold.unwrap_or(questions.len() as u64);
+    let questionnair = blocking(move || {
+        let dealer = Dealer::new(threshold, secret, &mut OsRng)?;
+        Questionnair::with_kdf(
+            &dealer,
+            questions.iter().map(String::as_str).collect(),
+            answers.iter().map(String::as_str).collect(),
+            vec![AnswerNormalizer::default(); questions.len()],
+            AnswerKdf::recommended(&mut OsRng),
+            &mut OsRng,
+        )
+    })
+    .await??;
+    let guard = AttemptGuard::new(&questionnair, service.policy);
+    let mut id = [0u8; 16];
+    OsRng.fill_bytes(&mut id);
+    let id: String = id.iter().map(|b| format!("{:02x}", b)).collect();
+    service.save(
+        &id,
+        &Record {
+            questionnair,
+            guard,
+        },
+    )?;
+    Ok(Json(CreateResponse { id }))
+}
+
+async fn questions<S: QuestionnairStore>(
+    State(service): State<Arc<Service<S>>>,
+    Path(id): Path<String>,
+    Query(query): Query<LocaleQuery>,
+) -> Result<Json<QuestionsResponse>, ServiceError> {
+    let record = service.load(&id)?;
+    let questionnair = &record.questionnair;
+    let questions = match &query.locale {
+        Some(locale) => questionnair.localized(locale),
+        None => questionnair.questions(),
+    };
+    Ok(Json(QuestionsResponse {
+        questions: questions.to_vec(),
+        hints: questionnair.hints().to_vec(),
+        required: questionnair.required().to_vec(),
+        threshold: questionnair.threshold(),
+    }))
+}
+
+/// Run the answer stretching on a blocking thread, so slow KDFs do not stall the async workers
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, ServiceError> {
+    tokio::task::spawn_blocking(f).await.map_err(|_| {
+        ServiceError(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Answering failed".to_string(),
+        )
+    })
+}
+
+async fn grade<S: QuestionnairStore>(
+    State(service): State<Arc<Service<S>>>,
+    Path(id): Path<String>,
+    Json(request): Json<AnswersRequest>,
+) -> Result<Json<GradeReport>, ServiceError> {
+    let answers = Zeroizing::new(request.answers);
+    let _lock = service.lock(&id).await;
+    let mut record = service.load(&id)?;
+    let (record, grade) = blocking(move || {
+        let answers: Vec<(usize, &str)> = answers.iter().map(|(i, a)| (*i, a.as_str())).collect();
+        let grade = record.guard.grade(&record.questionnair, &answers, now());
+        (record, grade)
+    })
+    .await?;
+    let grade = grade?;
+    service.save(&id, &record)?;
+    Ok(Json(grade))
+}
+
+async fn answer<S: QuestionnairStore>(
+    State(service): State<Arc<Service<S>>>,
+    Path(id): Path<String>,
+    Json(request): Json<AnswersRequest>,
+) -> Result<Json<AnswerResponse>, ServiceError> {
+    let answers = Zeroizing::new(request.answers);
+    let _lock = service.lock(&id).await;
+    let mut record = service.load(&id)?;
+    let (record, report) = blocking(move || {
+        let answers: Vec<(usize, &str)> = answers.iter().map(|(i, a)| (*i, a.as_str())).collect();
+        let report = record
+            .guard
+            .answer_report(&record.questionnair, &answers, now());
+        (record, report)
+    })
+    .await?;
+    let report = report?;
+    service.save(&id, &record)?;
+    match report.secret {
+        Some(secret) => Ok(Json(AnswerResponse {
+            secret: secret.to_bytes().to_vec(),
+        })),
+        None => Err(Error::InsufficientShares {
+            needed: record.questionnair.threshold(),
+            got: report.passed.len(),
+        }
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locks_are_per_questionnair_and_released() {
+        let service = Service {
+            store: MemoryStore::default(),
+            policy: BackoffPolicy::default(),
+            locks: Arc::default(),
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let a = service.lock("a").await;
+            // another questionnair is not blocked by the lock of the first
+            let b = service.lock("b").await;
+            assert_eq!(service.locks.lock().unwrap().len(), 2);
+            drop(b);
+            assert!(!service.locks.lock().unwrap().contains_key("b"));
+            drop(a);
+            assert!(service.locks.lock().unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn create_stretches_answers_and_bounds_questions() {
+        let service = Arc::new(Service {
+            store: MemoryStore::default(),
+            policy: BackoffPolicy::default(),
+            locks: Arc::default(),
+        });
+        let request = |n: usize| CreateRequest {
+            secret: vec![7; FIELD_ELEMENT_BYTES],
+            questions: (0..n).map(|i| format!("question {}", i)).collect(),
+            answers: (0..n).map(|i| format!("answer {}", i)).collect(),
+            threshold: Some(2),
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let too_many = create(State(service.clone()), Json(request(MAX_QUESTIONS + 1))).await;
+            assert_eq!(too_many.err().unwrap().0, StatusCode::PAYLOAD_TOO_LARGE);
+            let Json(created) = create(State(service.clone()), Json(request(2)))
+                .await
+                .ok()
+                .unwrap();
+            let blob = service.store.load(&created.id).unwrap();
+            assert!(blob.contains("Argon2id"));
+        });
+    }
+}
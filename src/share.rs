@@ -0,0 +1,334 @@
+use crate::error::Error;
+use crate::field::{FieldElement, FIELD_ELEMENT_BYTES};
+use alloc::{string::String, string::ToString, vec::Vec};
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::{Add, Mul, Sub};
+use ff::PrimeField;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Length of the encoding of a share, x || y
+pub const SHARE_BYTES: usize = 2 * FIELD_ELEMENT_BYTES;
+
+/// Represents a point on the polynomial
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Share<F: PrimeField + Zeroize = FieldElement> {
+    /// x-coordinate, never 0 as f(0) is the secret
+    /// Any distinct values work, reconstruction only uses the stored x so the order is free
+    pub x: F,
+    /// f(x)
+    pub y: F,
+}
+
+impl<F: PrimeField + Zeroize> Zeroize for Share<F> {
+    fn zeroize(&mut self) {
+        self.x.zeroize();
+        self.y.zeroize();
+    }
+}
+
+impl<F: PrimeField + Zeroize> Drop for Share<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<F: PrimeField + Zeroize> ZeroizeOnDrop for Share<F> {}
+
+/// Prints only x, y is the secret part
+impl<F: PrimeField + Zeroize> fmt::Debug for Share<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Share")
+            .field("x", &self.x)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: PrimeField + Zeroize> Share<F> {
+    /// Mask y with a pad for transport, the recipient removes it with `unmask`
+    /// This does not change the polynomial: a masked share is not a valid share
+    pub fn mask(&self, pad: F) -> Self {
+        Share {
+            x: self.x,
+            y: self.y + pad,
+        }
+    }
+
+    /// Remove a pad added by `mask`
+    pub fn unmask(&self, pad: F) -> Self {
+        Share {
+            x: self.x,
+            y: self.y - pad,
+        }
+    }
+
+    /// The share of s + c, when every holder adds the public constant c to its share
+    pub fn add_constant(&self, c: F) -> Self {
+        Share {
+            x: self.x,
+            y: self.y + c,
+        }
+    }
+}
+
+/// Shares of s1 and s2 at the same x add to the share of s1 + s2
+impl<'a, F: PrimeField + Zeroize> Add<&'a Share<F>> for &'a Share<F> {
+    type Output = Result<Share<F>, Error>;
+
+    fn add(self, other: &'a Share<F>) -> Self::Output {
+        if self.x != other.x {
+            return Err(Error::XMismatch);
+        }
+        Ok(Share {
+            x: self.x,
+            y: self.y + other.y,
+        })
+    }
+}
+
+impl<F: PrimeField + Zeroize> Add for Share<F> {
+    type Output = Result<Share<F>, Error>;
+
+    fn add(self, other: Share<F>) -> Self::Output {
+        &self + &other
+    }
+}
+
+/// Shares of s1 and s2 at the same x subtract to the share of s1 - s2
+impl<'a, F: PrimeField + Zeroize> Sub<&'a Share<F>> for &'a Share<F> {
+    type Output = Result<Share<F>, Error>;
+
+    fn sub(self, other: &'a Share<F>) -> Self::Output {
+        if self.x != other.x {
+            return Err(Error::XMismatch);
+        }
+        Ok(Share {
+            x: self.x,
+            y: self.y - other.y,
+        })
+    }
+}
+
+impl<F: PrimeField + Zeroize> Sub for Share<F> {
+    type Output = Result<Share<F>, Error>;
+
+    fn sub(self, other: Share<F>) -> Self::Output {
+        &self - &other
+    }
+}
+
+/// The share of s times the public scalar c
+impl<F: PrimeField + Zeroize> Mul<F> for &Share<F> {
+    type Output = Share<F>;
+
+    fn mul(self, c: F) -> Share<F> {
+        Share {
+            x: self.x,
+            y: self.y * c,
+        }
+    }
+}
+
+impl<F: PrimeField + Zeroize> Mul<F> for Share<F> {
+    type Output = Share<F>;
+
+    fn mul(self, c: F) -> Share<F> {
+        &self * c
+    }
+}
+
+impl Share {
+    /// The encoding x || y of canonical field elements
+    pub fn to_bytes(&self) -> [u8; SHARE_BYTES] {
+        let mut bytes = [0u8; SHARE_BYTES];
+        bytes[..FIELD_ELEMENT_BYTES].copy_from_slice(&self.x.to_bytes());
+        bytes[FIELD_ELEMENT_BYTES..].copy_from_slice(&self.y.to_bytes());
+        bytes
+    }
+
+    /// Read the encoding x || y
+    pub fn from_bytes(bytes: [u8; SHARE_BYTES]) -> Result<Self, Error> {
+        let mut x = [0u8; FIELD_ELEMENT_BYTES];
+        let mut y = [0u8; FIELD_ELEMENT_BYTES];
+        x.copy_from_slice(&bytes[..FIELD_ELEMENT_BYTES]);
+        y.copy_from_slice(&bytes[FIELD_ELEMENT_BYTES..]);
+        Ok(Share {
+            x: FieldElement::from_bytes(x)?,
+            y: FieldElement::from_bytes(y)?,
+        })
+    }
+
+    /// Read the encoding x || y from a slice of any length, for untrusted input
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes = <[u8; SHARE_BYTES]>::try_from(bytes).map_err(|_| Error::InvalidEncoding)?;
+        Share::from_bytes(bytes)
+    }
+
+    /// A word from the BIP39 English wordlist derived from H(x || y), so a share transcribed by
+    /// hand can be checked for typos
+    pub fn checksum_word(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.to_bytes());
+        let hash = hasher.finalize();
+        // the wordlist has 2^11 words
+        let index = (u16::from_be_bytes([hash[0], hash[1]]) >> 5) as usize;
+        WORDLIST
+            .lines()
+            .nth(index)
+            .expect("wordlist has 2048 words")
+            .to_string()
+    }
+
+    /// Check a transcribed share against its checksum word
+    pub fn verify_checksum_word(&self, word: &str) -> bool {
+        self.checksum_word() == word
+    }
+
+    /// The 24-word BIP39 English phrase of x || y, whose last word includes an 8-bit checksum
+    pub fn to_mnemonic(&self) -> String {
+        let bytes = Zeroizing::new(self.to_bytes());
+        let mut bits = Zeroizing::new(bytes.to_vec());
+        bits.push(Sha256::digest(&bytes[..])[0]);
+        let words: Vec<&str> = (0..MNEMONIC_WORDS)
+            .map(|i| {
+                let index = (0..11).fold(0, |acc, j| {
+                    let bit = i * 11 + j;
+                    (acc << 1) | ((bits[bit / 8] >> (7 - bit % 8)) & 1) as usize
+                });
+                WORDLIST
+                    .lines()
+                    .nth(index)
+                    .expect("wordlist has 2048 words")
+            })
+            .collect();
+        words.join(" ")
+    }
+
+    /// Read a phrase written by `to_mnemonic`, rejecting unknown words and wrong checksums
+    pub fn from_mnemonic(phrase: &str) -> Result<Self, Error> {
+        let words: Vec<String> = phrase.split_whitespace().map(str::to_lowercase).collect();
+        if words.len() != MNEMONIC_WORDS {
+            return Err(Error::InvalidEncoding);
+        }
+        let mut bits = Zeroizing::new([0u8; SHARE_BYTES + 1]);
+        for (i, word) in words.iter().enumerate() {
+            let index = WORDLIST
+                .lines()
+                .position(|w| w == word)
+                .ok_or(Error::InvalidEncoding)?;
+            for j in 0..11 {
+                let bit = i * 11 + j;
+                bits[bit / 8] |= (((index >> (10 - j)) & 1) as u8) << (7 - bit % 8);
+            }
+        }
+        let mut bytes = [0u8; SHARE_BYTES];
+        bytes.copy_from_slice(&bits[..SHARE_BYTES]);
+        if Sha256::digest(&bytes)[0] != bits[SHARE_BYTES] {
+            return Err(Error::InvalidEncoding);
+        }
+        let share = Share::from_bytes(bytes);
+        bytes.zeroize();
+        share
+    }
+}
+
+/// Words of a mnemonic, 256 bits of share and 8 of checksum at 11 bits per word
+const MNEMONIC_WORDS: usize = 24;
+
+/// The BIP39 English wordlist, one word per line
+const WORDLIST: &str = include_str!("bip39_english.txt");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn share() -> Share {
+        Share {
+            x: FieldElement::new(3),
+            y: FieldElement::new(1234),
+        }
+    }
+
+    #[test]
+    fn debug_redacts_y() {
+        let printed = format!("{:?}", share());
+        assert!(printed.contains(&format!("{:?}", share().x)));
+        assert!(!printed.contains(&format!("{:?}", share().y)));
+    }
+
+    #[test]
+    fn unmask_restores_masked_share() {
+        let pad = FieldElement::hash("pad");
+        let masked = share().mask(pad);
+        assert_eq!(masked.x, share().x);
+        assert_ne!(masked.y, share().y);
+        let unmasked = masked.unmask(pad);
+        assert_eq!((unmasked.x, unmasked.y), (share().x, share().y));
+    }
+
+    #[test]
+    fn checksum_word_is_stable_and_detects_changed_bytes() {
+        let word = share().checksum_word();
+        assert_eq!(word, "royal");
+        assert!(share().verify_checksum_word(&word));
+        let bytes = share().to_bytes();
+        for i in 0..SHARE_BYTES {
+            let mut changed = bytes;
+            changed[i] ^= 1;
+            let changed = Share::from_bytes(changed).unwrap();
+            assert!(!changed.verify_checksum_word(&word), "byte {}", i);
+        }
+    }
+
+    /// Shares of f(x) = s + a x + b x^2 at x = 1, 2, 3 for fixed s, a and b, computed
+    /// independently of this crate
+    const KNOWN_SHARES: [[u8; SHARE_BYTES]; 3] = [
+        [
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x76, 0x7b, 0x79, 0xcc, 0x0c, 0x11, 0xe0, 0x5f, 0xf2, 0xcc, 0xdd, 0x87,
+            0x79, 0x26, 0xe2, 0xf0,
+        ],
+        [
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x75, 0x48, 0xfa, 0xdf, 0x9c, 0x47, 0x3f, 0xd8, 0x70, 0x41, 0x0d, 0x1c,
+            0x6b, 0x85, 0xfc, 0x9d,
+        ],
+        [
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x6e, 0x98, 0xd6, 0xb0, 0x48, 0x5e, 0xfa, 0x67, 0x6b, 0x2b, 0x3a, 0x46,
+            0x3c, 0x62, 0x72, 0x08,
+        ],
+    ];
+    const KNOWN_SECRET: [u8; FIELD_ELEMENT_BYTES] = [
+        0x10, 0x32, 0x54, 0x76, 0x98, 0xba, 0xdc, 0xfe, 0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23,
+        0x01,
+    ];
+
+    #[test]
+    fn known_shares_reconstruct_known_secret() {
+        let shares: Vec<Share> = KNOWN_SHARES
+            .iter()
+            .map(|b| Share::from_bytes(*b).unwrap())
+            .collect();
+        for (share, bytes) in shares.iter().zip(&KNOWN_SHARES) {
+            assert_eq!(share.to_bytes(), *bytes);
+        }
+        let secret = crate::polynomial::Polynomial::reconstruct(&shares).unwrap();
+        assert_eq!(secret.to_bytes(), KNOWN_SECRET);
+    }
+
+    #[test]
+    fn constants_match_serialized_lengths() {
+        assert_eq!(FieldElement::new(1).to_bytes().len(), FIELD_ELEMENT_BYTES);
+        assert_eq!(
+            FieldElement::new(1).to_repr_bytes().len(),
+            crate::field::REPR_BYTES
+        );
+        assert_eq!(share().to_bytes().len(), SHARE_BYTES);
+        assert!(Share::from_slice(&[0u8; SHARE_BYTES - 1]).is_err());
+    }
+}
@@ -0,0 +1,850 @@
+//! Shares of one secret together with the metadata of their sharing, and a compact binary
+//! format for them. A share is encoded as
+//!
+//! magic (2) || version (1) || flags (1) || field id (2) || split id (16) || threshold (varint)
+//! || expiry (varint, optional) || x (varint, or 16 bytes) || y (16) || MAC (16, optional)
+//!
+//! with little-endian integers and LEB128 varints. The MAC is a truncated HMAC-SHA256 over
+//! everything before it under a key held by the dealer and the combiner.
+
+use crate::error::Error;
+use crate::field::{FieldElement, FIELD_ELEMENT_BYTES};
+use crate::polynomial::Polynomial;
+use crate::share::Share;
+use alloc::{string::ToString, vec::Vec};
+use core::convert::TryFrom;
+use ff::Field;
+use hmac::{Hmac, Mac, NewMac};
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+/// First bytes of a binary share
+pub const BINARY_MAGIC: [u8; 2] = *b"PS";
+/// First bytes of a binary container of shares
+pub const CONTAINER_MAGIC: [u8; 2] = *b"PC";
+/// Version of the binary share and container formats
+pub const BINARY_VERSION: u8 = 1;
+/// Field id of `FieldElement`, GF(2^128 - 159), in the binary format
+pub const BINARY_FIELD_ID: u16 = 1;
+/// Length of the random identifier shared by all shares of a split
+pub const SPLIT_ID_BYTES: usize = 16;
+/// Length of the truncated MAC of a binary share
+pub const MAC_BYTES: usize = 16;
+/// Length of the MAC key
+pub const MAC_KEY_BYTES: usize = 32;
+
+/// x is encoded as 16 bytes instead of a varint
+const FLAG_WIDE_X: u8 = 1;
+/// A MAC follows y
+const FLAG_MAC: u8 = 2;
+/// An expiry follows the threshold
+const FLAG_EXPIRY: u8 = 4;
+const KNOWN_FLAGS: u8 = FLAG_WIDE_X | FLAG_MAC | FLAG_EXPIRY;
+
+/// The metadata every share of a split carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShareMetadata {
+    /// The field of the shares, `BINARY_FIELD_ID` for `FieldElement`
+    pub field_id: u16,
+    /// Random identifier of the split, so shares of different splits are not combined
+    pub split_id: [u8; SPLIT_ID_BYTES],
+    /// Number of shares needed to reconstruct
+    pub threshold: u64,
+    /// Time in seconds since the Unix epoch after which the shares no longer reconstruct
+    /// Only MACed shares are protected from having it changed or removed
+    pub expiry: Option<u64>,
+}
+
+/// A share with the metadata of its split and an optional MAC over both
+#[derive(Debug, Clone)]
+pub struct FramedShare {
+    pub metadata: ShareMetadata,
+    pub share: Share,
+    pub mac: Option<[u8; MAC_BYTES]>,
+}
+
+impl FramedShare {
+    /// Attach the metadata to the share, with a MAC if a key is given
+    pub fn new(share: Share, metadata: ShareMetadata, key: Option<&[u8; MAC_KEY_BYTES]>) -> Self {
+        let mut framed = FramedShare {
+            metadata,
+            share,
+            mac: None,
+        };
+        if let Some(key) = key {
+            framed.mac = Some(mac(key, &framed.unauthenticated_bytes(true)));
+        }
+        framed
+    }
+
+    /// Whether the share carries a MAC that verifies under the key
+    pub fn verify_mac(&self, key: &[u8; MAC_KEY_BYTES]) -> bool {
+        match &self.mac {
+            Some(tag) => mac(key, &self.unauthenticated_bytes(true))
+                .ct_eq(tag)
+                .into(),
+            None => false,
+        }
+    }
+
+    /// The binary encoding
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut bytes = self.unauthenticated_bytes(self.mac.is_some());
+        if let Some(tag) = &self.mac {
+            bytes.extend_from_slice(tag);
+        }
+        bytes
+    }
+
+    /// Read the binary encoding, which has to be the whole input
+    /// The MAC is read but not verified, see `verify_mac`
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader(bytes);
+        let framed = FramedShare::read(&mut reader)?;
+        if !reader.0.is_empty() {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(framed)
+    }
+
+    /// The encoding up to the MAC, with the MAC flag set as given
+    fn unauthenticated_bytes(&self, with_mac: bool) -> Vec<u8> {
+        let x = self.share.x.to_bytes();
+        let wide = x[8..].iter().any(|b| *b != 0);
+        let mut flags = 0;
+        if wide {
+            flags |= FLAG_WIDE_X;
+        }
+        if with_mac {
+            flags |= FLAG_MAC;
+        }
+        if self.metadata.expiry.is_some() {
+            flags |= FLAG_EXPIRY;
+        }
+        let mut bytes = Vec::with_capacity(64);
+        bytes.extend_from_slice(&BINARY_MAGIC);
+        bytes.push(BINARY_VERSION);
+        bytes.push(flags);
+        bytes.extend_from_slice(&self.metadata.field_id.to_le_bytes());
+        bytes.extend_from_slice(&self.metadata.split_id);
+        write_varint(&mut bytes, self.metadata.threshold);
+        if let Some(expiry) = self.metadata.expiry {
+            write_varint(&mut bytes, expiry);
+        }
+        if wide {
+            bytes.extend_from_slice(&x);
+        } else {
+            let mut low = [0u8; 8];
+            low.copy_from_slice(&x[..8]);
+            write_varint(&mut bytes, u64::from_le_bytes(low));
+        }
+        bytes.extend_from_slice(&self.share.y.to_bytes());
+        bytes
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, Error> {
+        if reader.take(2)? != BINARY_MAGIC {
+            return Err(Error::InvalidEncoding);
+        }
+        let version = reader.byte()?;
+        if version != BINARY_VERSION {
+            return Err(Error::UnsupportedVersion {
+                version: version.into(),
+            });
+        }
+        let flags = reader.byte()?;
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(Error::InvalidEncoding);
+        }
+        let mut field_id = [0u8; 2];
+        field_id.copy_from_slice(reader.take(2)?);
+        let mut split_id = [0u8; SPLIT_ID_BYTES];
+        split_id.copy_from_slice(reader.take(SPLIT_ID_BYTES)?);
+        let threshold = reader.varint()?;
+        let expiry = if flags & FLAG_EXPIRY != 0 {
+            Some(reader.varint()?)
+        } else {
+            None
+        };
+        let x = if flags & FLAG_WIDE_X != 0 {
+            let x = reader.element()?;
+            // the short form is canonical for x below 2^64
+            if x.to_bytes()[8..].iter().all(|b| *b == 0) {
+                return Err(Error::InvalidEncoding);
+            }
+            x
+        } else {
+            FieldElement::new(reader.varint()?)
+        };
+        let y = reader.element()?;
+        let mac = if flags & FLAG_MAC != 0 {
+            let mut tag = [0u8; MAC_BYTES];
+            tag.copy_from_slice(reader.take(MAC_BYTES)?);
+            Some(tag)
+        } else {
+            None
+        };
+        Ok(FramedShare {
+            metadata: ShareMetadata {
+                field_id: u16::from_le_bytes(field_id),
+                split_id,
+                threshold,
+                expiry,
+            },
+            share: Share { x, y },
+            mac,
+        })
+    }
+}
+
+impl Share {
+    /// The binary encoding of the share with the metadata of its split, MACed if a key is given
+    pub fn to_binary(
+        &self,
+        metadata: &ShareMetadata,
+        key: Option<&[u8; MAC_KEY_BYTES]>,
+    ) -> Vec<u8> {
+        FramedShare::new(self.clone(), *metadata, key).to_binary()
+    }
+
+    /// Read a binary share with its metadata, see `FramedShare::from_binary`
+    pub fn from_binary(bytes: &[u8]) -> Result<FramedShare, Error> {
+        FramedShare::from_binary(bytes)
+    }
+}
+
+/// The shares of one secret, e.g. as loaded from separate files
+/// Consistency is only checked when reconstructing, so a loaded set can be inspected first
+#[derive(Debug, Clone, Default)]
+pub struct Shares(Vec<FramedShare>);
+
+impl Shares {
+    /// Split the secret into n shares of a new split with a random id, t of which reconstruct it,
+    /// MACed if a key is given
+    pub fn split<R: RngCore + CryptoRng>(
+        secret: FieldElement,
+        t: u64,
+        n: u64,
+        key: Option<&[u8; MAC_KEY_BYTES]>,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        Shares::deal(secret, t, n, None, key, rng)
+    }
+
+    /// Same as `split`, the shares no longer reconstruct after the expiry, in seconds since the
+    /// Unix epoch. Without a key the expiry is not authenticated
+    pub fn split_with_expiry<R: RngCore + CryptoRng>(
+        secret: FieldElement,
+        t: u64,
+        n: u64,
+        expiry: u64,
+        key: Option<&[u8; MAC_KEY_BYTES]>,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        Shares::deal(secret, t, n, Some(expiry), key, rng)
+    }
+
+    fn deal<R: RngCore + CryptoRng>(
+        secret: FieldElement,
+        t: u64,
+        n: u64,
+        expiry: Option<u64>,
+        key: Option<&[u8; MAC_KEY_BYTES]>,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        if t > n {
+            return Err(Error::InvalidThreshold);
+        }
+        let mut split_id = [0u8; SPLIT_ID_BYTES];
+        rng.fill_bytes(&mut split_id);
+        let metadata = ShareMetadata {
+            field_id: BINARY_FIELD_ID,
+            split_id,
+            threshold: t,
+            expiry,
+        };
+        let shares = Polynomial::new(t, secret, rng)?.share(n);
+        Ok(Shares(
+            shares
+                .into_iter()
+                .map(|share| FramedShare::new(share, metadata, key))
+                .collect(),
+        ))
+    }
+
+    /// The shares in the order they were added
+    pub fn shares(&self) -> &[FramedShare] {
+        &self.0
+    }
+
+    /// Add a share, e.g. read from a file
+    pub fn push(&mut self, share: FramedShare) {
+        self.0.push(share);
+    }
+
+    /// The x-coordinates of the shares, one per participant
+    pub fn participants(&self) -> Vec<FieldElement> {
+        self.0.iter().map(|s| s.share.x).collect()
+    }
+
+    /// Refresh the shares with a random polynomial with g(0) = 0 under a new split id and drop
+    /// the share at x, so the removed participant's old share no longer combines with the others
+    /// Runs where all shares are held, e.g. by the dealer, and MACs the new shares if a key is
+    /// given. Fails with `UnknownX` if no share is at x, and if fewer than threshold shares
+    /// would remain
+    pub fn remove_participant<R: RngCore + CryptoRng>(
+        &self,
+        x: &FieldElement,
+        key: Option<&[u8; MAC_KEY_BYTES]>,
+        rng: &mut R,
+    ) -> Result<Shares, Error> {
+        let mut metadata = self.validate()?;
+        if !self.0.iter().any(|s| s.share.x == *x) {
+            return Err(Error::UnknownX);
+        }
+        let remaining: Vec<Share> = self
+            .0
+            .iter()
+            .filter(|s| s.share.x != *x)
+            .map(|s| s.share.clone())
+            .collect();
+        if (remaining.len() as u64) < metadata.threshold {
+            return Err(Error::InsufficientShares {
+                needed: metadata.threshold as usize,
+                got: remaining.len(),
+            });
+        }
+        rng.fill_bytes(&mut metadata.split_id);
+        let refreshed = Polynomial::refresh(&remaining, metadata.threshold, rng)?;
+        Ok(Shares(
+            refreshed
+                .into_iter()
+                .map(|share| FramedShare::new(share, metadata, key))
+                .collect(),
+        ))
+    }
+
+    /// Whether at least threshold of the shares' x-coordinates are available, e.g. belong to
+    /// participants currently online, so that reconstruction can be attempted
+    pub fn verify_threshold_reachable(&self, available: &[FieldElement]) -> bool {
+        let threshold = match self.0.first() {
+            Some(share) => share.metadata.threshold,
+            None => return false,
+        };
+        let reachable = self
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(i, s)| {
+                available.contains(&s.share.x)
+                    && !self.0[..*i].iter().any(|t| t.share.x == s.share.x)
+            })
+            .count();
+        reachable as u64 >= threshold
+    }
+
+    /// Summarize the set, e.g. for an operator checking loaded shares before reconstructing
+    /// MACs are checked if a key is given
+    pub fn report(&self, key: Option<&[u8; MAC_KEY_BYTES]>) -> ShareHealth {
+        let first = self.0.first().map(|s| s.metadata);
+        let xs = self.participants();
+        let distinct_x = xs
+            .iter()
+            .enumerate()
+            .filter(|(i, x)| !xs[..*i].contains(x))
+            .count();
+        let bad_macs: Vec<usize> = match key {
+            Some(key) => (0..self.0.len())
+                .filter(|i| !self.0[*i].verify_mac(key))
+                .collect(),
+            None => Vec::new(),
+        };
+        let consistent = self.validate().is_ok();
+        let authenticated: Vec<FieldElement> = xs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !bad_macs.contains(i))
+            .map(|(_, x)| *x)
+            .collect();
+        ShareHealth {
+            threshold: first.map_or(0, |m| m.threshold),
+            present: self.0.len(),
+            distinct_x,
+            field_id: first.map(|m| m.field_id),
+            split_id: first.map(|m| m.split_id),
+            expiry: first.and_then(|m| m.expiry),
+            consistent,
+            duplicate_x: distinct_x < xs.len(),
+            macs_valid: key.map(|_| bad_macs.is_empty()),
+            threshold_reachable: consistent && self.verify_threshold_reachable(&authenticated),
+            bad_macs,
+        }
+    }
+
+    /// The container encoding magic (2) || version (1) || count (varint) || shares
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&CONTAINER_MAGIC);
+        bytes.push(BINARY_VERSION);
+        write_varint(&mut bytes, self.0.len() as u64);
+        for share in &self.0 {
+            bytes.extend_from_slice(&share.to_binary());
+        }
+        bytes
+    }
+
+    /// Read the container encoding, which has to be the whole input
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader(bytes);
+        if reader.take(2)? != CONTAINER_MAGIC {
+            return Err(Error::InvalidEncoding);
+        }
+        let version = reader.byte()?;
+        if version != BINARY_VERSION {
+            return Err(Error::UnsupportedVersion {
+                version: version.into(),
+            });
+        }
+        let count = reader.varint()?;
+        // every share takes at least 39 bytes, so a huge count cannot make us allocate
+        let mut shares = Vec::new();
+        for _ in 0..count {
+            shares.push(FramedShare::read(&mut reader)?);
+        }
+        if !reader.0.is_empty() {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(Shares(shares))
+    }
+
+    /// Reconstruct the secret after checking that all shares are over the same field, belong to
+    /// the same unexpired split of a `FieldElement` secret and are at least its threshold
+    /// With a key, every share must carry a MAC verifying under it
+    #[cfg(feature = "std")]
+    pub fn reconstruct(&self, key: Option<&[u8; MAC_KEY_BYTES]>) -> Result<FieldElement, Error> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.reconstruct_at(key, now)
+    }
+
+    /// Same as `reconstruct` at the time `now` in seconds since the Unix epoch, for hosts
+    /// without a system clock
+    pub fn reconstruct_at(
+        &self,
+        key: Option<&[u8; MAC_KEY_BYTES]>,
+        now: u64,
+    ) -> Result<FieldElement, Error> {
+        let metadata = self.validate()?;
+        if let Some(key) = key {
+            if let Some(index) = self.0.iter().position(|s| !s.verify_mac(key)) {
+                return Err(Error::CorruptShare { index });
+            }
+        }
+        if metadata.expiry.is_some_and(|expiry| now > expiry) {
+            return Err(Error::Expired);
+        }
+        if (self.0.len() as u64) < metadata.threshold {
+            return Err(Error::InsufficientShares {
+                needed: metadata.threshold as usize,
+                got: self.0.len(),
+            });
+        }
+        let shares: Vec<Share> = self.0.iter().map(|s| s.share.clone()).collect();
+        Polynomial::reconstruct(&shares)
+    }
+
+    /// The common metadata, or the first inconsistency
+    fn validate(&self) -> Result<ShareMetadata, Error> {
+        let first = self
+            .0
+            .first()
+            .ok_or(Error::InsufficientShares { needed: 1, got: 0 })?
+            .metadata;
+        if self.0.iter().any(|s| s.metadata.field_id != first.field_id) {
+            return Err(Error::FieldMismatch);
+        }
+        if let Some(index) = self.0.iter().position(|s| s.metadata != first) {
+            return Err(Error::GroupMismatch { index });
+        }
+        if first.field_id != BINARY_FIELD_ID {
+            return Err(Error::UnsupportedField(first.field_id.to_string()));
+        }
+        Ok(first)
+    }
+}
+
+/// Collects shares parsed one by one, checking that they have the same field, split id and
+/// threshold and distinct nonzero x-coordinates
+impl TryFrom<Vec<FramedShare>> for Shares {
+    type Error = Error;
+
+    fn try_from(shares: Vec<FramedShare>) -> Result<Self, Error> {
+        let shares = Shares(shares);
+        shares.validate()?;
+        for (i, s) in shares.0.iter().enumerate() {
+            if s.share.x.is_zero() {
+                return Err(Error::ZeroX);
+            }
+            if shares.0[..i].iter().any(|t| t.share.x == s.share.x) {
+                return Err(Error::DuplicateX);
+            }
+        }
+        Ok(shares)
+    }
+}
+
+/// Summary of a set of shares, see `Shares::report`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareHealth {
+    /// Threshold of the first share, 0 without shares
+    pub threshold: u64,
+    /// Number of shares
+    pub present: usize,
+    /// Number of distinct x-coordinates
+    pub distinct_x: usize,
+    /// Field id of the first share
+    pub field_id: Option<u16>,
+    /// Split id of the first share
+    pub split_id: Option<[u8; SPLIT_ID_BYTES]>,
+    /// Expiry of the first share
+    pub expiry: Option<u64>,
+    /// Whether all shares have the same metadata, over `FieldElement`
+    pub consistent: bool,
+    /// Whether two shares have the same x-coordinate
+    pub duplicate_x: bool,
+    /// Whether every MAC verifies, `None` without a key
+    pub macs_valid: Option<bool>,
+    /// Indices of the shares whose MAC does not verify
+    pub bad_macs: Vec<usize>,
+    /// Whether the consistent shares with valid MACs have threshold distinct x-coordinates
+    pub threshold_reachable: bool,
+}
+
+/// HMAC-SHA256 truncated to `MAC_BYTES`
+fn mac(key: &[u8; MAC_KEY_BYTES], bytes: &[u8]) -> [u8; MAC_BYTES] {
+    let mut hmac = Hmac::<Sha256>::new_varkey(key).expect("HMAC takes keys of any length");
+    hmac.update(bytes);
+    let mut tag = [0u8; MAC_BYTES];
+    tag.copy_from_slice(&hmac.finalize().into_bytes()[..MAC_BYTES]);
+    tag
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        bytes.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    bytes.push(v as u8);
+}
+
+/// Reads from the front of the input, failing instead of panicking when it runs out
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < n {
+            return Err(Error::InvalidEncoding);
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// A LEB128 varint in its shortest form
+    fn varint(&mut self) -> Result<u64, Error> {
+        let mut v = 0u64;
+        for i in 0..10 {
+            let b = self.byte()?;
+            if i == 9 && b > 1 {
+                return Err(Error::InvalidEncoding);
+            }
+            v |= u64::from(b & 0x7f) << (7 * i);
+            if b & 0x80 == 0 {
+                if b == 0 && i > 0 {
+                    return Err(Error::InvalidEncoding);
+                }
+                return Ok(v);
+            }
+        }
+        Err(Error::InvalidEncoding)
+    }
+
+    fn element(&mut self) -> Result<FieldElement, Error> {
+        let mut bytes = [0u8; FIELD_ELEMENT_BYTES];
+        bytes.copy_from_slice(self.take(FIELD_ELEMENT_BYTES)?);
+        FieldElement::from_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    const KEY: [u8; MAC_KEY_BYTES] = [9; MAC_KEY_BYTES];
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::from_seed([5; 32])
+    }
+
+    fn assert_same(a: &FramedShare, b: &FramedShare) {
+        assert_eq!(a.metadata, b.metadata);
+        assert_eq!((a.share.x, a.share.y), (b.share.x, b.share.y));
+        assert_eq!(a.mac, b.mac);
+    }
+
+    #[test]
+    fn share_round_trips() {
+        let shares = Shares::split(FieldElement::new(42), 2, 3, Some(&KEY), &mut rng()).unwrap();
+        let wide = FramedShare::new(
+            Share {
+                x: FieldElement::from_bytes([0xab; 16]).unwrap(),
+                y: FieldElement::new(7),
+            },
+            shares.shares()[0].metadata,
+            None,
+        );
+        for framed in shares.shares().iter().chain(Some(&wide)) {
+            let bytes = framed.to_binary();
+            let read = Share::from_binary(&bytes).unwrap();
+            assert_same(framed, &read);
+            assert_eq!(read.to_binary(), bytes);
+        }
+        assert!(Share::from_binary(&shares.shares()[0].to_binary())
+            .unwrap()
+            .verify_mac(&KEY));
+        assert!(!wide.verify_mac(&KEY));
+    }
+
+    #[test]
+    fn container_round_trips() {
+        let shares = Shares::split(FieldElement::new(42), 2, 3, None, &mut rng()).unwrap();
+        let read = Shares::from_binary(&shares.to_binary()).unwrap();
+        assert_eq!(read.shares().len(), 3);
+        for (a, b) in shares.shares().iter().zip(read.shares()) {
+            assert_same(a, b);
+        }
+        assert_eq!(read.reconstruct(None).unwrap(), FieldElement::new(42));
+    }
+
+    #[test]
+    fn rejects_truncated_and_garbage_input() {
+        let shares = Shares::split(FieldElement::new(42), 2, 3, Some(&KEY), &mut rng()).unwrap();
+        let share = shares.shares()[0].to_binary();
+        for n in 0..share.len() {
+            assert!(Share::from_binary(&share[..n]).is_err());
+        }
+        let container = shares.to_binary();
+        for n in 0..container.len() {
+            assert!(Shares::from_binary(&container[..n]).is_err());
+        }
+        let mut trailing = share.clone();
+        trailing.push(0);
+        assert!(Share::from_binary(&trailing).is_err());
+        let mut future = share.clone();
+        future[2] = BINARY_VERSION + 1;
+        assert_eq!(
+            Share::from_binary(&future).unwrap_err(),
+            Error::UnsupportedVersion {
+                version: u16::from(BINARY_VERSION) + 1
+            }
+        );
+        let mut rng = rng();
+        for len in 0..200 {
+            let mut garbage = vec![0u8; len];
+            rng.fill_bytes(&mut garbage);
+            assert!(Share::from_binary(&garbage).is_err());
+            if len < 2 {
+                continue;
+            }
+            garbage[..2].copy_from_slice(&CONTAINER_MAGIC);
+            let _ = Shares::from_binary(&garbage);
+        }
+    }
+
+    #[test]
+    fn reconstruct_rejects_mixed_fields() {
+        let shares = Shares::split(FieldElement::new(42), 2, 2, None, &mut rng()).unwrap();
+        let mut bytes: Vec<Vec<u8>> = shares.shares().iter().map(|s| s.to_binary()).collect();
+        // field id header of the second share
+        bytes[1][4..6].copy_from_slice(&2u16.to_le_bytes());
+        let mut mixed = Shares::default();
+        for b in &bytes {
+            mixed.push(Share::from_binary(b).unwrap());
+        }
+        assert_eq!(mixed.reconstruct(None), Err(Error::FieldMismatch));
+    }
+
+    #[test]
+    fn threshold_reachable_with_enough_participants() {
+        let shares = Shares::split(FieldElement::new(42), 3, 5, None, &mut rng()).unwrap();
+        let online: Vec<FieldElement> =
+            [1, 3, 5, 9].iter().map(|x| FieldElement::new(*x)).collect();
+        assert!(shares.verify_threshold_reachable(&online));
+        assert!(!shares.verify_threshold_reachable(&online[..2]));
+        assert!(!shares.verify_threshold_reachable(&[FieldElement::new(1); 3]));
+        assert!(!Shares::default().verify_threshold_reachable(&online));
+    }
+
+    #[test]
+    fn reconstruct_enforces_expiry() {
+        let now = 1_700_000_000;
+        let expired =
+            Shares::split_with_expiry(FieldElement::new(42), 2, 3, now - 1, Some(&KEY), &mut rng())
+                .unwrap();
+        assert_eq!(expired.reconstruct_at(Some(&KEY), now), Err(Error::Expired));
+        assert_eq!(expired.reconstruct(Some(&KEY)), Err(Error::Expired));
+        let valid = Shares::split_with_expiry(
+            FieldElement::new(42),
+            2,
+            3,
+            now + 60,
+            Some(&KEY),
+            &mut rng(),
+        )
+        .unwrap();
+        assert_eq!(
+            valid.reconstruct_at(Some(&KEY), now).unwrap(),
+            FieldElement::new(42)
+        );
+        let read = Shares::from_binary(&valid.to_binary()).unwrap();
+        assert_eq!(read.shares()[0].metadata.expiry, Some(now + 60));
+        // extending the expiry breaks the MAC
+        let mut extended = Shares::default();
+        for share in expired.shares() {
+            let mut share = share.clone();
+            share.metadata.expiry = Some(now + 60);
+            extended.push(share);
+        }
+        assert_eq!(
+            extended.reconstruct_at(Some(&KEY), now),
+            Err(Error::CorruptShare { index: 0 })
+        );
+    }
+
+    #[test]
+    fn removed_participant_cannot_combine_with_refreshed_shares() {
+        let mut rng = rng();
+        let secret = FieldElement::new(42);
+        let shares = Shares::split(secret, 2, 3, Some(&KEY), &mut rng).unwrap();
+        let removed = FieldElement::new(2);
+        let refreshed = shares
+            .remove_participant(&removed, Some(&KEY), &mut rng)
+            .unwrap();
+        assert_eq!(
+            refreshed.participants(),
+            vec![FieldElement::new(1), FieldElement::new(3)]
+        );
+        assert_eq!(refreshed.reconstruct(Some(&KEY)).unwrap(), secret);
+
+        let old = shares.shares()[1].clone();
+        let mut mixed = Shares::default();
+        mixed.push(refreshed.shares()[0].clone());
+        mixed.push(old.clone());
+        assert_eq!(
+            mixed.reconstruct(None),
+            Err(Error::GroupMismatch { index: 1 })
+        );
+        // even with forged metadata the old share interpolates to garbage
+        let forged = FramedShare::new(old.share, refreshed.shares()[0].metadata, None);
+        let mut mixed = Shares::default();
+        mixed.push(FramedShare::new(
+            refreshed.shares()[0].share.clone(),
+            forged.metadata,
+            None,
+        ));
+        mixed.push(forged);
+        assert_ne!(mixed.reconstruct(None).unwrap(), secret);
+
+        assert_eq!(
+            refreshed
+                .remove_participant(&FieldElement::new(1), None, &mut rng)
+                .unwrap_err(),
+            Error::InsufficientShares { needed: 2, got: 1 }
+        );
+        // removing a participant twice is a mistake, not another refresh
+        assert_eq!(
+            refreshed
+                .remove_participant(&removed, Some(&KEY), &mut rng)
+                .unwrap_err(),
+            Error::UnknownX
+        );
+    }
+
+    #[test]
+    fn report_summarizes_healthy_and_broken_sets() {
+        let shares = Shares::split(FieldElement::new(42), 2, 3, Some(&KEY), &mut rng()).unwrap();
+        let metadata = shares.shares()[0].metadata;
+        assert_eq!(
+            shares.report(Some(&KEY)),
+            ShareHealth {
+                threshold: 2,
+                present: 3,
+                distinct_x: 3,
+                field_id: Some(BINARY_FIELD_ID),
+                split_id: Some(metadata.split_id),
+                expiry: None,
+                consistent: true,
+                duplicate_x: false,
+                macs_valid: Some(true),
+                bad_macs: Vec::new(),
+                threshold_reachable: true,
+            }
+        );
+        assert_eq!(shares.report(None).macs_valid, None);
+
+        let mut broken = Shares::default();
+        broken.push(shares.shares()[0].clone());
+        broken.push(shares.shares()[0].clone());
+        let mut tampered = shares.shares()[1].clone();
+        tampered.share.y += FieldElement::new(1);
+        broken.push(tampered);
+        let report = broken.report(Some(&KEY));
+        assert_eq!(report.present, 3);
+        assert_eq!(report.distinct_x, 2);
+        assert!(report.duplicate_x);
+        assert!(report.consistent);
+        assert_eq!(report.macs_valid, Some(false));
+        assert_eq!(report.bad_macs, vec![2]);
+        assert!(!report.threshold_reachable);
+    }
+
+    #[test]
+    fn try_from_checks_each_invariant() {
+        let shares = Shares::split(FieldElement::new(42), 2, 3, None, &mut rng()).unwrap();
+        let valid = shares.shares().to_vec();
+        let collected = Shares::try_from(valid.clone()).unwrap();
+        assert_eq!(collected.reconstruct(None).unwrap(), FieldElement::new(42));
+
+        let with = |i: usize, change: &dyn Fn(&mut FramedShare)| {
+            let mut shares = valid.clone();
+            change(&mut shares[i]);
+            Shares::try_from(shares).unwrap_err()
+        };
+        assert_eq!(with(1, &|s| s.metadata.field_id = 2), Error::FieldMismatch);
+        assert_eq!(
+            with(2, &|s| s.metadata.split_id = [0; SPLIT_ID_BYTES]),
+            Error::GroupMismatch { index: 2 }
+        );
+        assert_eq!(
+            with(1, &|s| s.metadata.threshold = 3),
+            Error::GroupMismatch { index: 1 }
+        );
+        assert_eq!(
+            with(2, &|s| s.share.x = FieldElement::new(1)),
+            Error::DuplicateX
+        );
+        assert_eq!(with(0, &|s| s.share.x = FieldElement::zero()), Error::ZeroX);
+        assert_eq!(
+            Shares::try_from(Vec::new()).unwrap_err(),
+            Error::InsufficientShares { needed: 1, got: 0 }
+        );
+    }
+}
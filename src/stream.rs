@@ -0,0 +1,184 @@
+//! Streaming sharing of large files, chunk by chunk, without holding them in memory.
+//!
+//! Each share stream starts with its x-coordinate and the threshold, followed by one y-value per
+//! chunk of `CHUNK_BYTES` secret bytes. The 16th byte of each chunk element holds the number of
+//! bytes it carries, and the stream ends with a chunk shorter than `CHUNK_BYTES`, so truncated
+//! streams are detected.
+
+use crate::encoding::CHUNK_BYTES;
+use crate::error::Error;
+use crate::field::{FieldElement, FIELD_ELEMENT_BYTES};
+use crate::polynomial::Polynomial;
+use crate::share::Share;
+use rand_core::{CryptoRng, RngCore};
+use std::io::{self, Read, Write};
+use zeroize::Zeroizing;
+
+/// Length of the header x || threshold of each share stream
+const HEADER_BYTES: usize = FIELD_ELEMENT_BYTES + 8;
+
+fn invalid_data(e: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Splits everything written to it into n share streams, t of which reconstruct it
+/// `finish` has to be called to end the streams, unfinished streams do not combine
+pub struct SplitWriter<W: Write, R: RngCore + CryptoRng> {
+    writers: Vec<W>,
+    t: u64,
+    rng: R,
+    buffer: Zeroizing<Vec<u8>>,
+}
+
+impl<W: Write, R: RngCore + CryptoRng> SplitWriter<W, R> {
+    /// Write the header of share i + 1 to the i-th writer
+    pub fn new(mut writers: Vec<W>, t: u64, rng: R) -> io::Result<Self> {
+        if t as usize > writers.len() {
+            return Err(invalid_data(Error::InvalidThreshold));
+        }
+        for (i, w) in writers.iter_mut().enumerate() {
+            let mut header = [0u8; HEADER_BYTES];
+            header[..FIELD_ELEMENT_BYTES]
+                .copy_from_slice(&FieldElement::new(i as u64 + 1).to_bytes());
+            header[FIELD_ELEMENT_BYTES..].copy_from_slice(&t.to_le_bytes());
+            w.write_all(&header)?;
+        }
+        Ok(SplitWriter {
+            writers,
+            t,
+            rng,
+            buffer: Zeroizing::new(Vec::with_capacity(CHUNK_BYTES)),
+        })
+    }
+
+    /// Share the remaining bytes and end the streams, returning the writers
+    pub fn finish(mut self) -> io::Result<Vec<W>> {
+        let tail = Zeroizing::new(self.buffer.to_vec());
+        self.write_chunk(&tail)?;
+        for w in self.writers.iter_mut() {
+            w.flush()?;
+        }
+        Ok(self.writers)
+    }
+
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<()> {
+        let mut bytes = Zeroizing::new([0u8; FIELD_ELEMENT_BYTES]);
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        bytes[CHUNK_BYTES] = chunk.len() as u8;
+        let s = FieldElement::from_bytes(*bytes).expect("chunk is below modulus");
+        let polynomial = Polynomial::new(self.t, s, &mut self.rng).map_err(invalid_data)?;
+        for (i, w) in self.writers.iter_mut().enumerate() {
+            let y = polynomial.evaluate(&FieldElement::new(i as u64 + 1));
+            w.write_all(&y.to_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write, R: RngCore + CryptoRng> Write for SplitWriter<W, R> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        let full = self.buffer.len() / CHUNK_BYTES * CHUNK_BYTES;
+        let chunks = Zeroizing::new(self.buffer[..full].to_vec());
+        for chunk in chunks.chunks(CHUNK_BYTES) {
+            self.write_chunk(chunk)?;
+        }
+        self.buffer.drain(..full);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        for w in self.writers.iter_mut() {
+            w.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the secret back from at least t share streams written by `SplitWriter`
+pub struct CombineReader<R: Read> {
+    readers: Vec<R>,
+    xs: Vec<FieldElement>,
+    buffer: Zeroizing<Vec<u8>>,
+    position: usize,
+    done: bool,
+}
+
+impl<R: Read> CombineReader<R> {
+    /// Read the headers, all streams have to be of the same sharing
+    pub fn new(mut readers: Vec<R>) -> io::Result<Self> {
+        let mut xs = Vec::with_capacity(readers.len());
+        let mut threshold = None;
+        for r in readers.iter_mut() {
+            let mut header = [0u8; HEADER_BYTES];
+            r.read_exact(&mut header)?;
+            let mut x = [0u8; FIELD_ELEMENT_BYTES];
+            x.copy_from_slice(&header[..FIELD_ELEMENT_BYTES]);
+            xs.push(FieldElement::from_bytes(x).map_err(invalid_data)?);
+            let mut t = [0u8; 8];
+            t.copy_from_slice(&header[FIELD_ELEMENT_BYTES..]);
+            let t = u64::from_le_bytes(t);
+            if threshold.is_some_and(|threshold| threshold != t) {
+                return Err(invalid_data(Error::GroupMismatch {
+                    index: xs.len() - 1,
+                }));
+            }
+            threshold = Some(t);
+        }
+        let t = threshold.unwrap_or(1) as usize;
+        if readers.len() < t {
+            return Err(invalid_data(Error::InsufficientShares {
+                needed: t,
+                got: readers.len(),
+            }));
+        }
+        Ok(CombineReader {
+            readers,
+            xs,
+            buffer: Zeroizing::new(Vec::with_capacity(CHUNK_BYTES)),
+            position: 0,
+            done: false,
+        })
+    }
+
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let mut shares = Vec::with_capacity(self.readers.len());
+        for (r, x) in self.readers.iter_mut().zip(&self.xs) {
+            let mut y = [0u8; FIELD_ELEMENT_BYTES];
+            r.read_exact(&mut y).map_err(|e| match e.kind() {
+                io::ErrorKind::UnexpectedEof => invalid_data(Error::InvalidEncoding),
+                _ => e,
+            })?;
+            shares.push(Share {
+                x: *x,
+                y: FieldElement::from_bytes(y).map_err(invalid_data)?,
+            });
+        }
+        let s = Polynomial::reconstruct(&shares).map_err(invalid_data)?;
+        let bytes = Zeroizing::new(s.to_bytes());
+        let len = bytes[CHUNK_BYTES] as usize;
+        if len > CHUNK_BYTES || bytes[len..CHUNK_BYTES].iter().any(|b| *b != 0) {
+            return Err(invalid_data(Error::InvalidEncoding));
+        }
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&bytes[..len]);
+        self.position = 0;
+        self.done = len < CHUNK_BYTES;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for CombineReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.buffer.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.read_chunk()?;
+        }
+        let n = buf.len().min(self.buffer.len() - self.position);
+        buf[..n].copy_from_slice(&self.buffer[self.position..self.position + n]);
+        self.position += n;
+        Ok(n)
+    }
+}
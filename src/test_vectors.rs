@@ -0,0 +1,225 @@
+//! Known-answer tests pinning the dealing and the share encoding, so other implementations can
+//! check that they interoperate with this crate.
+//!
+//! Each vector deals `secret` with `Dealer::from_seed(threshold, secret, seed)` and issues the
+//! shares at x = 1, .., n, given as the hex of `Share::to_bytes`. Any `threshold` of them
+//! reconstruct the secret. With the `proptest` feature, `strategies` generates random
+//! thresholds, secrets and share subsets for property tests.
+
+use crate::error::Error;
+use crate::field::FieldElement;
+use crate::polynomial::{Dealer, Polynomial};
+use crate::share::Share;
+use alloc::{string::String, vec::Vec};
+
+/// A known answer: a seeded dealing and the shares it issues
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownAnswer {
+    pub seed: [u8; 32],
+    /// Hex of `FieldElement::to_bytes`
+    pub secret: &'static str,
+    pub threshold: u64,
+    /// Hex of `Share::to_bytes`, at x = 1, .., n
+    pub shares: &'static [&'static str],
+}
+
+/// The published known answers
+pub const KNOWN_ANSWERS: &[KnownAnswer] = &[
+    KnownAnswer {
+        seed: [0; 32],
+        secret: "00000000000000000000000000000000",
+        threshold: 2,
+        shares: &[
+            "01000000000000000000000000000000c94b6292a6d25792212c279883485cb7",
+            "020000000000000000000000000000003198c4244da5af2443584e300791b86e",
+            "0300000000000000000000000000000099e426b7f37707b7648475c88ad91426",
+        ],
+    },
+    KnownAnswer {
+        seed: [1; 32],
+        secret: "2a000000000000000000000000000000",
+        threshold: 3,
+        shares: &[
+            "0100000000000000000000000000000082ee67db3fa405b49ce1aefe0ce7c9a0",
+            "0200000000000000000000000000000083a7bab606a6d5e20d9fb29fb820cabc",
+            "030000000000000000000000000000002d2bf8915405708c53380be302ad0054",
+            "04000000000000000000000000000000e178206d29c2d4b06dadb8c8eb8b6d66",
+            "050000000000000000000000000000009f90334885dc03505cfeba5073bd10f4",
+        ],
+    },
+    KnownAnswer {
+        seed: [0xff; 32],
+        secret: "ffffffffffffffff0000000000000000",
+        threshold: 5,
+        shares: &[
+            "0100000000000000000000000000000025e15e4fb7c80094f03d21f9ade1fae1",
+            "02000000000000000000000000000000eda3871f715fc51b9519155517a9ba51",
+            "03000000000000000000000000000000d33a4b3253c86a8a60d7a530b06a5f81",
+            "04000000000000000000000000000000811bc6da96c13196657a9a9c31b4d9c4",
+            "05000000000000000000000000000000f23c60fd88c37eb857c4b69d998cea91",
+            "06000000000000000000000000000000d316cd0f8a00da2d8b35bb2c2b742380",
+            "07000000000000000000000000000000c1a20b190e65eff5f40c65366e64e648",
+            "080000000000000000000000000000000b5b66b19c978ed32a486e9b2fd065c7",
+        ],
+    },
+];
+
+impl KnownAnswer {
+    /// The secret
+    pub fn secret(&self) -> Result<FieldElement, Error> {
+        FieldElement::from_bytes(decode_hex(self.secret)?)
+    }
+
+    /// The shares, decoded
+    pub fn shares(&self) -> Result<Vec<Share>, Error> {
+        self.shares
+            .iter()
+            .map(|hex| Share::from_bytes(decode_hex(hex)?))
+            .collect()
+    }
+
+    /// Checks that this crate deals exactly the published shares and that the first threshold
+    /// of them reconstruct the secret
+    pub fn check(&self) -> Result<bool, Error> {
+        let secret = self.secret()?;
+        let shares = self.shares()?;
+        let mut dealer = Dealer::from_seed(self.threshold, secret, self.seed)?;
+        for (x, share) in (1..).zip(&shares) {
+            if dealer.issue(x)?.to_bytes() != share.to_bytes() {
+                return Ok(false);
+            }
+        }
+        let needed = self.threshold as usize;
+        if shares.len() < needed {
+            return Err(Error::InsufficientShares {
+                needed,
+                got: shares.len(),
+            });
+        }
+        Ok(Polynomial::reconstruct(&shares[..needed])? == secret)
+    }
+}
+
+/// The hex of the shares at x = 1, .., n that `Dealer::from_seed(threshold, secret, seed)`
+/// issues, the format of `KnownAnswer::shares`, for generating new vectors
+pub fn generate(
+    seed: [u8; 32],
+    secret: FieldElement,
+    threshold: u64,
+    n: u64,
+) -> Result<Vec<String>, Error> {
+    let mut dealer = Dealer::from_seed(threshold, secret, seed)?;
+    (1..=n)
+        .map(|x| Ok(encode_hex(&dealer.issue(x)?.to_bytes())))
+        .collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    bytes
+        .iter()
+        .flat_map(|b| [DIGITS[(b >> 4) as usize], DIGITS[(b & 0xf) as usize]])
+        .map(char::from)
+        .collect()
+}
+
+/// Decode lowercase or uppercase hex of exactly N bytes
+fn decode_hex<const N: usize>(hex: &str) -> Result<[u8; N], Error> {
+    let digits = hex.as_bytes();
+    if digits.len() != 2 * N {
+        return Err(Error::InvalidEncoding);
+    }
+    let mut bytes = [0u8; N];
+    for (byte, pair) in bytes.iter_mut().zip(digits.chunks(2)) {
+        let digit = |d: u8| (d as char).to_digit(16).ok_or(Error::InvalidEncoding);
+        *byte = (digit(pair[0])? << 4 | digit(pair[1])?) as u8;
+    }
+    Ok(bytes)
+}
+
+/// proptest strategies for random sharings
+#[cfg(feature = "proptest")]
+pub mod strategies {
+    use crate::field::FieldElement;
+    use alloc::vec::Vec;
+    use proptest::prelude::*;
+    use proptest::sample::subsequence;
+
+    /// Field elements hashed from random bytes
+    pub fn field_element() -> impl Strategy<Value = FieldElement> {
+        any::<[u8; 32]>().prop_map(|bytes| FieldElement::hash_bytes(&bytes))
+    }
+
+    /// (t, n) with 2 <= t <= n <= max_n
+    pub fn threshold(max_n: u64) -> impl Strategy<Value = (u64, u64)> {
+        (2..=max_n.max(2)).prop_flat_map(|n| (2..=n, Just(n)))
+    }
+
+    /// (t, n, indices) for a sorted subset of at least t of the share indices 0..n
+    pub fn threshold_and_subset(max_n: u64) -> impl Strategy<Value = (u64, u64, Vec<usize>)> {
+        threshold(max_n).prop_flat_map(|(t, n)| {
+            let indices: Vec<usize> = (0..n as usize).collect();
+            (
+                Just(t),
+                Just(n),
+                subsequence(indices, t as usize..=n as usize),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_vectors_match_published() {
+        for kat in KNOWN_ANSWERS {
+            let generated = generate(
+                kat.seed,
+                kat.secret().unwrap(),
+                kat.threshold,
+                kat.shares.len() as u64,
+            )
+            .unwrap();
+            assert_eq!(generated, kat.shares);
+            assert_eq!(encode_hex(&kat.secret().unwrap().to_bytes()), kat.secret);
+        }
+    }
+
+    #[test]
+    fn known_answers_check() {
+        for kat in KNOWN_ANSWERS {
+            assert_eq!(kat.check(), Ok(true));
+        }
+        let drifted = KnownAnswer {
+            seed: [2; 32],
+            ..KNOWN_ANSWERS[0]
+        };
+        assert_eq!(drifted.check(), Ok(false));
+    }
+
+    #[cfg(feature = "proptest")]
+    mod properties {
+        use super::strategies::{field_element, threshold_and_subset};
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn generated_subsets_reconstruct(
+                seed: [u8; 32],
+                secret in field_element(),
+                (t, n, indices) in threshold_and_subset(8),
+            ) {
+                let shares: Vec<Share> = generate(seed, secret, t, n)
+                    .unwrap()
+                    .iter()
+                    .map(|hex| Share::from_bytes(decode_hex(hex).unwrap()).unwrap())
+                    .collect();
+                let subset: Vec<Share> = indices.iter().map(|&i| shares[i].clone()).collect();
+                prop_assert_eq!(Polynomial::reconstruct(&subset).unwrap(), secret);
+            }
+        }
+    }
+}
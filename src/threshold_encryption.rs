@@ -0,0 +1,241 @@
+//! Threshold ElGamal decryption: messages are encrypted to the public key g^s of a shared
+//! secret s, and t holders of shares of s decrypt together without reconstructing s.
+//!
+//! Encryption is hashed ElGamal in ristretto255, the commitment group of `vss`: c1 = g^r and the message is
+//! XORed with a SHA-256 keystream of Y^r and authenticated with a tag. Each holder publishes a
+//! decryption share c1^{s_i} with a Chaum-Pedersen proof that it used the same s_i as in its
+//! verification share g^{s_i}, and t valid decryption shares interpolate Y^r in the exponent.
+
+use crate::curves::Ed25519Scalar;
+use crate::error::Error;
+use crate::polynomial::lagrange_coefficients;
+use crate::share::Share;
+use crate::vss::GroupElement;
+use ff::Field;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// A message encrypted to the shared public key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ciphertext {
+    pub c1: GroupElement,
+    pub c2: Vec<u8>,
+    pub tag: [u8; 32],
+}
+
+/// Proof that log_g(a) = log_h(b), as the commitments and the response
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChaumPedersenProof {
+    pub a1: GroupElement,
+    pub a2: GroupElement,
+    pub z: Ed25519Scalar,
+}
+
+/// Holder `index`'s share c1^{s_i} of the decryption, with its proof of correctness
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecryptionShare {
+    pub index: usize,
+    pub d: GroupElement,
+    pub proof: ChaumPedersenProof,
+}
+
+/// Encrypt the message to the public key g^s
+pub fn encrypt<R: RngCore + CryptoRng>(
+    public_key: &GroupElement,
+    message: &[u8],
+    rng: &mut R,
+) -> Ciphertext {
+    let r = Ed25519Scalar::random(&mut *rng);
+    let c1 = GroupElement::generator().pow(&r);
+    let shared = public_key.pow(&r);
+    let c2: Vec<u8> = message
+        .iter()
+        .zip(keystream(&shared, message.len()))
+        .map(|(m, k)| m ^ k)
+        .collect();
+    let tag = tag(&shared, &c1, &c2);
+    Ciphertext { c1, c2, tag }
+}
+
+/// Holder `index`'s decryption share computed with its share s_i at x = index
+/// Fails if the share is not at x = index, as the proof would not verify against the
+/// verification share of `index`
+pub fn decryption_share<R: RngCore + CryptoRng>(
+    ciphertext: &Ciphertext,
+    index: usize,
+    share: &Share<Ed25519Scalar>,
+    rng: &mut R,
+) -> Result<DecryptionShare, Error> {
+    if index == 0 || share.x != Ed25519Scalar::from(index as u64) {
+        return Err(Error::UnknownParty { index });
+    }
+    let g = GroupElement::generator();
+    let verification_share = g.pow(&share.y);
+    let d = ciphertext.c1.pow(&share.y);
+    let w = Ed25519Scalar::random(&mut *rng);
+    let a1 = g.pow(&w);
+    let a2 = ciphertext.c1.pow(&w);
+    let e = proof_challenge(&verification_share, &ciphertext.c1, &d, &a1, &a2);
+    Ok(DecryptionShare {
+        index,
+        d,
+        proof: ChaumPedersenProof {
+            a1,
+            a2,
+            z: w + e * share.y,
+        },
+    })
+}
+
+/// Checks the proof of the decryption share against the holder's verification share g^{s_i},
+/// e.g. from `Commitments::share_commitment`
+pub fn verify_decryption_share(
+    decryption_share: &DecryptionShare,
+    ciphertext: &Ciphertext,
+    verification_share: &GroupElement,
+) -> bool {
+    let proof = &decryption_share.proof;
+    let e = proof_challenge(
+        verification_share,
+        &ciphertext.c1,
+        &decryption_share.d,
+        &proof.a1,
+        &proof.a2,
+    );
+    GroupElement::generator().pow(&proof.z) == proof.a1.clone() * verification_share.pow(&e)
+        && ciphertext.c1.pow(&proof.z) == proof.a2.clone() * decryption_share.d.pow(&e)
+}
+
+/// Decrypt from at least t decryption shares, which should be verified first
+/// Fails if the shares do not interpolate the key the message was encrypted under
+pub fn combine(
+    ciphertext: &Ciphertext,
+    decryption_shares: &[DecryptionShare],
+) -> Result<Vec<u8>, Error> {
+    let xs: Vec<Ed25519Scalar> = decryption_shares
+        .iter()
+        .map(|s| Ed25519Scalar::from(s.index as u64))
+        .collect();
+    if xs.iter().any(|x| x.is_zero()) {
+        return Err(Error::ZeroX);
+    }
+    let shared = lagrange_coefficients(&xs, &Ed25519Scalar::zero())?
+        .iter()
+        .zip(decryption_shares)
+        .map(|(l, s)| s.d.pow(l))
+        .reduce(|acc, e| acc * e)
+        .ok_or(Error::InsufficientShares { needed: 1, got: 0 })?;
+    let expected = tag(&shared, &ciphertext.c1, &ciphertext.c2);
+    if !bool::from(expected.ct_eq(&ciphertext.tag)) {
+        return Err(Error::DecryptionFailed);
+    }
+    Ok(ciphertext
+        .c2
+        .iter()
+        .zip(keystream(&shared, ciphertext.c2.len()))
+        .map(|(c, k)| c ^ k)
+        .collect())
+}
+
+/// SHA-256(domain || Y^r || counter) blocks
+fn keystream(shared: &GroupElement, len: usize) -> Vec<u8> {
+    let bytes = shared.to_bytes();
+    let mut stream = Vec::with_capacity(len + 32);
+    for counter in 0u64.. {
+        if stream.len() >= len {
+            break;
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(b"polynomials elgamal keystream");
+        hasher.update(bytes);
+        hasher.update(counter.to_le_bytes());
+        stream.extend_from_slice(&hasher.finalize());
+    }
+    stream.truncate(len);
+    stream
+}
+
+/// SHA-256(domain || Y^r || c1 || c2)
+fn tag(shared: &GroupElement, c1: &GroupElement, c2: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"polynomials elgamal tag");
+    for e in [shared, c1] {
+        let bytes = e.to_bytes();
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+    hasher.update(c2);
+    hasher.finalize().into()
+}
+
+/// e = H(g^{s_i} || c1 || d || a1 || a2)
+fn proof_challenge(
+    verification_share: &GroupElement,
+    c1: &GroupElement,
+    d: &GroupElement,
+    a1: &GroupElement,
+    a2: &GroupElement,
+) -> Ed25519Scalar {
+    let mut input = b"polynomials chaum pedersen".to_vec();
+    for e in [verification_share, c1, d, a1, a2] {
+        let bytes = e.to_bytes();
+        input.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        input.extend_from_slice(&bytes);
+    }
+    Ed25519Scalar::hash_bytes(&input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vss::deal;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn threshold_holders_decrypt() {
+        let mut rng = ChaCha20Rng::from_seed([12; 32]);
+        let (shares, commitments) = deal(Ed25519Scalar::random(&mut rng), 2, 3, &mut rng).unwrap();
+        let ciphertext = encrypt(commitments.secret(), b"attack at dawn", &mut rng);
+        let decryption_shares: Vec<DecryptionShare> = shares[1..]
+            .iter()
+            .zip(2..)
+            .map(|(share, index)| decryption_share(&ciphertext, index, share, &mut rng).unwrap())
+            .collect();
+        for d in &decryption_shares {
+            let verification_share =
+                commitments.share_commitment(&Ed25519Scalar::from(d.index as u64));
+            assert!(verify_decryption_share(d, &ciphertext, &verification_share));
+        }
+        assert_eq!(
+            combine(&ciphertext, &decryption_shares).unwrap(),
+            b"attack at dawn"
+        );
+        let mut wrong = decryption_shares.clone();
+        wrong[0].index = 1;
+        assert_eq!(
+            combine(&ciphertext, &wrong).unwrap_err(),
+            Error::DecryptionFailed
+        );
+    }
+
+    #[test]
+    fn mislabelled_shares_are_rejected() {
+        let mut rng = ChaCha20Rng::from_seed([15; 32]);
+        let (shares, commitments) = deal(Ed25519Scalar::random(&mut rng), 2, 3, &mut rng).unwrap();
+        let ciphertext = encrypt(commitments.secret(), b"message", &mut rng);
+        for index in [0, 1, 3] {
+            assert_eq!(
+                decryption_share(&ciphertext, index, &shares[1], &mut rng).unwrap_err(),
+                Error::UnknownParty { index }
+            );
+        }
+        assert!(decryption_share(&ciphertext, 2, &shares[1], &mut rng).is_ok());
+    }
+}
@@ -0,0 +1,443 @@
+//! Feldman verifiable secret sharing: the dealer publishes commitments g^{a_i} to the
+//! coefficients of the polynomial, against which every holder checks their share.
+//!
+//! The commitments live in ristretto255, the prime-order group of order
+//! l = 2^252 + 27742317777372353535851937790883648493 built on Curve25519, so the shares are
+//! over its scalar field `Ed25519Scalar` and discrete logarithms take about 2^126 steps. The group
+//! is written multiplicatively, g^a is the point a * G.
+//!
+//! Feldman commitments reveal g^s, so anyone able to take discrete logarithms learns the secret.
+//! Pedersen commitments g^{a_i} h^{b_i} blind every coefficient with a second random polynomial
+//! and hide the secret unconditionally. The second generator h is hashed into the group, so its
+//! logarithm to the base g is unknown.
+
+use crate::curves::Ed25519Scalar;
+use crate::error::Error;
+use crate::polynomial::Polynomial;
+use crate::share::Share;
+use core::convert::TryFrom;
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
+use ff::Field;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha512};
+use std::ops::Mul;
+use std::sync::OnceLock;
+
+/// Length of the compressed encoding of a group element
+pub const GROUP_ELEMENT_BYTES: usize = 32;
+
+/// Hashes the label with SHA-512 and maps it into the group with the Elligator map of
+/// ristretto255, so nobody knows the logarithm of the result
+fn hash_to_group(label: &[u8]) -> RistrettoPoint {
+    let wide: [u8; 64] = Sha512::digest(label).into();
+    RistrettoPoint::from_uniform_bytes(&wide)
+}
+
+/// An element of ristretto255
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupElement(RistrettoPoint);
+
+impl GroupElement {
+    /// The generator g, the ristretto255 base point
+    pub fn generator() -> Self {
+        GroupElement(RISTRETTO_BASEPOINT_POINT)
+    }
+
+    /// The second generator h, whose logarithm to the base g is unknown
+    pub fn blinding_generator() -> Self {
+        static H: OnceLock<RistrettoPoint> = OnceLock::new();
+        GroupElement(*H.get_or_init(|| hash_to_group(b"polynomials pedersen h")))
+    }
+
+    /// The identity element
+    pub fn identity() -> Self {
+        GroupElement(RistrettoPoint::identity())
+    }
+
+    /// Computes self^e
+    pub fn pow(&self, e: &Ed25519Scalar) -> Self {
+        GroupElement(self.0 * Scalar::from(*e))
+    }
+
+    /// The canonical 32-byte compressed encoding
+    pub fn to_bytes(&self) -> [u8; GROUP_ELEMENT_BYTES] {
+        self.0.compress().to_bytes()
+    }
+
+    /// Decodes a compressed element, rejecting non-canonical encodings
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        CompressedRistretto::from_slice(bytes)
+            .ok()
+            .and_then(|c| c.decompress())
+            .map(GroupElement)
+            .ok_or(Error::InvalidEncoding)
+    }
+}
+
+impl From<RistrettoPoint> for GroupElement {
+    fn from(point: RistrettoPoint) -> Self {
+        GroupElement(point)
+    }
+}
+
+impl From<GroupElement> for RistrettoPoint {
+    fn from(element: GroupElement) -> Self {
+        element.0
+    }
+}
+
+/// The group operation, point addition written multiplicatively
+impl Mul for GroupElement {
+    type Output = GroupElement;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, other: GroupElement) -> GroupElement {
+        GroupElement(self.0 + other.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for GroupElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_bytes().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for GroupElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = <[u8; GROUP_ELEMENT_BYTES]>::deserialize(deserializer)?;
+        GroupElement::from_bytes(&bytes).map_err(de::Error::custom)
+    }
+}
+
+/// Commitments g^{a_0}, .., g^{a_{t-1}} to the coefficients of the polynomial, lowest first
+/// There is at least one, deserializing an empty list fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "Vec<GroupElement>"))]
+pub struct Commitments(Vec<GroupElement>);
+
+impl Commitments {
+    /// Commit to the coefficients of the polynomial
+    pub fn new(polynomial: &Polynomial<Ed25519Scalar>) -> Self {
+        let g = GroupElement::generator();
+        // coefficients are stored highest first
+        let commitments = polynomial
+            .coefficients
+            .iter()
+            .rev()
+            .map(|a| g.pow(a))
+            .collect();
+        Commitments(commitments)
+    }
+
+    /// The commitment g^s to the secret
+    pub fn secret(&self) -> &GroupElement {
+        &self.0[0]
+    }
+
+    /// Checks g^y == prod_i C_i^{x^i}
+    pub fn verify(&self, share: &Share<Ed25519Scalar>) -> bool {
+        GroupElement::generator().pow(&share.y) == evaluate_in_exponent(&self.0, share.x)
+    }
+
+    /// The commitment g^{f(x)} to the share at x, e.g. a signer's verification share
+    pub fn share_commitment(&self, x: &Ed25519Scalar) -> GroupElement {
+        evaluate_in_exponent(&self.0, *x)
+    }
+
+    /// The number of coefficients committed to, which is the threshold of the shares
+    pub fn threshold(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The commitments to the sum of the two polynomials, failing if their thresholds differ
+    pub fn combine(&self, other: &Commitments) -> Result<Commitments, Error> {
+        if self.threshold() != other.threshold() {
+            return Err(Error::InvalidThreshold);
+        }
+        Ok(Commitments(
+            self.0
+                .iter()
+                .zip(&other.0)
+                .map(|(a, b)| a.clone() * b.clone())
+                .collect(),
+        ))
+    }
+}
+
+/// The commitments to the coefficients, lowest first, failing for an empty list
+impl TryFrom<Vec<GroupElement>> for Commitments {
+    type Error = Error;
+
+    fn try_from(commitments: Vec<GroupElement>) -> Result<Self, Error> {
+        if commitments.is_empty() {
+            return Err(Error::InsufficientShares { needed: 1, got: 0 });
+        }
+        Ok(Commitments(commitments))
+    }
+}
+
+/// Computes prod_i C_i^{x^i}, the commitment to the polynomial evaluated at x
+fn evaluate_in_exponent(commitments: &[GroupElement], x: Ed25519Scalar) -> GroupElement {
+    let mut result = GroupElement::identity();
+    let mut power = Ed25519Scalar::one();
+    for c in commitments {
+        result = result * c.pow(&power);
+        power *= x;
+    }
+    result
+}
+
+/// Split s into n shares of which t reconstruct it, together with the commitments to publish
+pub fn deal<R: RngCore + CryptoRng>(
+    s: Ed25519Scalar,
+    t: u64,
+    n: u64,
+    rng: &mut R,
+) -> Result<(Vec<Share<Ed25519Scalar>>, Commitments), Error> {
+    let polynomial = Polynomial::new(t, s, rng)?;
+    let commitments = Commitments::new(&polynomial);
+    Ok((polynomial.share(n), commitments))
+}
+
+/// Checks the share against the dealer's commitments before accepting it
+pub fn verify_share(share: &Share<Ed25519Scalar>, commitments: &Commitments) -> bool {
+    commitments.verify(share)
+}
+
+/// Verify every share against the commitments of a threshold t sharing, then interpolate the
+/// secret. Fails with the positions of all failing shares before interpolating any of them
+pub fn reconstruct_vss(
+    shares: &[Share<Ed25519Scalar>],
+    commitments: &Commitments,
+    threshold: usize,
+) -> Result<Ed25519Scalar, Error> {
+    if commitments.threshold() != threshold {
+        return Err(Error::InvalidThreshold);
+    }
+    let indices: Vec<usize> = shares
+        .iter()
+        .enumerate()
+        .filter(|(_, share)| !commitments.verify(share))
+        .map(|(i, _)| i)
+        .collect();
+    if !indices.is_empty() {
+        return Err(Error::InvalidShares { indices });
+    }
+    if shares.len() < threshold {
+        return Err(Error::InsufficientShares {
+            needed: threshold,
+            got: shares.len(),
+        });
+    }
+    Polynomial::reconstruct(shares)
+}
+
+/// A share together with its value r(x) of the blinding polynomial
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PedersenShare {
+    pub share: Share<Ed25519Scalar>,
+    pub blinding: Ed25519Scalar,
+}
+
+/// Commitments g^{a_i} h^{b_i} to the coefficients of the polynomial and of the blinding
+/// polynomial, lowest first
+/// There is at least one, deserializing an empty list fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "Vec<GroupElement>"))]
+pub struct PedersenCommitments(Vec<GroupElement>);
+
+impl PedersenCommitments {
+    /// Checks g^y h^r == prod_i C_i^{x^i}
+    pub fn verify(&self, share: &PedersenShare) -> bool {
+        let expected = evaluate_in_exponent(&self.0, share.share.x);
+        let committed = GroupElement::generator().pow(&share.share.y)
+            * GroupElement::blinding_generator().pow(&share.blinding);
+        committed == expected
+    }
+}
+
+/// The blinded commitments to the coefficients, lowest first, failing for an empty list
+impl TryFrom<Vec<GroupElement>> for PedersenCommitments {
+    type Error = Error;
+
+    fn try_from(commitments: Vec<GroupElement>) -> Result<Self, Error> {
+        if commitments.is_empty() {
+            return Err(Error::InsufficientShares { needed: 1, got: 0 });
+        }
+        Ok(PedersenCommitments(commitments))
+    }
+}
+
+/// Dealer of Pedersen verifiable secret sharing
+pub struct PedersenDealer;
+
+impl PedersenDealer {
+    /// Split s into n shares of which t reconstruct it, together with the blinded commitments
+    /// to publish
+    pub fn deal<R: RngCore + CryptoRng>(
+        s: Ed25519Scalar,
+        t: u64,
+        n: u64,
+        rng: &mut R,
+    ) -> Result<(Vec<PedersenShare>, PedersenCommitments), Error> {
+        let polynomial = Polynomial::new(t, s, rng)?;
+        let blinding_secret = Ed25519Scalar::random(&mut *rng);
+        let blinding = Polynomial::new(t, blinding_secret, rng)?;
+        let g = GroupElement::generator();
+        let h = GroupElement::blinding_generator();
+        // coefficients are stored highest first
+        let commitments = polynomial
+            .coefficients
+            .iter()
+            .zip(&blinding.coefficients)
+            .rev()
+            .map(|(a, b)| g.pow(a) * h.pow(b))
+            .collect();
+        let shares = polynomial
+            .share(n)
+            .into_iter()
+            .zip(blinding.share(n))
+            .map(|(share, r)| PedersenShare {
+                share,
+                blinding: r.y,
+            })
+            .collect();
+        Ok((shares, PedersenCommitments(commitments)))
+    }
+}
+
+/// Checks the share and its blinding value against the dealer's Pedersen commitments
+pub fn verify_pedersen_share(share: &PedersenShare, commitments: &PedersenCommitments) -> bool {
+    commitments.verify(share)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::from_seed([8; 32])
+    }
+
+    #[test]
+    fn shares_verify_and_tampered_shares_do_not() {
+        let s = Ed25519Scalar::from(42);
+        let (shares, commitments) = deal(s, 3, 5, &mut rng()).unwrap();
+        assert_eq!(commitments.threshold(), 3);
+        assert_eq!(*commitments.secret(), GroupElement::generator().pow(&s));
+        assert!(shares.iter().all(|share| verify_share(share, &commitments)));
+        let tampered = Share {
+            x: shares[0].x,
+            y: shares[0].y + Ed25519Scalar::one(),
+        };
+        assert!(!verify_share(&tampered, &commitments));
+        assert_eq!(Polynomial::reconstruct(&shares[2..]).unwrap(), s);
+    }
+
+    #[test]
+    fn reconstruct_vss_catches_tampered_shares() {
+        let s = Ed25519Scalar::from(42);
+        let (mut shares, commitments) = deal(s, 3, 5, &mut rng()).unwrap();
+        assert_eq!(reconstruct_vss(&shares[1..4], &commitments, 3).unwrap(), s);
+        shares[2].y += Ed25519Scalar::one();
+        assert_eq!(
+            reconstruct_vss(&shares[1..4], &commitments, 3).unwrap_err(),
+            Error::InvalidShares { indices: vec![1] }
+        );
+        assert_eq!(
+            reconstruct_vss(&shares[..2], &commitments, 3).unwrap_err(),
+            Error::InsufficientShares { needed: 3, got: 2 }
+        );
+        assert_eq!(
+            reconstruct_vss(&shares[3..], &commitments, 2).unwrap_err(),
+            Error::InvalidThreshold
+        );
+    }
+
+    #[test]
+    fn pedersen_shares_verify() {
+        let (shares, commitments) =
+            PedersenDealer::deal(Ed25519Scalar::from(7), 2, 3, &mut rng()).unwrap();
+        assert!(shares
+            .iter()
+            .all(|s| verify_pedersen_share(s, &commitments)));
+        let mut tampered = shares[1].clone();
+        tampered.blinding += Ed25519Scalar::one();
+        assert!(!verify_pedersen_share(&tampered, &commitments));
+    }
+
+    #[test]
+    fn group_elements_round_trip_and_reject_garbage() {
+        let h = GroupElement::blinding_generator();
+        assert_ne!(h, GroupElement::generator());
+        assert_eq!(GroupElement::from_bytes(&h.to_bytes()).unwrap(), h);
+        assert!(GroupElement::from_bytes(&[0xff; GROUP_ELEMENT_BYTES]).is_err());
+        assert!(GroupElement::from_bytes(&h.to_bytes()[1..]).is_err());
+    }
+
+    #[test]
+    fn empty_commitments_are_rejected() {
+        assert_eq!(
+            Commitments::try_from(Vec::new()).unwrap_err(),
+            Error::InsufficientShares { needed: 1, got: 0 }
+        );
+        let (_, commitments) = deal(Ed25519Scalar::from(1), 2, 2, &mut rng()).unwrap();
+        assert_eq!(
+            Commitments::try_from(commitments.0.clone()).unwrap(),
+            commitments
+        );
+        assert_eq!(
+            PedersenCommitments::try_from(Vec::new()).unwrap_err(),
+            Error::InsufficientShares { needed: 1, got: 0 }
+        );
+        let (_, commitments) =
+            PedersenDealer::deal(Ed25519Scalar::from(1), 2, 2, &mut rng()).unwrap();
+        assert_eq!(
+            PedersenCommitments::try_from(commitments.0.clone()).unwrap(),
+            commitments
+        );
+    }
+
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    #[test]
+    fn empty_commitments_do_not_deserialize() {
+        assert!(serde_json::from_str::<Commitments>("[]").is_err());
+        assert!(serde_json::from_str::<PedersenCommitments>("[]").is_err());
+        let (_, commitments) =
+            PedersenDealer::deal(Ed25519Scalar::from(1), 2, 2, &mut rng()).unwrap();
+        let json = serde_json::to_string(&commitments).unwrap();
+        assert_eq!(
+            serde_json::from_str::<PedersenCommitments>(&json).unwrap(),
+            commitments
+        );
+    }
+
+    #[test]
+    fn combined_commitments_verify_summed_shares() {
+        let mut rng = rng();
+        let (a, ca) = deal(Ed25519Scalar::from(1), 3, 4, &mut rng).unwrap();
+        let (b, cb) = deal(Ed25519Scalar::from(2), 3, 4, &mut rng).unwrap();
+        let combined = ca.combine(&cb).unwrap();
+        for (a, b) in a.iter().zip(&b) {
+            assert!(verify_share(&(a + b).unwrap(), &combined));
+        }
+        assert_eq!(
+            *combined.secret(),
+            GroupElement::generator().pow(&Ed25519Scalar::from(3))
+        );
+        let (_, other) = deal(Ed25519Scalar::from(2), 2, 4, &mut rng).unwrap();
+        assert_eq!(ca.combine(&other).unwrap_err(), Error::InvalidThreshold);
+    }
+}
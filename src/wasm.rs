@@ -0,0 +1,82 @@
+//! wasm-bindgen wrappers for running questionnairs and sharing in the browser.
+//!
+//! Questionnairs and shares are exchanged as JSON strings and secrets as byte arrays. Errors are
+//! thrown as their message.
+
+use crate::encoding::Secret;
+use crate::error::Error;
+use crate::field::{FieldElement, FIELD_ELEMENT_BYTES};
+use crate::questionnair::{answer, Questionnair};
+use crate::share::Share;
+use core::convert::TryFrom;
+use rand_core::OsRng;
+use wasm_bindgen::prelude::*;
+
+fn js_error<E: core::fmt::Display>(e: E) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
+
+/// Create a questionnair as JSON from a 16-byte secret and JSON arrays of the questions and
+/// answers, any `threshold` of which recover the secret
+#[wasm_bindgen]
+pub fn create_questionnaire(
+    secret: &[u8],
+    questions: &str,
+    answers: &str,
+    threshold: u32,
+) -> Result<String, JsValue> {
+    let secret = <[u8; FIELD_ELEMENT_BYTES]>::try_from(secret)
+        .map_err(|_| js_error("The secret has to be 16 bytes"))?;
+    let secret = FieldElement::from_bytes(secret).map_err(js_error)?;
+    let questions: Vec<String> = serde_json::from_str(questions).map_err(js_error)?;
+    let answers: Vec<String> = serde_json::from_str(answers).map_err(js_error)?;
+    let questionnair = Questionnair::with_threshold(
+        secret,
+        threshold as u64,
+        questions.iter().map(String::as_str).collect(),
+        answers.iter().map(String::as_str).collect(),
+        &mut OsRng,
+    )
+    .map_err(js_error)?;
+    serde_json::to_string(&questionnair).map_err(js_error)
+}
+
+/// Recover the 16-byte secret of a JSON questionnair from a JSON array of answers in question
+/// order, where empty answers are skipped
+#[wasm_bindgen]
+pub fn answer_questionnaire(questionnaire: &str, answers: &str) -> Result<Vec<u8>, JsValue> {
+    let questionnair = Questionnair::from_json(questionnaire).map_err(js_error)?;
+    let answers: Vec<String> = serde_json::from_str(answers).map_err(js_error)?;
+    let secret =
+        answer(&questionnair, answers.iter().map(String::as_str).collect()).map_err(js_error)?;
+    Ok(secret.to_bytes().to_vec())
+}
+
+/// Split the secret into a JSON array with the shares of each of the n participants, t of
+/// which reconstruct it
+#[wasm_bindgen]
+pub fn split_secret(secret: &[u8], t: u32, n: u32) -> Result<String, JsValue> {
+    let shares = Secret::from_bytes(secret)
+        .split(t as u64, n as u64, &mut OsRng)
+        .map_err(js_error)?;
+    let participants: Vec<Vec<&Share>> = (0..n as usize)
+        .map(|i| shares.iter().map(|element| &element[i]).collect())
+        .collect();
+    serde_json::to_string(&participants).map_err(js_error)
+}
+
+/// Reconstruct the secret from a JSON array with the shares of at least t participants, as
+/// produced by `split_secret`
+#[wasm_bindgen]
+pub fn combine_shares(shares: &str) -> Result<Vec<u8>, JsValue> {
+    let participants: Vec<Vec<Share>> = serde_json::from_str(shares).map_err(js_error)?;
+    let elements = participants.first().map_or(0, Vec::len);
+    if participants.iter().any(|p| p.len() != elements) {
+        return Err(js_error(Error::LengthMismatch));
+    }
+    let shares: Vec<Vec<Share>> = (0..elements)
+        .map(|j| participants.iter().map(|p| p[j].clone()).collect())
+        .collect();
+    let secret = Secret::reconstruct(&shares).map_err(js_error)?;
+    Ok(secret.to_bytes())
+}
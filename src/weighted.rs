@@ -0,0 +1,116 @@
+use crate::error::Error;
+use crate::field::FieldElement;
+use crate::polynomial::Polynomial;
+use crate::share::Share;
+use alloc::vec::Vec;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The shares of a participant, one per unit of weight
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WeightedShare {
+    pub shares: Vec<Share>,
+}
+
+impl WeightedShare {
+    /// The weight of the participant
+    pub fn weight(&self) -> u32 {
+        self.shares.len() as u32
+    }
+}
+
+/// Split s among participants with the given weights, so that any participants with a total
+/// weight of t reconstruct it. A participant of weight w gets w shares at consecutive x-points
+/// Fails if the total weight is below t, or does not fit a u64
+pub fn split_weighted<R: RngCore + CryptoRng>(
+    s: FieldElement,
+    t: u64,
+    weights: &[u64],
+    rng: &mut R,
+) -> Result<Vec<Vec<Share>>, Error> {
+    let n = weights
+        .iter()
+        .try_fold(0u64, |n, w| n.checked_add(*w))
+        .ok_or(Error::InvalidThreshold)?;
+    if n < t {
+        return Err(Error::InvalidThreshold);
+    }
+    let mut shares = Polynomial::new(t, s, rng)?.share(n).into_iter();
+    Ok(weights
+        .iter()
+        .map(|w| shares.by_ref().take(*w as usize).collect())
+        .collect())
+}
+
+/// Reconstruct s from the shares of participants with a total weight of at least t
+pub fn reconstruct_weighted(participants: &[Vec<Share>], t: u64) -> Result<FieldElement, Error> {
+    let shares: Vec<Share> = participants.iter().flatten().cloned().collect();
+    if (shares.len() as u64) < t {
+        return Err(Error::InsufficientShares {
+            needed: t as usize,
+            got: shares.len(),
+        });
+    }
+    Polynomial::reconstruct(&shares)
+}
+
+/// Deal each participant a `WeightedShare` of its weight, so that participants with a total
+/// weight of t reconstruct s
+pub fn deal_weighted<R: RngCore + CryptoRng>(
+    s: FieldElement,
+    t: u64,
+    weights: &[u32],
+    rng: &mut R,
+) -> Result<Vec<WeightedShare>, Error> {
+    let weights: Vec<u64> = weights.iter().map(|w| u64::from(*w)).collect();
+    Ok(split_weighted(s, t, &weights, rng)?
+        .into_iter()
+        .map(|shares| WeightedShare { shares })
+        .collect())
+}
+
+/// Reconstruct s from the `WeightedShare`s of participants with a total weight of at least t
+pub fn combine_weighted(participants: &[WeightedShare], t: u64) -> Result<FieldElement, Error> {
+    let shares: Vec<Vec<Share>> = participants.iter().map(|p| p.shares.clone()).collect();
+    reconstruct_weighted(&shares, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn weights_three_and_two_meet_threshold_five() {
+        let secret = FieldElement::new(55);
+        let mut rng = ChaCha20Rng::from_seed([6; 32]);
+        let participants = split_weighted(secret, 5, &[3, 2, 1, 1], &mut rng).unwrap();
+        assert_eq!(participants[0].len(), 3);
+        assert_eq!(reconstruct_weighted(&participants[..2], 5).unwrap(), secret);
+        assert_eq!(
+            reconstruct_weighted(&participants[1..], 5).unwrap_err(),
+            Error::InsufficientShares { needed: 5, got: 4 }
+        );
+    }
+
+    #[test]
+    fn split_rejects_weights_below_threshold() {
+        let mut rng = ChaCha20Rng::from_seed([6; 32]);
+        let secret = FieldElement::new(55);
+        assert_eq!(
+            split_weighted(secret, 5, &[2, 1, 1], &mut rng).unwrap_err(),
+            Error::InvalidThreshold
+        );
+        assert_eq!(
+            split_weighted(secret, 5, &[u64::MAX, 2], &mut rng).unwrap_err(),
+            Error::InvalidThreshold
+        );
+        assert_eq!(
+            deal_weighted(secret, 5, &[2, 2], &mut rng).unwrap_err(),
+            Error::InvalidThreshold
+        );
+    }
+}